@@ -0,0 +1,126 @@
+//! Hit tests and movement against a field full of ducks. Each runs the
+//! game's own system in a headless `App` with nothing else added, so
+//! what's measured is the system itself and not the rest of a frame.
+//!
+//! The ducks are laid out over the field by their number, the same every
+//! run, and kept flying so every iteration has as many to look through.
+
+use basic_project::{
+    duck_state::{DuckStateCommands, Dying, Flying, Heading},
+    events::{DuckDamaged, DuckHit, PlayerId, ShotFired},
+    handle_shoot_duck, move_ducks,
+    settings::Settings,
+    spatial::SpatialIndex,
+    time_scale::GameTimeScale,
+    view::View,
+    weapon::{EquippedWeapon, WeaponModifiers},
+    wind::Wind,
+    Duck, GameInputEvent, PreviousPosition, SimulationDriver,
+};
+use bevy::{prelude::*, time::TimePlugin};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const DUCK_COUNTS: [usize; 3] = [10, 100, 1000];
+// Inside where the ducks turn around, above the grass
+const FIELD: Rect = Rect {
+    min: Vec2::new(-110.0, -40.0),
+    max: Vec2::new(110.0, 110.0),
+};
+
+fn scatter(i: usize, step: f32) -> f32 {
+    (i as f32 * step).fract()
+}
+
+fn field_position(i: usize) -> Vec2 {
+    FIELD.min + Vec2::new(scatter(i, 0.618_034), scatter(i, 0.414_214)) * FIELD.size()
+}
+
+fn app_with_ducks(count: usize) -> App {
+    let mut app = App::new();
+    app.add_plugins((TaskPoolPlugin::default(), TimePlugin))
+        .init_resource::<GameTimeScale>()
+        .init_resource::<Wind>()
+        .init_resource::<View>()
+        .init_resource::<SpatialIndex>()
+        .init_resource::<Assets<TextureAtlasLayout>>()
+        .init_resource::<EquippedWeapon>()
+        .init_resource::<WeaponModifiers>()
+        .init_resource::<Settings>()
+        .init_resource::<SimulationDriver>()
+        .add_event::<GameInputEvent>()
+        .add_event::<ShotFired>()
+        .add_event::<DuckHit>()
+        .add_event::<DuckDamaged>();
+    for i in 0..count {
+        let position = field_position(i);
+        let heading = if i % 2 == 0 {
+            Heading::Left
+        } else {
+            Heading::Right
+        };
+        app.world_mut().spawn((
+            Duck::default(),
+            Flying { heading },
+            Transform::from_translation(position.extend(0.0)),
+            PreviousPosition(position),
+        ));
+    }
+    app
+}
+
+fn movement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("move_ducks");
+    for count in DUCK_COUNTS {
+        let mut app = app_with_ducks(count);
+        app.add_systems(Update, move_ducks);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.iter(|| app.update());
+        });
+    }
+    group.finish();
+}
+
+#[derive(Resource, Default)]
+struct ShotNumber(usize);
+
+// One shot a step, somewhere new on the field each time so some hit and
+// some miss, with the weapon ready again for it
+fn aim_shot(
+    mut shot_number: ResMut<ShotNumber>,
+    mut equipped: ResMut<EquippedWeapon>,
+    mut input_writer: EventWriter<GameInputEvent>,
+) {
+    shot_number.0 += 1;
+    *equipped = EquippedWeapon::default();
+    input_writer.write(GameInputEvent::Shoot(
+        PlayerId(0),
+        field_position(shot_number.0 * 7),
+        None,
+    ));
+}
+
+// Hit ducks go straight back in the air, the next shot has as many to
+// choose from
+fn revive_ducks(mut commands: Commands, duck_query: Query<Entity, With<Dying>>) {
+    for entity in &duck_query {
+        commands.entity(entity).set_duck_state(Flying::default());
+    }
+}
+
+fn hit_detection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("handle_shoot_duck");
+    for count in DUCK_COUNTS {
+        let mut app = app_with_ducks(count);
+        app.init_resource::<ShotNumber>().add_systems(
+            Update,
+            (move_ducks, aim_shot, handle_shoot_duck, revive_ducks).chain(),
+        );
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.iter(|| app.update());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, movement, hit_detection);
+criterion_main!(benches);
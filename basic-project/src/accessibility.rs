@@ -0,0 +1,361 @@
+//! Accessibility page off the title menu, plus the aim assist it turns on.
+//! High visibility ducks have their own module, `high_visibility`, the rain
+//! and fog that can be turned off here `weather`, pixel-perfect hits
+//! `alpha_mask`, the run timer `speedrun`, and the first game's prompts
+//! that can be brought back from here `tutorial`. The language, the skin,
+//! the season and the display, its monitor and sizes, see `display`, are
+//! picked here too, there's no other settings page. The asset packs in use
+//! are listed under the options.
+//! The page sits on top of the menu instead of replacing it, so the buttons
+//! other plugins add to the menu are still there when it's closed.
+
+use bevy::{prelude::*, window::Monitor};
+
+use crate::{
+    achievements::UnlockedAchievements,
+    asset_pack::AssetPacks,
+    locale::Locale,
+    menu::{spawn_menu_button, spawn_menu_page, MenuScreen},
+    settings::{AccessibilitySettings, Settings, MAX_WINDOW_SCALE, MIN_WINDOW_SCALE},
+    tutorial::Tutorial,
+    GameState,
+};
+
+// Picked from in turn with the radius button
+const ASSIST_RADII: [f32; 4] = [8.0, 16.0, 24.0, 32.0];
+// Picked from in turn with the UI scale button
+const UI_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+#[derive(Component)]
+struct AccessibilityButton;
+
+#[derive(Component)]
+struct AccessibilityPage;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum AccessibilityOption {
+    AimAssist,
+    AssistRadius,
+    HighVisibility,
+    Weather,
+    HitDetection,
+    SpeedrunTimer,
+    ResetTutorial,
+    Language,
+    Skin,
+    Season,
+    Display,
+    Monitor,
+    WindowScale,
+    UiScale,
+    Back,
+}
+
+impl AccessibilityOption {
+    fn label(&self, settings: &Settings, tutorial: &Tutorial, locale: &Locale) -> String {
+        let accessibility = &settings.accessibility;
+        let on_off = |on| locale.get(if on { "option.on" } else { "option.off" });
+        match self {
+            AccessibilityOption::AimAssist => locale.format(
+                "accessibility.aim_assist",
+                &[("value", &on_off(accessibility.aim_assist))],
+            ),
+            AccessibilityOption::AssistRadius => locale.format(
+                "accessibility.assist_radius",
+                &[("value", &accessibility.assist_radius)],
+            ),
+            AccessibilityOption::HighVisibility => locale.format(
+                "accessibility.high_visibility",
+                &[("value", &on_off(accessibility.high_visibility))],
+            ),
+            AccessibilityOption::Weather => locale.format(
+                "accessibility.weather",
+                &[("value", &on_off(accessibility.weather))],
+            ),
+            AccessibilityOption::HitDetection => locale.format(
+                "accessibility.hit_detection",
+                &[(
+                    "value",
+                    &locale.get(&format!("hit_detection.{}", accessibility.hit_detection)),
+                )],
+            ),
+            AccessibilityOption::SpeedrunTimer => locale.format(
+                "accessibility.speedrun_timer",
+                &[("value", &on_off(settings.speedrun.enabled))],
+            ),
+            AccessibilityOption::ResetTutorial if tutorial.seen => {
+                locale.get("accessibility.show_tutorial").to_string()
+            }
+            AccessibilityOption::ResetTutorial => {
+                locale.get("accessibility.tutorial_next_game").to_string()
+            }
+            AccessibilityOption::Language => locale.format(
+                "accessibility.language",
+                &[(
+                    "value",
+                    &locale.get(&format!("language.{}", settings.ui.language)),
+                )],
+            ),
+            AccessibilityOption::Skin => locale.format(
+                "accessibility.skin",
+                &[(
+                    "value",
+                    &locale.get(&format!("skin.{}", settings.cosmetic.skin)),
+                )],
+            ),
+            AccessibilityOption::Season => locale.format(
+                "accessibility.season",
+                &[(
+                    "value",
+                    &locale.get(&format!("season.{}", settings.cosmetic.season)),
+                )],
+            ),
+            AccessibilityOption::Display => locale.format(
+                "accessibility.display",
+                &[(
+                    "value",
+                    &locale.get(&format!("display.{}", settings.window.display)),
+                )],
+            ),
+            // Counted from 1 on the page
+            AccessibilityOption::Monitor => locale.format(
+                "accessibility.monitor",
+                &[("value", &(settings.window.monitor + 1))],
+            ),
+            AccessibilityOption::WindowScale => locale.format(
+                "accessibility.window_scale",
+                &[("value", &settings.window.scale)],
+            ),
+            AccessibilityOption::UiScale => {
+                locale.format("accessibility.ui_scale", &[("value", &settings.ui.scale)])
+            }
+            AccessibilityOption::Back => locale.get("menu.back").to_string(),
+        }
+    }
+}
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Menu),
+            add_accessibility_button.after(crate::menu::setup_menu),
+        )
+        .add_systems(
+            Update,
+            (
+                open_accessibility_page,
+                handle_accessibility_options,
+                update_option_labels,
+            )
+                .chain()
+                .run_if(in_state(GameState::Menu)),
+        );
+    }
+}
+
+/// Where a shot lands with the assist on: on the closest of `ducks` within
+/// the radius, or right where it was aimed when none are that close
+pub fn assisted_aim(
+    settings: &AccessibilitySettings,
+    shot: Vec2,
+    ducks: impl IntoIterator<Item = Vec2>,
+) -> Vec2 {
+    if !settings.aim_assist {
+        return shot;
+    }
+    ducks
+        .into_iter()
+        .filter(|duck| duck.distance(shot) <= settings.assist_radius)
+        .min_by(|a, b| {
+            a.distance_squared(shot)
+                .total_cmp(&b.distance_squared(shot))
+        })
+        .unwrap_or(shot)
+}
+
+fn add_accessibility_button(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    menu_query: Query<Entity, With<MenuScreen>>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).with_children(|parent| {
+            spawn_menu_button(
+                parent,
+                locale.get("accessibility.title"),
+                AccessibilityButton,
+            );
+        });
+    }
+}
+
+fn open_accessibility_page(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    tutorial: Res<Tutorial>,
+    locale: Res<Locale>,
+    packs: Option<Res<AssetPacks>>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<AccessibilityButton>)>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    spawn_menu_page(&mut commands, 6.0, AccessibilityPage).with_children(|parent| {
+        parent.spawn((
+            Text::new(locale.get("accessibility.title")),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+            Node {
+                margin: UiRect::bottom(Val::Px(12.0)),
+                ..default()
+            },
+        ));
+        for option in [
+            AccessibilityOption::AimAssist,
+            AccessibilityOption::AssistRadius,
+            AccessibilityOption::HighVisibility,
+            AccessibilityOption::Weather,
+            AccessibilityOption::HitDetection,
+            AccessibilityOption::SpeedrunTimer,
+            AccessibilityOption::ResetTutorial,
+            AccessibilityOption::Language,
+            AccessibilityOption::Skin,
+            AccessibilityOption::Season,
+            AccessibilityOption::Display,
+            AccessibilityOption::Monitor,
+            AccessibilityOption::WindowScale,
+            AccessibilityOption::UiScale,
+            AccessibilityOption::Back,
+        ] {
+            spawn_menu_button(parent, &option.label(&settings, &tutorial, &locale), option);
+        }
+        let names: Vec<_> = packs
+            .iter()
+            .flat_map(|packs| &packs.0)
+            .filter(|pack| pack.error.is_none())
+            .map(|pack| pack.name.as_str())
+            .collect();
+        if !names.is_empty() {
+            parent.spawn((
+                Text::new(locale.format("accessibility.packs", &[("value", &names.join(", "))])),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+            ));
+        }
+    });
+}
+
+fn handle_accessibility_options(
+    mut commands: Commands,
+    mut settings: ResMut<Settings>,
+    mut tutorial: ResMut<Tutorial>,
+    unlocked: Res<UnlockedAchievements>,
+    monitor_query: Query<(), With<Monitor>>,
+    button_query: Query<(&Interaction, &AccessibilityOption), Changed<Interaction>>,
+    page_query: Query<Entity, With<AccessibilityPage>>,
+) {
+    for (interaction, option) in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match option {
+            AccessibilityOption::AimAssist => {
+                let accessibility = &mut settings.accessibility;
+                accessibility.aim_assist = !accessibility.aim_assist;
+            }
+            AccessibilityOption::AssistRadius => {
+                let accessibility = &mut settings.accessibility;
+                // Anything set by hand that isn't one of them starts over
+                let next = ASSIST_RADII
+                    .iter()
+                    .position(|radius| *radius == accessibility.assist_radius)
+                    .map_or(0, |i| (i + 1) % ASSIST_RADII.len());
+                accessibility.assist_radius = ASSIST_RADII[next];
+            }
+            AccessibilityOption::HighVisibility => {
+                let accessibility = &mut settings.accessibility;
+                accessibility.high_visibility = !accessibility.high_visibility;
+            }
+            AccessibilityOption::Weather => {
+                let accessibility = &mut settings.accessibility;
+                accessibility.weather = !accessibility.weather;
+            }
+            AccessibilityOption::HitDetection => {
+                let accessibility = &mut settings.accessibility;
+                accessibility.hit_detection = accessibility.hit_detection.next();
+            }
+            AccessibilityOption::SpeedrunTimer => {
+                settings.speedrun.enabled = !settings.speedrun.enabled
+            }
+            AccessibilityOption::ResetTutorial => tutorial.seen = false,
+            AccessibilityOption::Language => settings.ui.language = settings.ui.language.next(),
+            // Only the ones earned so far come round
+            AccessibilityOption::Skin => {
+                settings.cosmetic.skin = settings.cosmetic.skin.next_unlocked(&unlocked)
+            }
+            // Takes effect the next time the game starts
+            AccessibilityOption::Season => {
+                settings.cosmetic.season = settings.cosmetic.season.next()
+            }
+            AccessibilityOption::Display => {
+                settings.window.display = settings.window.display.next()
+            }
+            // Round the ones plugged in now
+            AccessibilityOption::Monitor => {
+                let monitors = monitor_query.iter().count().max(1);
+                settings.window.monitor = (settings.window.monitor + 1) % monitors;
+            }
+            AccessibilityOption::WindowScale => {
+                let window = &mut settings.window;
+                window.scale = if window.scale >= MAX_WINDOW_SCALE {
+                    MIN_WINDOW_SCALE
+                } else {
+                    window.scale + 1
+                };
+            }
+            // Same as the radius, one set by hand starts over
+            AccessibilityOption::UiScale => {
+                let ui = &mut settings.ui;
+                let next = UI_SCALES
+                    .iter()
+                    .position(|scale| *scale == ui.scale)
+                    .map_or(0, |i| (i + 1) % UI_SCALES.len());
+                ui.scale = UI_SCALES[next];
+            }
+            AccessibilityOption::Back => {
+                for entity in &page_query {
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+// The locale catches up with a new language in `PreUpdate`, the labels
+// change with it the frame after
+fn update_option_labels(
+    settings: Res<Settings>,
+    tutorial: Res<Tutorial>,
+    locale: Res<Locale>,
+    button_query: Query<(&AccessibilityOption, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !settings.is_changed() && !tutorial.is_changed() && !locale.is_changed() {
+        return;
+    }
+    for (option, children) in &button_query {
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = option.label(&settings, &tutorial, &locale);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Achievements earned from the gameplay events. Unlocks are kept in the
+//! profile and a toast pops up the first time each one is earned.
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    demo::Demo,
+    events::{AchievementUnlocked, DuckHit, RoundEnded},
+    locale::Locale,
+    score::SessionStats,
+    GameState,
+};
+
+const TOAST_SECONDS: f32 = 3.0;
+// Accuracy over a whole game needed for Sharpshooter
+const SHARPSHOOTER_ACCURACY: f32 = 0.95;
+// So a single lucky shot doesn't count as a game of good shooting
+const SHARPSHOOTER_MIN_SHOTS: u32 = 20;
+
+pub struct Achievement {
+    /// Stable name used on disk and by platform integrations. In game the
+    /// name and description come from the language tables under
+    /// `achievement.<id>`, these are what platforms are given.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_blood",
+        name: "First Blood",
+        description: "Shoot your first duck",
+    },
+    Achievement {
+        id: "perfect_round",
+        name: "Perfect Round",
+        description: "Finish a round without letting a duck escape",
+    },
+    Achievement {
+        id: "sharpshooter",
+        name: "Sharpshooter",
+        description: "95% accuracy over a game",
+    },
+];
+
+/// Ids of everything earned so far
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnlockedAchievements(pub HashSet<String>);
+
+impl UnlockedAchievements {
+    /// Marks the achievement as earned, sends the event only the first time
+    fn unlock(&mut self, id: &'static str, unlocked_writer: &mut EventWriter<AchievementUnlocked>) {
+        if self.0.insert(id.to_string()) {
+            unlocked_writer.write(AchievementUnlocked { id });
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct AchievementToast(Timer);
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UnlockedAchievements>()
+            .add_systems(
+                Update,
+                (
+                    // The bot's demo games don't count
+                    (unlock_first_blood, unlock_perfect_round)
+                        .run_if(in_state(GameState::InGame).and(not(resource_exists::<Demo>))),
+                    show_achievement_toasts,
+                    tick_achievement_toasts,
+                ),
+            )
+            .add_systems(OnEnter(GameState::GameOver), unlock_sharpshooter);
+    }
+}
+
+fn unlock_first_blood(
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+    mut unlocked_writer: EventWriter<AchievementUnlocked>,
+) {
+    if duck_hit_reader.read().next().is_some() {
+        unlocked.unlock("first_blood", &mut unlocked_writer);
+    }
+}
+
+fn unlock_perfect_round(
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+    mut unlocked_writer: EventWriter<AchievementUnlocked>,
+) {
+    for ended in round_ended_reader.read() {
+        if ended.ducks_hit > 0 && ended.ducks_escaped == 0 {
+            unlocked.unlock("perfect_round", &mut unlocked_writer);
+        }
+    }
+}
+
+fn unlock_sharpshooter(
+    mut unlocked: ResMut<UnlockedAchievements>,
+    stats: Res<SessionStats>,
+    mut unlocked_writer: EventWriter<AchievementUnlocked>,
+) {
+    if stats.shots_fired >= SHARPSHOOTER_MIN_SHOTS && stats.accuracy() >= SHARPSHOOTER_ACCURACY {
+        unlocked.unlock("sharpshooter", &mut unlocked_writer);
+    }
+}
+
+fn show_achievement_toasts(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    toast_query: Query<(), With<AchievementToast>>,
+    mut unlocked_reader: EventReader<AchievementUnlocked>,
+) {
+    let mut shown = toast_query.iter().count();
+    for unlocked in unlocked_reader.read() {
+        let Some(achievement) = ACHIEVEMENTS.iter().find(|a| a.id == unlocked.id) else {
+            continue;
+        };
+        // Stack under the ones still up
+        commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(4.0 + shown as f32 * 28.0),
+                    right: Val::Px(4.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::BLACK),
+                GlobalZIndex(10),
+                AchievementToast(Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Text::new(locale.get(&format!("achievement.{}.name", achievement.id))),
+                    TextFont {
+                        font_size: 10.0,
+                        ..default()
+                    },
+                ));
+                parent.spawn((
+                    Text::new(locale.get(&format!("achievement.{}.description", achievement.id))),
+                    TextFont {
+                        font_size: 6.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.75, 0.75, 0.75)),
+                ));
+            });
+        shown += 1;
+    }
+}
+
+fn tick_achievement_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toast_query: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in &mut toast_query {
+        toast.0.tick(time.delta());
+        if toast.0.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
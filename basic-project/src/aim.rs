@@ -0,0 +1,96 @@
+//! Turns aiming input into reticle movement the way the player set it up in
+//! `Settings`. Sticks and keys go through `AimMotion`, which shapes, speeds
+//! up and smooths them, and raw mouse input moves the cursor from the mouse's
+//! own motion so the OS pointer acceleration doesn't get in the way.
+//!
+//! With the gyro feature a controller's gyro adds fine aim on top of the
+//! stick, it's passed on through `GyroMotion`.
+
+use bevy::{
+    input::{mouse::AccumulatedMouseMotion, InputSystem},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::settings::{AimSettings, Settings};
+
+// Counts as held all the way over past this, sticks rarely reach a full 1
+const FULL_DEFLECTION: f32 = 0.95;
+
+/// How a stick or key aimed reticle is moving, kept between frames for the
+/// acceleration and smoothing
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct AimMotion {
+    velocity: Vec2,
+    // Seconds at full deflection
+    held: f32,
+}
+
+impl AimMotion {
+    /// How far to move for `input` over `delta` seconds. `input` is a stick
+    /// or keys, at most 1 long, and `speed` the pixels per second it moves
+    /// at full deflection before the settings are applied.
+    pub fn step(&mut self, settings: &AimSettings, input: Vec2, speed: f32, delta: f32) -> Vec2 {
+        let deflection = input.length().min(1.0);
+        let shaped = input.normalize_or_zero() * deflection.powf(settings.curve);
+        if deflection >= FULL_DEFLECTION {
+            self.held += delta;
+        } else {
+            self.held = 0.0;
+        }
+        let boost = 1.0 + settings.acceleration * self.held.min(1.0);
+        let target = shaped * speed * settings.sensitivity * boost;
+        self.velocity = if settings.smoothing > 0.0 {
+            self.velocity
+                .lerp(target, (delta / settings.smoothing).min(1.0))
+        } else {
+            target
+        };
+        self.velocity * delta
+    }
+}
+
+/// What the controller's gyro did this frame, stays at rest without the gyro
+/// feature or a controller that has one
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct GyroMotion {
+    /// Pixels to move the stick aimed reticle by
+    pub delta: Vec2,
+    /// The recenter button was pressed, the reticle goes back to the middle
+    pub recenter: bool,
+}
+
+pub struct AimPlugin;
+
+impl Plugin for AimPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GyroMotion>()
+            .add_systems(PreUpdate, move_raw_cursor.after(InputSystem));
+    }
+}
+
+// Everything else reads the cursor from the window, so the window's cursor is
+// what gets moved. Where the OS put the pointer in between is overwritten.
+fn move_raw_cursor(
+    settings: Res<Settings>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut cursor: Local<Option<Vec2>>,
+) {
+    let Ok(mut win) = window_query.single_mut() else {
+        return;
+    };
+    if !settings.mouse.raw_input || !win.focused {
+        *cursor = None;
+        return;
+    }
+    // Picks up from wherever the pointer came into the window
+    let Some(position) = cursor.or(win.cursor_position()) else {
+        return;
+    };
+    // Motion comes in physical pixels
+    let motion = mouse_motion.delta * settings.mouse.sensitivity / win.scale_factor();
+    let position = (position + motion).clamp(Vec2::ZERO, win.size());
+    win.set_cursor_position(Some(position));
+    *cursor = Some(position);
+}
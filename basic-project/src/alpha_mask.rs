@@ -0,0 +1,161 @@
+//! Optional pixel-accurate hit detection. A duck's hitbox is usually a lot
+//! bigger than its art, so in this mode a shot also has to land on a
+//! non-transparent pixel of the frame the duck is currently showing. Picked
+//! with `accessibility.hit_detection`.
+
+use std::{fmt, str::FromStr};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::GameAssets;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HitDetection {
+    /// Anything inside the duck's `Hitbox` counts
+    #[default]
+    Hitbox,
+    /// Only opaque pixels of the current frame count
+    AlphaMask,
+}
+
+impl HitDetection {
+    /// The other one, for the accessibility page
+    pub fn next(self) -> Self {
+        match self {
+            HitDetection::Hitbox => HitDetection::AlphaMask,
+            HitDetection::AlphaMask => HitDetection::Hitbox,
+        }
+    }
+}
+
+impl FromStr for HitDetection {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hitbox" => Ok(HitDetection::Hitbox),
+            "alpha_mask" => Ok(HitDetection::AlphaMask),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for HitDetection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HitDetection::Hitbox => "hitbox",
+            HitDetection::AlphaMask => "alpha_mask",
+        })
+    }
+}
+
+/// Opaque pixels of the skin's duck spritesheet, built on the CPU once the
+/// image has loaded, with the frames it's cut into. Whichever look a duck
+/// is showing, it's tested against its frame of the skin's own sheet.
+#[derive(Resource)]
+pub struct DuckAlphaMask {
+    size: UVec2,
+    opaque: Vec<bool>,
+    frames: Vec<URect>,
+}
+
+impl DuckAlphaMask {
+    pub fn from_image(image: &Image, layout: &TextureAtlasLayout) -> Self {
+        let size = image.size();
+        let mut opaque = Vec::with_capacity((size.x * size.y) as usize);
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let alpha = image.get_color_at(x, y).map_or(0.0, |c| c.alpha());
+                opaque.push(alpha > 0.5);
+            }
+        }
+        Self {
+            size,
+            opaque,
+            frames: layout.textures.clone(),
+        }
+    }
+
+    /// `local` is the shot relative to the sprite centre in world units
+    /// (y up), `index` the atlas index of the frame being shown. A frame
+    /// the mask doesn't know of counts as a hit.
+    pub fn is_opaque(&self, index: usize, local: Vec2, flip_x: bool) -> bool {
+        let Some(&frame) = self.frames.get(index) else {
+            return true;
+        };
+        let frame_size = frame.size().as_vec2();
+        let local = if flip_x {
+            Vec2::new(-local.x, local.y)
+        } else {
+            local
+        };
+        // Image rows go down, world y goes up
+        let pixel = Vec2::new(local.x + frame_size.x / 2.0, frame_size.y / 2.0 - local.y);
+        if pixel.x < 0.0 || pixel.y < 0.0 || pixel.x >= frame_size.x || pixel.y >= frame_size.y {
+            return false;
+        }
+        let pixel = frame.min + pixel.as_uvec2();
+        if pixel.x >= self.size.x || pixel.y >= self.size.y {
+            return false;
+        }
+        self.opaque[(pixel.y * self.size.x + pixel.x) as usize]
+    }
+}
+
+pub fn build_duck_alpha_mask(
+    mut commands: Commands,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut layout_events: EventReader<AssetEvent<TextureAtlasLayout>>,
+    mut built_from: Local<Option<AssetId<Image>>>,
+    images: Res<Assets<Image>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    game_assets: Option<Res<GameAssets>>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    let layout_id = game_assets.skin_duck_layout().id();
+    // The frames come from the JSON, which is saved separately
+    if layout_events
+        .read()
+        .any(|event| matches!(event, AssetEvent::Modified { id } if *id == layout_id))
+    {
+        *built_from = None;
+    }
+    let Some(layout) = layouts.get(layout_id) else {
+        return;
+    };
+    if let Some(image) =
+        duck_image_to_build(&game_assets, &images, &mut built_from, &mut image_events)
+    {
+        commands.insert_resource(DuckAlphaMask::from_image(image, layout));
+        println!("Built duck alpha mask");
+    }
+}
+
+/// The skin's duck sheet if whatever is built from it has to be built
+/// again, `built_from` being what it was built from last. A new skin
+/// brings a different sheet, which can still be loading, and saving one
+/// with `hot_reload` on changes it in place. The looks go by this too.
+pub fn duck_image_to_build<'a>(
+    game_assets: &GameAssets,
+    images: &'a Assets<Image>,
+    built_from: &mut Option<AssetId<Image>>,
+    image_events: &mut EventReader<AssetEvent<Image>>,
+) -> Option<&'a Image> {
+    let id = game_assets.skin_duck_image().id();
+    // Read through all of them either way, so they don't pile up for the
+    // next frame
+    let modified = image_events
+        .read()
+        .filter(|event| matches!(event, AssetEvent::Modified { id: changed } if *changed == id))
+        .count();
+    if modified == 0 && *built_from == Some(id) {
+        return None;
+    }
+    let image = images.get(id)?;
+    *built_from = Some(id);
+    Some(image)
+}
@@ -0,0 +1,133 @@
+//! In endless mode a hit duck now and then drops a shell. It falls down to
+//! the grass and lies there for a bit, shooting it gives the shooter extra
+//! shells on top of a full load.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    events::{AmmoCollected, DuckHit, ShotFired},
+    rng::GameRng,
+    time_scale::GameTimeScale,
+    versus::Reticle,
+    weapon::EquippedWeapon,
+    GameMode, GameState, Simulation,
+};
+
+const DROP_CHANCE: f64 = 0.05;
+const BONUS_SHELLS: u32 = 2;
+// Pixels per second, a lot slower than a duck so there's time to shoot it
+const FALL_SPEED: f32 = 60.0;
+// Resting on top of the grass, well above where ducks take off from
+const REST_Y: f32 = -28.0;
+// Seconds it lies in the grass before it's gone
+const PICKUP_LIFETIME: f32 = 5.0;
+const SHELL_COLOR: Color = Color::srgb(0.85, 0.1, 0.1);
+const BRASS_COLOR: Color = Color::srgb(0.9, 0.75, 0.2);
+
+#[derive(Component, Clone)]
+pub struct AmmoPickup {
+    lifetime: Timer,
+}
+
+pub struct AmmoPickupPlugin;
+
+impl Plugin for AmmoPickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Simulation,
+            (drop_ammo, fall_ammo, collect_ammo).run_if(resource_equals(GameMode::Endless)),
+        );
+    }
+}
+
+fn drop_ammo(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+) {
+    for hit in duck_hit_reader.read() {
+        if !rng.gen_bool(DROP_CHANCE) {
+            continue;
+        }
+        // Red hull on a brass head, like the shells in the HUD
+        commands
+            .spawn((
+                Sprite::from_color(SHELL_COLOR, Vec2::new(4.0, 7.0)),
+                // In front of the grass so it can't hide in it
+                Transform::from_translation(hit.position.extend(2.0)),
+                AmmoPickup {
+                    lifetime: Timer::from_seconds(PICKUP_LIFETIME, TimerMode::Once),
+                },
+                StateScoped(GameState::InGame),
+            ))
+            .with_child((
+                Sprite::from_color(BRASS_COLOR, Vec2::new(4.0, 2.0)),
+                Transform::from_xyz(0.0, -4.5, 0.1),
+            ));
+    }
+}
+
+fn fall_ammo(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut pickup_query: Query<(Entity, &mut Transform, &mut Visibility, &mut AmmoPickup)>,
+) {
+    let delta = time_scale.delta_secs(&time);
+    for (entity, mut transform, mut visibility, mut pickup) in &mut pickup_query {
+        if transform.translation.y > REST_Y {
+            transform.translation.y = (transform.translation.y - FALL_SPEED * delta).max(REST_Y);
+            continue;
+        }
+        // Only counts down once it's landed
+        pickup.lifetime.tick(time_scale.delta(&time));
+        if pickup.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Blink for the last second and a half
+        let elapsed = pickup.lifetime.elapsed_secs();
+        let visible =
+            pickup.lifetime.remaining_secs() > 1.5 || ((elapsed * 8.0) as u32).is_multiple_of(2);
+        visibility.set_if_neq(if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+fn collect_ammo(
+    mut commands: Commands,
+    mut equipped: ResMut<EquippedWeapon>,
+    mut player_weapon_query: Query<(&Reticle, &mut EquippedWeapon)>,
+    pickup_query: Query<(Entity, &Transform), With<AmmoPickup>>,
+    mut shot_fired_reader: EventReader<ShotFired>,
+    mut ammo_collected_writer: EventWriter<AmmoCollected>,
+) {
+    for shot in shot_fired_reader.read() {
+        for (entity, transform) in &pickup_query {
+            let pos = transform.translation.xy();
+            // A bit more than the shell itself, it's tiny
+            let hitbox = Rect::from_center_half_size(pos, Vec2::splat(6.0));
+            if !hitbox.contains(shot.position) {
+                continue;
+            }
+            let weapon = match player_weapon_query
+                .iter_mut()
+                .find(|(reticle, _)| reticle.player == shot.player)
+            {
+                Some((_, weapon)) => weapon.into_inner(),
+                None => &mut *equipped,
+            };
+            weapon.ammo += BONUS_SHELLS;
+            commands.entity(entity).despawn();
+            ammo_collected_writer.write(AmmoCollected {
+                player: shot.player,
+                position: pos,
+                shells: BONUS_SHELLS,
+            });
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Frame animation for anything drawn from a spritesheet. A clip is a run of
+//! atlas indices played at a fixed rate, `SpriteAnimation` plays one on the
+//! sprite it's on and game code only decides which clip that should be.
+//! Ducks step with the simulation so they stay in sync with it, anything
+//! else steps with the frame.
+
+use bevy::prelude::*;
+
+use crate::{time_scale::GameTimeScale, Duck};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClipMode {
+    /// Back to `first` after `last`
+    #[default]
+    Loop,
+    /// Stays on `last` and finishes
+    Once,
+    /// `first` to `last` and back again
+    PingPong,
+}
+
+/// Names a clip so whoever waits on it can tell it apart from the rest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ClipId(pub &'static str);
+
+/// Atlas indices `first..=last` played at `fps` frames a second
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AnimationClip {
+    pub id: ClipId,
+    pub first: usize,
+    pub last: usize,
+    pub fps: f32,
+    pub mode: ClipMode,
+}
+
+impl AnimationClip {
+    pub fn new(id: ClipId, first: usize, last: usize, fps: f32, mode: ClipMode) -> Self {
+        Self {
+            id,
+            first,
+            last,
+            fps,
+            mode,
+        }
+    }
+}
+
+/// A `Once` clip reached its end on this entity
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AnimationFinished(pub Entity, pub ClipId);
+
+/// Plays an `AnimationClip` on the entity's `Sprite`. The default plays
+/// nothing until a clip is given with `play`.
+#[derive(Component, Debug, Clone)]
+pub struct SpriteAnimation {
+    clip: AnimationClip,
+    /// Multiplies the clip's frame rate
+    pub speed: f32,
+    frame: usize,
+    elapsed: f32,
+    backwards: bool,
+    finished: bool,
+    stepped: bool,
+}
+
+impl Default for SpriteAnimation {
+    fn default() -> Self {
+        Self::new(AnimationClip::default())
+    }
+}
+
+impl SpriteAnimation {
+    pub fn new(clip: AnimationClip) -> Self {
+        Self {
+            clip,
+            speed: 1.0,
+            frame: clip.first,
+            elapsed: 0.0,
+            backwards: false,
+            finished: false,
+            stepped: false,
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Switches to `clip` from its first frame, keeps going if it's already
+    /// the one playing
+    pub fn play(&mut self, clip: AnimationClip) {
+        if self.clip != clip {
+            *self = Self::new(clip).with_speed(self.speed);
+        }
+    }
+
+    pub fn clip(&self) -> AnimationClip {
+        self.clip
+    }
+
+    /// Atlas index being shown
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// A `Once` clip has shown its last frame for a whole frame
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The frame moved on the last time animations were played, a looping
+    /// single frame clip still steps
+    pub fn stepped(&self) -> bool {
+        self.stepped
+    }
+
+    /// Returns whether the clip finished during this tick
+    fn tick(&mut self, delta: f32) -> bool {
+        self.stepped = false;
+        if self.finished || self.clip.fps <= 0.0 {
+            return false;
+        }
+        let frame_time = 1.0 / self.clip.fps;
+        self.elapsed += delta * self.speed;
+        while self.elapsed >= frame_time && !self.finished {
+            self.elapsed -= frame_time;
+            self.stepped = true;
+            self.advance();
+        }
+        self.finished
+    }
+
+    fn advance(&mut self) {
+        let AnimationClip { first, last, .. } = self.clip;
+        match self.clip.mode {
+            ClipMode::Loop if self.frame >= last => self.frame = first,
+            ClipMode::Once if self.frame >= last => self.finished = true,
+            ClipMode::Loop | ClipMode::Once => self.frame += 1,
+            ClipMode::PingPong if first == last => {}
+            ClipMode::PingPong if self.backwards => {
+                if self.frame <= first {
+                    self.backwards = false;
+                    self.frame = first + 1;
+                } else {
+                    self.frame -= 1;
+                }
+            }
+            ClipMode::PingPong => {
+                if self.frame >= last {
+                    self.backwards = true;
+                    self.frame = last - 1;
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+    }
+}
+
+pub fn play_sprite_animations(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut animation_query: Query<(Entity, &mut SpriteAnimation, &mut Sprite), With<Duck>>,
+    mut animation_finished_writer: EventWriter<AnimationFinished>,
+) {
+    step_animations(
+        time_scale.delta_secs(&time),
+        animation_query.iter_mut(),
+        &mut animation_finished_writer,
+    );
+}
+
+pub fn play_frame_animations(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut animation_query: Query<(Entity, &mut SpriteAnimation, &mut Sprite), Without<Duck>>,
+    mut animation_finished_writer: EventWriter<AnimationFinished>,
+) {
+    step_animations(
+        time_scale.delta_secs(&time),
+        animation_query.iter_mut(),
+        &mut animation_finished_writer,
+    );
+}
+
+fn step_animations<'a>(
+    delta: f32,
+    animations: impl Iterator<Item = (Entity, Mut<'a, SpriteAnimation>, Mut<'a, Sprite>)>,
+    animation_finished_writer: &mut EventWriter<AnimationFinished>,
+) {
+    for (entity, mut animation, mut sprite) in animations {
+        if animation.tick(delta) {
+            animation_finished_writer.write(AnimationFinished(entity, animation.clip.id));
+        }
+        // Looked at first so a sprite that stays on its frame isn't marked
+        // changed
+        let stale = sprite
+            .texture_atlas
+            .as_ref()
+            .is_some_and(|atlas| atlas.index != animation.frame);
+        if let (true, Some(atlas)) = (stale, &mut sprite.texture_atlas) {
+            atlas.index = animation.frame;
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! Duck animations taken from an Aseprite JSON export instead of the clips
+//! built in `animate_ducks`, only built with the `aseprite` feature.
+//!
+//! Export the sheet with "Array" frames and the tags included
+//! (`aseprite -b duck.aseprite --sheet duck_single_spritesheet.png --data
+//! duck.aseprite.json --format json-array --list-tags`). Frame durations and
+//! tag directions come from the file. Ducks play `fly` while flying, `die`
+//! once when hit and then `falling`, other sprites can play any tag, like the
+//! dog's `laugh`, with `AsepriteAnimation`.
+
+use std::{collections::HashMap, time::Duration};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, ParseAssetPathError},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    duck_state::{Dying, Escaping, Falling, Flying, Launching, Perched},
+    time_scale::GameTimeScale,
+    Duck, Simulation,
+};
+
+const DUCK_SHEET: &str = "textures/duck.aseprite.json";
+
+/// Frames of one tag in the order they play, `pingpong` already unrolled
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    pub frames: Vec<usize>,
+    pub durations: Vec<Duration>,
+}
+
+#[derive(Asset, TypePath, Debug)]
+pub struct AsepriteSheet {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub clips: HashMap<String, AnimationClip>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteExport {
+    frames: Vec<AsepriteFrame>,
+    meta: AsepriteMeta,
+}
+
+#[derive(Deserialize)]
+struct AsepriteFrame {
+    frame: FrameRect,
+    /// Milliseconds
+    duration: u64,
+}
+
+#[derive(Deserialize)]
+struct FrameRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsepriteMeta {
+    image: String,
+    size: FrameSize,
+    #[serde(default)]
+    frame_tags: Vec<FrameTag>,
+}
+
+#[derive(Deserialize)]
+struct FrameSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct FrameTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: TagDirection,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum TagDirection {
+    #[default]
+    Forward,
+    Reverse,
+    Pingpong,
+}
+
+#[derive(Default)]
+pub struct AsepriteLoader;
+
+#[derive(Debug, Error)]
+pub enum AsepriteLoaderError {
+    #[error("could not read aseprite export: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse aseprite export, was it exported as an array? {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("aseprite export image path is invalid: {0}")]
+    ImagePath(#[from] ParseAssetPathError),
+    #[error("tag `{0}` runs past the last frame")]
+    TagOutOfRange(String),
+}
+
+impl AssetLoader for AsepriteLoader {
+    type Asset = AsepriteSheet;
+    type Settings = ();
+    type Error = AsepriteLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let export: AsepriteExport = serde_json::from_slice(&bytes)?;
+        let image_path = load_context
+            .asset_path()
+            .resolve_embed(&export.meta.image)?;
+        let image = load_context.load(image_path);
+
+        let mut layout =
+            TextureAtlasLayout::new_empty(UVec2::new(export.meta.size.w, export.meta.size.h));
+        for frame in &export.frames {
+            let rect = &frame.frame;
+            layout.add_texture(URect::new(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h));
+        }
+
+        let mut clips = HashMap::new();
+        for tag in export.meta.frame_tags {
+            if tag.from > tag.to || tag.to >= export.frames.len() {
+                return Err(AsepriteLoaderError::TagOutOfRange(tag.name));
+            }
+            let forward: Vec<usize> = (tag.from..=tag.to).collect();
+            let frames = match tag.direction {
+                TagDirection::Forward => forward,
+                TagDirection::Reverse => forward.into_iter().rev().collect(),
+                // There and back without showing either end twice
+                TagDirection::Pingpong => {
+                    let back = forward.iter().rev().skip(1);
+                    let back = back.take(forward.len().saturating_sub(2));
+                    forward.iter().chain(back).copied().collect()
+                }
+            };
+            let durations = frames
+                .iter()
+                .map(|&frame| Duration::from_millis(export.frames[frame].duration))
+                .collect();
+            clips.insert(tag.name, AnimationClip { frames, durations });
+        }
+
+        let layout = load_context.add_labeled_asset("layout".to_string(), layout);
+        Ok(AsepriteSheet {
+            image,
+            layout,
+            clips,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
+
+/// Plays a tag of an `AsepriteSheet` on the sprite it's on, switching tags
+/// starts the new one from its first frame
+#[derive(Component, Debug, Clone)]
+pub struct AsepriteAnimation {
+    pub sheet: Handle<AsepriteSheet>,
+    tag: String,
+    /// Position in the clip, not the atlas index
+    frame: usize,
+    elapsed: Duration,
+    /// Stops on the last frame instead of looping
+    once: bool,
+}
+
+impl AsepriteAnimation {
+    pub fn new(sheet: Handle<AsepriteSheet>, tag: &str) -> Self {
+        Self {
+            sheet,
+            tag: tag.to_string(),
+            frame: 0,
+            elapsed: Duration::ZERO,
+            once: false,
+        }
+    }
+
+    pub fn play(&mut self, tag: &str, once: bool) {
+        if self.tag != tag {
+            self.tag = tag.to_string();
+            self.frame = 0;
+            self.elapsed = Duration::ZERO;
+        }
+        self.once = once;
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Whether a tag played once has reached its last frame
+    pub fn finished(&self, sheets: &Assets<AsepriteSheet>) -> bool {
+        self.once
+            && sheets
+                .get(&self.sheet)
+                .and_then(|sheet| sheet.clips.get(&self.tag))
+                .is_some_and(|clip| self.frame + 1 >= clip.frames.len())
+    }
+}
+
+#[derive(Resource)]
+struct DuckAnimations(Handle<AsepriteSheet>);
+
+pub struct AsepritePlugin;
+
+impl Plugin for AsepritePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AsepriteSheet>()
+            .init_asset_loader::<AsepriteLoader>()
+            .add_systems(Startup, load_duck_animations)
+            .add_observer(animate_new_ducks)
+            .add_systems(
+                Simulation,
+                (pick_duck_tags, play_duck_animations)
+                    .chain()
+                    .after(crate::animation::play_sprite_animations),
+            )
+            .add_systems(
+                Update,
+                play_aseprite_animations.run_if(in_state(crate::PauseState::Running)),
+            );
+    }
+}
+
+fn load_duck_animations(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(DuckAnimations(asset_server.load(DUCK_SHEET)));
+}
+
+fn animate_new_ducks(
+    trigger: Trigger<OnAdd, Duck>,
+    mut commands: Commands,
+    animations: Res<DuckAnimations>,
+) {
+    commands
+        .entity(trigger.target())
+        .insert(AsepriteAnimation::new(animations.0.clone(), "fly"));
+}
+
+// Only ducks that just got their animation or went into another state
+#[allow(clippy::type_complexity)]
+fn pick_duck_tags(
+    mut duck_query: Query<
+        (
+            &mut AsepriteAnimation,
+            Has<Launching>,
+            Has<Flying>,
+            Has<Escaping>,
+            Has<Perched>,
+            Has<Dying>,
+            Has<Falling>,
+        ),
+        Or<(
+            Added<AsepriteAnimation>,
+            Added<Launching>,
+            Added<Flying>,
+            Added<Escaping>,
+            Added<Perched>,
+            Added<Dying>,
+            Added<Falling>,
+        )>,
+    >,
+) {
+    for (mut animation, launching, flying, escaping, perched, dying, falling) in &mut duck_query {
+        // There's no sitting tag, a perched duck just flaps in place
+        if launching || flying || escaping || perched {
+            animation.play("fly", false);
+        } else if dying {
+            animation.play("die", true);
+        } else if falling {
+            animation.play("falling", false);
+        }
+    }
+}
+
+// Ducks step with the simulation so they stay in sync with it, anything else
+// steps with the frame
+fn play_duck_animations(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    sheets: Res<Assets<AsepriteSheet>>,
+    mut animation_query: Query<(&mut AsepriteAnimation, &mut Sprite), With<Duck>>,
+) {
+    let delta = time_scale.delta(&time);
+    for (mut animation, mut sprite) in &mut animation_query {
+        step_animation(&sheets, delta, &mut animation, &mut sprite);
+    }
+}
+
+fn play_aseprite_animations(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    sheets: Res<Assets<AsepriteSheet>>,
+    mut animation_query: Query<(&mut AsepriteAnimation, &mut Sprite), Without<Duck>>,
+) {
+    let delta = time_scale.delta(&time);
+    for (mut animation, mut sprite) in &mut animation_query {
+        step_animation(&sheets, delta, &mut animation, &mut sprite);
+    }
+}
+
+fn step_animation(
+    sheets: &Assets<AsepriteSheet>,
+    delta: Duration,
+    animation: &mut AsepriteAnimation,
+    sprite: &mut Sprite,
+) {
+    let Some(sheet) = sheets.get(&animation.sheet) else {
+        return;
+    };
+    let Some(clip) = sheet.clips.get(&animation.tag) else {
+        return;
+    };
+    if clip.frames.is_empty() {
+        return;
+    }
+    animation.elapsed += delta;
+    loop {
+        let duration = clip.durations[animation.frame];
+        if duration.is_zero() || animation.elapsed < duration {
+            break;
+        }
+        animation.elapsed -= duration;
+        if animation.frame + 1 < clip.frames.len() {
+            animation.frame += 1;
+        } else if animation.once {
+            animation.elapsed = Duration::ZERO;
+            break;
+        } else {
+            animation.frame = 0;
+        }
+    }
+    let index = clip.frames[animation.frame];
+    if sprite.image != sheet.image {
+        sprite.image = sheet.image.clone();
+    }
+    let atlas = sprite.texture_atlas.get_or_insert_with(|| TextureAtlas {
+        layout: sheet.layout.clone(),
+        index,
+    });
+    if atlas.layout != sheet.layout {
+        atlas.layout = sheet.layout.clone();
+    }
+    atlas.index = index;
+}
@@ -0,0 +1,287 @@
+//! Asset packs, folders in `mods/` next to `assets/` that swap out the
+//! game's files. A pack is laid out like `assets/`, so
+//! `mods/big_ducks/textures/duck_single_spritesheet.png` replaces the duck
+//! spritesheet. Spritesheets, sounds, levels and data tables all work the
+//! same way. Packs are applied in name order and later ones win, prefix them
+//! with numbers to pick the order.
+//!
+//! Packs are checked once at startup. Data files have to parse and
+//! spritesheets have to be PNGs the size of the ones they replace, so they
+//! still fit the layouts cut from them. A pack with a broken file is left
+//! out whole. The ones in use are listed on the accessibility page, the
+//! console's `packs` command lists them all with why any were left out.
+//!
+//! The seasonal theme, see `season`, is a pack from `seasons/` that goes
+//! under all of them.
+//!
+//! The packs are read through the default asset source, which
+//! [`AssetPackPlugin`] replaces, so it has to be added before `AssetPlugin`.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::{
+    asset::io::{
+        file::FileAssetReader, AssetReader, AssetReaderError, AssetSource, AssetSourceId,
+        PathStream, Reader,
+    },
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::{
+    console::ConsoleAppExt,
+    level::Level,
+    season::{Season, SEASONS_FOLDER},
+    settings::{Settings, SETTINGS_FILE},
+    species::SpeciesTable,
+    storage::Storage,
+    weapon::WeaponTable,
+};
+
+const ASSETS_FOLDER: &str = "assets";
+const MODS_FOLDER: &str = "mods";
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+#[derive(Debug, Error)]
+pub enum AssetPackError {
+    #[error("could not read {path}: {1}", path = .0.display())]
+    Io(PathBuf, io::Error),
+    #[error("{path} is broken: {1}", path = .0.display())]
+    Ron(PathBuf, ron::de::SpannedError),
+    #[error("{} is not a PNG", .0.display())]
+    NotPng(PathBuf),
+    #[error("{} is {found} but has to be {wanted}", .path.display())]
+    WrongSize {
+        path: PathBuf,
+        found: UVec2,
+        wanted: UVec2,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct AssetPack {
+    pub name: String,
+    /// Next to `assets/`
+    pub folder: PathBuf,
+    /// Files it replaces
+    pub replaced: usize,
+    /// Files the game doesn't have, these are still loaded if asked for
+    pub added: usize,
+    /// Why it was left out, if it was
+    pub error: Option<String>,
+}
+
+/// The season's pack, if there is one, then every pack found in `mods/`, in
+/// the order they're applied
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AssetPacks(pub Vec<AssetPack>);
+
+impl AssetPacks {
+    /// Checks every pack, `base` is the folder `assets/` is in
+    fn scan(base: &Path, season: Season) -> Self {
+        let mut folders: Vec<_> = season
+            .folder()
+            .map(|name| Path::new(SEASONS_FOLDER).join(name))
+            .into_iter()
+            .collect();
+        if let Ok(entries) = fs::read_dir(base.join(MODS_FOLDER)) {
+            let mut mods: Vec<_> = entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| Path::new(MODS_FOLDER).join(entry.file_name()))
+                .collect();
+            mods.sort();
+            folders.extend(mods);
+        }
+        let assets = base.join(ASSETS_FOLDER);
+        let packs = folders
+            .into_iter()
+            .map(|folder| {
+                let name = folder
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let mut pack = AssetPack {
+                    name,
+                    folder,
+                    replaced: 0,
+                    added: 0,
+                    error: None,
+                };
+                if let Err(error) = check_pack(&base.join(&pack.folder), &assets, &mut pack) {
+                    println!("Leaving out asset pack {}: {error}", pack.name);
+                    pack.error = Some(error.to_string());
+                }
+                pack
+            })
+            .collect();
+        Self(packs)
+    }
+
+    fn usable(&self) -> impl Iterator<Item = &AssetPack> {
+        self.0.iter().filter(|pack| pack.error.is_none())
+    }
+}
+
+fn check_pack(folder: &Path, assets: &Path, pack: &mut AssetPack) -> Result<(), AssetPackError> {
+    for file in files_in(folder).map_err(|error| AssetPackError::Io(folder.into(), error))? {
+        let relative = file.strip_prefix(folder).unwrap_or(&file);
+        let original = assets.join(relative);
+        if original.is_file() {
+            pack.replaced += 1;
+        } else {
+            pack.added += 1;
+        }
+        let name = relative.to_string_lossy();
+        if name.ends_with(".level.ron") {
+            check_ron::<Level>(&file, relative)?;
+        } else if name.ends_with(".weapons.ron") {
+            check_ron::<WeaponTable>(&file, relative)?;
+        } else if name.ends_with(".species.ron") {
+            check_ron::<SpeciesTable>(&file, relative)?;
+        } else if name.ends_with(".png") {
+            let found = png_size(&file, relative)?;
+            if original.is_file() {
+                let wanted = png_size(&original, relative)?;
+                if found != wanted {
+                    return Err(AssetPackError::WrongSize {
+                        path: relative.into(),
+                        found,
+                        wanted,
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn files_in(folder: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(files_in(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn check_ron<T: DeserializeOwned>(file: &Path, relative: &Path) -> Result<(), AssetPackError> {
+    let bytes = fs::read(file).map_err(|error| AssetPackError::Io(relative.into(), error))?;
+    ron::de::from_bytes::<T>(&bytes)
+        .map(|_| ())
+        .map_err(|error| AssetPackError::Ron(relative.into(), error))
+}
+
+// The width and height are the first thing in the header, no need to decode
+// the rest
+fn png_size(file: &Path, relative: &Path) -> Result<UVec2, AssetPackError> {
+    let bytes = fs::read(file).map_err(|error| AssetPackError::Io(relative.into(), error))?;
+    if bytes.len() < 24 || bytes[..8] != PNG_SIGNATURE {
+        return Err(AssetPackError::NotPng(relative.into()));
+    }
+    let number =
+        |at: usize| u32::from_be_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+    Ok(UVec2::new(number(16), number(20)))
+}
+
+/// Reads from the last pack that has the file, or `assets/` if none do
+struct PackReader {
+    packs: Vec<FileAssetReader>,
+    assets: FileAssetReader,
+}
+
+impl PackReader {
+    fn reader_for(&self, path: &Path) -> &FileAssetReader {
+        self.packs
+            .iter()
+            .rev()
+            .find(|pack| pack.root_path().join(path).exists())
+            .unwrap_or(&self.assets)
+    }
+}
+
+impl AssetReader for PackReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        self.reader_for(path).read(path).await
+    }
+
+    // Settings go with the file they're for
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        self.reader_for(path).read_meta(path).await
+    }
+
+    // A folder comes whole from one place, packs don't add to the game's
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        self.reader_for(path).read_directory(path).await
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        self.reader_for(path).is_directory(path).await
+    }
+}
+
+pub struct AssetPackPlugin;
+
+impl Plugin for AssetPackPlugin {
+    fn build(&self, app: &mut App) {
+        // Too early for the settings resource, the season is read straight
+        // from the file
+        let storage = Storage::default();
+        let season = Settings::load_or_default(&storage.config_file(SETTINGS_FILE))
+            .cosmetic
+            .season;
+        let packs = AssetPacks::scan(&FileAssetReader::get_base_path(), season);
+        let folders: Vec<_> = packs.usable().map(|pack| pack.folder.clone()).collect();
+        for pack in packs.usable() {
+            println!("Using asset pack {}", pack.name);
+        }
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build()
+                .with_reader(move || {
+                    Box::new(PackReader {
+                        packs: folders.iter().map(FileAssetReader::new).collect(),
+                        assets: FileAssetReader::new(ASSETS_FOLDER),
+                    })
+                })
+                // Saving a file in a pack isn't picked up, only in `assets/`
+                .with_watcher(AssetSource::get_default_watcher(
+                    ASSETS_FOLDER.to_string(),
+                    Duration::from_millis(300),
+                )),
+        )
+        .insert_resource(packs)
+        .add_console_command("packs", "packs", list_packs);
+    }
+}
+
+fn list_packs(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let packs = world.resource::<AssetPacks>();
+    if packs.0.is_empty() {
+        return Ok(format!("No asset packs in {MODS_FOLDER}/"));
+    }
+    let lines: Vec<_> = packs
+        .0
+        .iter()
+        .map(|pack| match &pack.error {
+            Some(error) => format!("{} left out, {error}", pack.name),
+            None => format!(
+                "{} replaces {} adds {}",
+                pack.name, pack.replaced, pack.added
+            ),
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
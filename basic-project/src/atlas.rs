@@ -0,0 +1,58 @@
+//! Keeps the spritesheet layouts in step with the images when they change on
+//! disk. Build with the `hot_reload` feature to have the assets folder
+//! watched, then a sheet that gained or lost frames gets a new grid and the
+//! sprites using it pick it up without a restart. The sheets packed
+//! together by `packing` are packed again instead, these only see the
+//! packed image.
+
+use bevy::prelude::*;
+
+use crate::GameAssets;
+
+pub fn rebuild_atlas_layouts(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    game_assets: Option<Res<GameAssets>>,
+    mut sprite_query: Query<&mut Sprite>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    for event in image_events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        for (image_handle, layout_handle) in game_assets.atlases() {
+            if image_handle.id() != *id {
+                continue;
+            }
+            let (Some(image), Some(layout)) = (images.get(*id), layouts.get(layout_handle)) else {
+                continue;
+            };
+            if layout.size == image.size() {
+                continue;
+            }
+            // Every frame of a grid is the same size, the new sheet keeps it
+            let Some(tile) = layout.textures.first().map(URect::size) else {
+                continue;
+            };
+            let columns = (image.size().x / tile.x).max(1);
+            let rows = (image.size().y / tile.y).max(1);
+            let rebuilt = TextureAtlasLayout::from_grid(tile, columns, rows, None, None);
+            let frames = rebuilt.len();
+            layouts.insert(layout_handle, rebuilt);
+            println!("Rebuilt atlas layout as {columns}x{rows} frames of {tile}");
+            // A sheet that lost frames would leave sprites pointing past the end
+            for mut sprite in &mut sprite_query {
+                if let Some(atlas) = sprite
+                    .texture_atlas
+                    .as_mut()
+                    .filter(|atlas| atlas.layout == *layout_handle)
+                {
+                    atlas.index = atlas.index.min(frames - 1);
+                }
+            }
+        }
+    }
+}
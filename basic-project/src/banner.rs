@@ -0,0 +1,167 @@
+//! Blue boxes that slide down over the play field to announce something,
+//! like "FLY AWAY!!" when a duck gets away. Anything can queue one up with
+//! [`Banners::show`], they're shown one at a time in order.
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    bitmap_text::BitmapText,
+    events::{DuckEscaped, RoundEnded},
+    hud::Hud,
+    locale::Locale,
+    nes_timing::{steps_nes_frames, whole_frames, NesTiming},
+    ExtraDuck, GameState, PauseState, Simulation,
+};
+
+const BANNER_COLOR: Color = Color::srgb(0.1, 0.2, 0.75);
+// Over the play field and HUD, under the reticles
+const BANNER_Z: f32 = 4.0;
+// Where it rests and where it slides in from, off the top of the screen
+const REST_Y: f32 = 40.0;
+const HIDDEN_Y: f32 = 240.0 / 2.0 + 16.0;
+const SLIDE_SECONDS: f32 = 0.25;
+pub const BANNER_SECONDS: f32 = 1.5;
+
+#[derive(Debug, Clone)]
+struct QueuedBanner {
+    text: String,
+    seconds: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct Banners {
+    queue: VecDeque<QueuedBanner>,
+    showing: Option<String>,
+}
+
+impl Banners {
+    /// Queues `text` to show for `seconds`, not counting the slide in and
+    /// out. Skipped if the same banner is already up or waiting.
+    pub fn show(&mut self, text: impl Into<String>, seconds: f32) {
+        let text = text.into();
+        if self.showing.as_ref() == Some(&text) || self.queue.iter().any(|b| b.text == text) {
+            return;
+        }
+        self.queue.push_back(QueuedBanner { text, seconds });
+    }
+
+    /// Nothing on screen and nothing waiting
+    pub fn is_idle(&self) -> bool {
+        self.showing.is_none() && self.queue.is_empty()
+    }
+}
+
+#[derive(Component)]
+struct Banner {
+    timer: Timer,
+}
+
+pub struct BannerPlugin;
+
+impl Plugin for BannerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Banners>()
+            .add_systems(OnExit(GameState::InGame), clear_banners)
+            .add_systems(
+                Update,
+                (
+                    queue_round_banners,
+                    show_next_banner,
+                    slide_banners.run_if(not(steps_nes_frames)),
+                )
+                    .chain()
+                    .run_if(in_state(PauseState::Running)),
+            )
+            // On NES timing a banner is up for a whole number of steps
+            // however fast the display is
+            .add_systems(Simulation, slide_banners.run_if(steps_nes_frames));
+    }
+}
+
+fn clear_banners(mut banners: ResMut<Banners>) {
+    *banners = Banners::default();
+}
+
+fn queue_round_banners(
+    locale: Res<Locale>,
+    mut banners: ResMut<Banners>,
+    extra_query: Query<(), With<ExtraDuck>>,
+    mut duck_escaped_reader: EventReader<DuckEscaped>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+) {
+    if duck_escaped_reader
+        .read()
+        .any(|escaped| !extra_query.contains(escaped.entity))
+    {
+        banners.show(locale.get("banner.fly_away"), BANNER_SECONDS);
+    }
+    for ended in round_ended_reader.read() {
+        if ended.ducks_escaped == 0 {
+            banners.show(locale.get("banner.perfect"), BANNER_SECONDS);
+        }
+    }
+}
+
+fn show_next_banner(
+    mut commands: Commands,
+    nes_timing: Option<Res<NesTiming>>,
+    mut banners: ResMut<Banners>,
+) {
+    if banners.showing.is_some() {
+        return;
+    }
+    let Some(next) = banners.queue.pop_front() else {
+        return;
+    };
+    // Eight pixels a letter with a bit of room either side
+    let size = Vec2::new(next.text.chars().count() as f32 * 8.0 + 16.0, 16.0);
+    let mut shown = Duration::from_secs_f32(next.seconds + SLIDE_SECONDS * 2.0);
+    if nes_timing.is_some() {
+        shown = whole_frames(shown);
+    }
+    commands
+        .spawn((
+            // The white outline
+            Sprite::from_color(Color::WHITE, size + 2.0),
+            Transform::from_xyz(0.0, HIDDEN_Y, BANNER_Z),
+            Banner {
+                timer: Timer::new(shown, TimerMode::Once),
+            },
+            Hud,
+            StateScoped(GameState::InGame),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Sprite::from_color(BANNER_COLOR, size),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            ));
+            parent.spawn((
+                BitmapText::new(next.text.clone()),
+                Transform::from_xyz(0.0, 0.0, 0.2),
+            ));
+        });
+    banners.showing = Some(next.text);
+}
+
+fn slide_banners(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut banners: ResMut<Banners>,
+    mut banner_query: Query<(Entity, &mut Transform, &mut Banner)>,
+) {
+    for (entity, mut transform, mut banner) in &mut banner_query {
+        banner.timer.tick(time.delta());
+        if banner.timer.finished() {
+            commands.entity(entity).despawn();
+            banners.showing = None;
+            continue;
+        }
+        // Down at the start, back up at the end
+        let elapsed = banner.timer.elapsed_secs();
+        let left = banner.timer.remaining_secs();
+        let shown = (elapsed.min(left) / SLIDE_SECONDS).min(1.0);
+        transform.translation.y = HIDDEN_Y.lerp(REST_Y, shown).round();
+    }
+}
@@ -0,0 +1,165 @@
+//! Asymmetric mode where player two flies the ducks with the keyboard or a
+//! gamepad and player one hunts them with the mouse. Steering wears out
+//! the duck's stamina, once it's gone the duck can only drift.
+
+use bevy::prelude::*;
+
+use crate::{
+    boss::Boss,
+    duck_state::{Flying, Heading},
+    events::{DuckSpawned, RoundEnded},
+    hud::Hud,
+    spatial::SpatialIndex,
+    time_scale::GameTimeScale,
+    view::View,
+    Dead, GameState,
+};
+
+// Rounds played before the game ends
+pub const BE_THE_DUCK_ROUNDS: u32 = 5;
+const STEER_SPEED: f32 = 70.0;
+const DRIFT_SPEED: f32 = 15.0;
+// Stamina per second, a full bar is 1
+const STAMINA_DRAIN: f32 = 0.3;
+const STAMINA_REGEN: f32 = 0.15;
+// Seconds the duck has to stay in the field before it can fly off the top
+const STAY_SECONDS: f32 = 6.0;
+const STAMINA_BAR_WIDTH: f32 = 32.0;
+
+/// The duck player two is flying. Only one at a time, the rest of the
+/// flock keeps its normal behaviour.
+#[derive(Component)]
+pub struct PlayerDuck {
+    pub stamina: f32,
+    stay: Timer,
+}
+
+impl Default for PlayerDuck {
+    fn default() -> Self {
+        Self {
+            stamina: 1.0,
+            stay: Timer::from_seconds(STAY_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct StaminaBar;
+
+pub fn setup_be_the_duck(mut commands: Commands) {
+    commands.spawn((
+        Sprite::from_color(
+            Color::srgb(0.3, 0.9, 0.3),
+            Vec2::new(STAMINA_BAR_WIDTH, 3.0),
+        ),
+        Transform::from_xyz(0.0, 112.0, 3.0),
+        StaminaBar,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+// Player two takes over the next duck that shows up when they aren't
+// flying one already
+pub fn possess_duck(
+    mut commands: Commands,
+    player_duck_query: Query<(), (With<PlayerDuck>, Without<Dead>)>,
+    boss_query: Query<(), With<Boss>>,
+    mut duck_spawned_reader: EventReader<DuckSpawned>,
+) {
+    let mut flying = !player_duck_query.is_empty();
+    for spawned in duck_spawned_reader.read() {
+        if flying || boss_query.contains(spawned.entity) {
+            continue;
+        }
+        commands
+            .entity(spawned.entity)
+            .insert(PlayerDuck::default());
+        flying = true;
+    }
+}
+
+pub fn steer_player_duck(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+    view: Res<View>,
+    mut spatial_index: ResMut<SpatialIndex>,
+    duck_query: Query<(Entity, &mut Transform, &mut Flying, &mut PlayerDuck), Without<Dead>>,
+) {
+    let delta = time_scale.delta_secs(&time);
+    let mut input = Vec2::ZERO;
+    for (key, direction) in [
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::KeyA, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::KeyD, Vec2::X),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::KeyS, Vec2::NEG_Y),
+        (KeyCode::ArrowUp, Vec2::Y),
+        (KeyCode::KeyW, Vec2::Y),
+    ] {
+        if keyboard_input.pressed(key) {
+            input += direction;
+        }
+    }
+    if let Some(gamepad) = gamepad_query.iter().next() {
+        input += gamepad.left_stick();
+    }
+    let input = input.clamp_length_max(1.0);
+
+    for (entity, mut transform, mut flying, mut player_duck) in duck_query {
+        player_duck.stay.tick(time_scale.delta(&time));
+        let velocity = if player_duck.stamina > 0.0 && input != Vec2::ZERO {
+            player_duck.stamina = (player_duck.stamina - STAMINA_DRAIN * delta).max(0.0);
+            input * STEER_SPEED
+        } else {
+            // Out of breath, slowly floats up and can't dodge anything
+            player_duck.stamina = (player_duck.stamina + STAMINA_REGEN * delta).min(1.0);
+            Vec2::Y * DRIFT_SPEED
+        };
+        if velocity.x > 0.0 && flying.heading != Heading::Right {
+            flying.heading = Heading::Right;
+        } else if velocity.x < 0.0 && flying.heading != Heading::Left {
+            flying.heading = Heading::Left;
+        }
+        let mut position = transform.translation.xy() + velocity * delta;
+        position.x = position.x.clamp(view.min_x(), view.max_x());
+        // The top only opens up once the duck has been out long enough
+        let top = if player_duck.stay.finished() {
+            f32::INFINITY
+        } else {
+            100.0
+        };
+        position.y = position.y.clamp(-40.0, top);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        spatial_index.insert(entity, position);
+    }
+}
+
+pub fn update_stamina_bar(
+    duck_query: Query<&PlayerDuck, Without<Dead>>,
+    mut bar_query: Query<&mut Transform, With<StaminaBar>>,
+) {
+    let stamina = duck_query
+        .iter()
+        .next()
+        .map_or(0.0, |player_duck| player_duck.stamina);
+    for mut transform in &mut bar_query {
+        transform.scale.x = stamina;
+    }
+}
+
+pub fn end_be_the_duck(
+    mut round_ended_reader: EventReader<RoundEnded>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if round_ended_reader
+        .read()
+        .any(|ended| ended.round >= BE_THE_DUCK_ROUNDS)
+    {
+        next_state.set(GameState::GameOver);
+    }
+}
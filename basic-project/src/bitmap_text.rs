@@ -0,0 +1,160 @@
+//! Text drawn from the NES font sheet, one 8x8 sprite per letter, so it
+//! lines up with the background's pixels instead of being smoothed like
+//! Bevy's vector text. The sheet only has upper case ASCII, lower case
+//! letters are drawn as capitals and accented ones as the plain letter.
+
+use bevy::prelude::*;
+
+use crate::GameAssets;
+
+const GLYPH_SIZE: f32 = 8.0;
+// The sheet starts at the space
+const FIRST_GLYPH: u8 = b' ';
+const LAST_GLYPH: u8 = b'_';
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    /// The transform is the top left corner
+    Left,
+    #[default]
+    Center,
+    /// The transform is the top right corner
+    Right,
+}
+
+/// Lays the glyphs out as children whenever it changes. Lines are split on
+/// `\n` and stacked eight pixels apart.
+#[derive(Component, Debug, Clone)]
+#[require(Transform, Visibility)]
+pub struct BitmapText {
+    pub text: String,
+    pub color: Color,
+    pub align: TextAlign,
+}
+
+impl BitmapText {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            color: Color::WHITE,
+            align: TextAlign::default(),
+        }
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Only writes through `Mut` when the text is different, so it isn't
+    /// marked changed and laid out again every frame. Going through
+    /// `&mut self` would mark it either way.
+    pub fn set(text: &mut Mut<Self>, value: impl AsRef<str>) {
+        let value = value.as_ref();
+        if text.text != value {
+            text.text = value.to_string();
+        }
+    }
+}
+
+#[derive(Component)]
+struct Glyph;
+
+pub struct BitmapTextPlugin;
+
+impl Plugin for BitmapTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            layout_bitmap_text
+                .before(TransformSystem::TransformPropagate)
+                .run_if(resource_exists::<GameAssets>),
+        );
+    }
+}
+
+/// The plain letter for the accented ones the translations use
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ä' | 'À' | 'Á' | 'Â' | 'Ä' => 'A',
+        'ç' | 'Ç' => 'C',
+        'è' | 'é' | 'ê' | 'ë' | 'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'ì' | 'í' | 'î' | 'ï' | 'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'ñ' | 'Ñ' => 'N',
+        'ò' | 'ó' | 'ô' | 'ö' | 'Ò' | 'Ó' | 'Ô' | 'Ö' => 'O',
+        'ù' | 'ú' | 'û' | 'ü' | 'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'ÿ' | 'Ÿ' => 'Y',
+        'œ' | 'Œ' => 'O',
+        '’' => '\'',
+        _ => c,
+    }
+}
+
+fn glyph_index(c: char) -> usize {
+    let c = fold_accent(c).to_ascii_uppercase();
+    let c = if c.is_ascii() && (FIRST_GLYPH..=LAST_GLYPH).contains(&(c as u8)) {
+        c as u8
+    } else {
+        b'?'
+    };
+    (c - FIRST_GLYPH) as usize
+}
+
+fn layout_bitmap_text(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    text_query: Query<(Entity, &BitmapText, Option<&Children>), Changed<BitmapText>>,
+    glyph_query: Query<(), With<Glyph>>,
+) {
+    for (entity, text, children) in &text_query {
+        // The old glyphs, anything else parented to the text stays
+        for child in children.into_iter().flatten() {
+            if glyph_query.contains(*child) {
+                commands.entity(*child).despawn();
+            }
+        }
+        let lines: Vec<&str> = text.text.lines().collect();
+        let height = lines.len() as f32 * GLYPH_SIZE;
+        commands.entity(entity).with_children(|parent| {
+            for (row, line) in lines.iter().enumerate() {
+                let width = line.chars().count() as f32 * GLYPH_SIZE;
+                let left = match text.align {
+                    TextAlign::Left => 0.0,
+                    TextAlign::Center => -width / 2.0,
+                    TextAlign::Right => -width,
+                };
+                // Centred text is centred up and down too
+                let top = match text.align {
+                    TextAlign::Center => height / 2.0,
+                    _ => 0.0,
+                };
+                for (column, c) in line.chars().enumerate() {
+                    if c == ' ' {
+                        continue;
+                    }
+                    let x = left + (column as f32 + 0.5) * GLYPH_SIZE;
+                    let y = top - (row as f32 + 0.5) * GLYPH_SIZE;
+                    parent.spawn((
+                        Sprite {
+                            color: text.color,
+                            ..Sprite::from_atlas_image(
+                                game_assets.font.clone(),
+                                TextureAtlas {
+                                    layout: game_assets.font_layout.clone(),
+                                    index: glyph_index(c),
+                                },
+                            )
+                        },
+                        Transform::from_xyz(x, y, 0.0),
+                        Glyph,
+                    ));
+                }
+            }
+        });
+    }
+}
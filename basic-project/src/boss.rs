@@ -0,0 +1,82 @@
+//! Every few rounds a single big duck takes a beating instead of the usual
+//! flock. It loops around the sky for a while and then makes a run for it.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animation::SpriteAnimation,
+    duck_state::{Flying, Heading},
+    spatial::SpatialIndex,
+    time_scale::GameTimeScale,
+    view::View,
+    Dead, Duck, Health,
+};
+
+pub const BOSS_EVERY: u32 = 5;
+pub const BOSS_SCALE: f32 = 2.0;
+const BOSS_HEALTH: f32 = 10.0;
+const BOSS_POINTS: u32 = 10_000;
+// Seconds it circles before flying off
+const BOSS_STAY: f32 = 20.0;
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Boss {
+    stay: Timer,
+}
+
+// Darker than the regular ducks so it reads as something different
+pub const BOSS_TINT: Color = Color::srgb(1.0, 0.55, 0.55);
+
+/// Everything on top of a regular duck that makes it the boss, spawn it
+/// with `spawn::SpawnDuckCommands`
+pub fn boss_bundle() -> impl Bundle {
+    (
+        Duck {
+            speed: 40.0,
+            points: BOSS_POINTS,
+        },
+        Health {
+            current: BOSS_HEALTH,
+        },
+        Boss {
+            stay: Timer::from_seconds(BOSS_STAY, TimerMode::Once),
+        },
+        SpriteAnimation::default().with_speed(2.0),
+    )
+}
+
+// Figure eight over the field instead of bouncing between the edges
+pub fn move_boss(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    view: Res<View>,
+    mut spatial_index: ResMut<SpatialIndex>,
+    boss_query: Query<(Entity, &mut Transform, &Duck, &mut Flying, &mut Boss), Without<Dead>>,
+) {
+    let delta = time_scale.delta_secs(&time);
+    for (entity, mut transform, duck, mut flying, mut boss) in boss_query {
+        boss.stay.tick(time_scale.delta(&time));
+        if boss.stay.finished() {
+            // Give up and leave through the top
+            transform.translation.y += duck.speed * 2.0 * delta;
+        } else {
+            let t = boss.stay.elapsed_secs() * duck.speed / 60.0;
+            let target = Vec2::new(view.to_world(t.sin() * 90.0), (t * 2.0).sin() * 30.0 + 40.0);
+            let previous = transform.translation.xy();
+            // Ease towards the curve so it doesn't teleport from its spawn
+            let next = previous.lerp(target, (delta * 3.0).min(1.0));
+            let heading = if next.x >= previous.x {
+                Heading::Right
+            } else {
+                Heading::Left
+            };
+            if flying.heading != heading {
+                flying.heading = heading;
+            }
+            transform.translation.x = next.x;
+            transform.translation.y = next.y;
+        }
+        spatial_index.insert(entity, transform.translation.xy());
+    }
+}
@@ -0,0 +1,137 @@
+//! Autoplay bot, plays the title screen's demo games and with the `bot`
+//! feature every game, for soak testing. It picks the duck closest to where
+//! it last shot, waits its reaction time and fires, missing by up to a
+//! duck's width when accuracy is low. Shots go through `GameInputEvent` like
+//! the mouse's, so the rest of the game can't tell the difference.
+//!
+//! Tuned from the console with `bot on|off`, `bot reaction <seconds>` and
+//! `bot accuracy <0-1>`.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    console::ConsoleAppExt, demo::Demo, duck_state::Shootable, events::PlayerId,
+    weapon::EquippedWeapon, Dead, FrameSet, GameInputEvent, PauseState, RoundPhase,
+};
+
+// How far off a shot lands at zero accuracy
+const MAX_MISS: f32 = 16.0;
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct BotSettings {
+    /// Plays every game, demos get played either way
+    pub enabled: bool,
+    /// Seconds between picking a duck and shooting at it
+    pub reaction: f32,
+    /// 1 always hits the middle of the duck, 0 can miss by a duck's width
+    pub accuracy: f32,
+}
+
+impl Default for BotSettings {
+    fn default() -> Self {
+        Self {
+            enabled: cfg!(feature = "bot"),
+            reaction: 0.4,
+            accuracy: 0.8,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct Bot {
+    target: Option<Entity>,
+    reaction: Timer,
+    last_shot: Vec2,
+}
+
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BotSettings>()
+            .init_resource::<Bot>()
+            .add_console_command(
+                "bot",
+                "bot on|off|reaction <seconds>|accuracy <0-1>",
+                bot_command,
+            )
+            .add_systems(
+                Update,
+                run_bot.in_set(FrameSet::Input).run_if(
+                    in_state(PauseState::Running)
+                        .and(in_state(RoundPhase::Playing))
+                        .and(
+                            resource_exists::<Demo>
+                                .or(|settings: Res<BotSettings>| settings.enabled),
+                        ),
+                ),
+            );
+    }
+}
+
+fn run_bot(
+    time: Res<Time>,
+    settings: Res<BotSettings>,
+    mut bot: ResMut<Bot>,
+    equipped: Res<EquippedWeapon>,
+    duck_query: Query<(Entity, &Transform), (Shootable, Without<Dead>)>,
+    mut game_input_event_writer: EventWriter<GameInputEvent>,
+) {
+    // Reloads as soon as it runs dry, the same way a player would
+    if equipped.ammo == 0 {
+        game_input_event_writer.write(GameInputEvent::Reload(PlayerId(0)));
+        return;
+    }
+    let alive = |entity: Entity| duck_query.contains(entity);
+    if !bot.target.is_some_and(alive) {
+        let last_shot = bot.last_shot;
+        bot.target = duck_query
+            .iter()
+            .min_by(|(_, a), (_, b)| {
+                let a = a.translation.xy().distance_squared(last_shot);
+                let b = b.translation.xy().distance_squared(last_shot);
+                a.total_cmp(&b)
+            })
+            .map(|(entity, _)| entity);
+        bot.reaction = Timer::from_seconds(settings.reaction, TimerMode::Once);
+    }
+    let Some(target) = bot.target else {
+        return;
+    };
+    bot.reaction.tick(time.delta());
+    if !bot.reaction.finished() {
+        return;
+    }
+    let Ok((_, transform)) = duck_query.get(target) else {
+        return;
+    };
+    // Its own randomness, the game's generators stay reproducible from the
+    // seed
+    let mut rng = rand::thread_rng();
+    let miss = MAX_MISS * (1.0 - settings.accuracy.clamp(0.0, 1.0));
+    let offset = Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)) * miss;
+    let position = transform.translation.xy() + offset;
+    game_input_event_writer.write(GameInputEvent::Shoot(PlayerId(0), position, None));
+    bot.last_shot = position;
+    // Look again next frame, the same duck if it's still up
+    bot.target = None;
+}
+
+fn bot_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    const USAGE: &str = "Usage: bot on|off|reaction <seconds>|accuracy <0-1>";
+    let mut settings = world.resource_mut::<BotSettings>();
+    let value = || {
+        args.get(1)
+            .and_then(|word| word.parse::<f32>().ok())
+            .ok_or_else(|| USAGE.to_string())
+    };
+    match args.first() {
+        Some(&"on") => settings.enabled = true,
+        Some(&"off") => settings.enabled = false,
+        Some(&"reaction") => settings.reaction = value()?.max(0.0),
+        Some(&"accuracy") => settings.accuracy = value()?.clamp(0.0, 1.0),
+        _ => return Err(USAGE.to_string()),
+    }
+    Ok(format!("{:?}", *settings))
+}
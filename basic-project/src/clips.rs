@@ -0,0 +1,159 @@
+//! Keeps the last few seconds of the game at the NES resolution and saves
+//! them as a GIF next to the screenshots when something worth sharing
+//! happens, two ducks down in quick succession or the boss.
+//!
+//! Frames are read back from the native camera's image a few times a second
+//! and the encoding happens off the main thread, but holding on to the
+//! frames and reading them back still costs, so it's behind a feature.
+
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+    tasks::AsyncComputeTaskPool,
+};
+
+use crate::{
+    boss::Boss,
+    events::{DuckHit, PlayerId},
+    screenshot::NativeTarget,
+    storage::Storage,
+    GameState, PauseState,
+};
+
+const CLIP_FPS: f32 = 15.0;
+const CLIP_SECONDS: f32 = 3.0;
+// Keeps going a moment after the kill so the duck can be seen falling
+const CLIP_TAIL: f32 = 0.5;
+// A second hit this soon after the last one makes a double kill
+const DOUBLE_KILL_WINDOW: f32 = 0.5;
+const WIDTH: u16 = 256;
+const HEIGHT: u16 = 240;
+
+/// RGBA frames, oldest first
+#[derive(Resource, Default)]
+pub struct ClipBuffer {
+    frames: VecDeque<Vec<u8>>,
+    capture: Timer,
+    /// Counting down to saving the clip once something's worth it
+    pending: Option<Timer>,
+    /// Seconds since player one last hit a duck
+    since_hit: Option<f32>,
+}
+
+pub struct ClipsPlugin;
+
+impl Plugin for ClipsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClipBuffer {
+            capture: Timer::from_seconds(1.0 / CLIP_FPS, TimerMode::Repeating),
+            ..default()
+        })
+        .add_systems(OnEnter(GameState::InGame), clear_clip_buffer)
+        .add_systems(
+            Update,
+            (capture_clip_frame, watch_for_highlights, save_clip)
+                .chain()
+                .in_set(crate::FrameSet::Presentation)
+                .run_if(in_state(PauseState::Running)),
+        );
+    }
+}
+
+fn clear_clip_buffer(mut buffer: ResMut<ClipBuffer>) {
+    buffer.frames.clear();
+    buffer.pending = None;
+    buffer.since_hit = None;
+}
+
+fn capture_clip_frame(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    target: Res<NativeTarget>,
+    mut buffer: ResMut<ClipBuffer>,
+) {
+    if !buffer.capture.tick(time.delta()).just_finished() {
+        return;
+    }
+    commands.spawn(Screenshot::image(target.0.clone())).observe(
+        |trigger: Trigger<ScreenshotCaptured>, mut buffer: ResMut<ClipBuffer>| {
+            let Ok(image) = trigger.event().0.clone().try_into_dynamic() else {
+                return;
+            };
+            buffer.frames.push_back(image.to_rgba8().into_raw());
+            while buffer.frames.len() > (CLIP_FPS * CLIP_SECONDS) as usize {
+                buffer.frames.pop_front();
+            }
+        },
+    );
+}
+
+// Only player one's shots, the other players aren't the one watching
+fn watch_for_highlights(
+    time: Res<Time<Real>>,
+    boss_query: Query<(), With<Boss>>,
+    mut buffer: ResMut<ClipBuffer>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+) {
+    if let Some(since_hit) = buffer.since_hit.as_mut() {
+        *since_hit += time.delta_secs();
+    }
+    for hit in duck_hit_reader.read() {
+        if hit.player != PlayerId(0) {
+            continue;
+        }
+        let double_kill = buffer
+            .since_hit
+            .is_some_and(|since_hit| since_hit <= DOUBLE_KILL_WINDOW);
+        if (double_kill || boss_query.contains(hit.entity)) && buffer.pending.is_none() {
+            buffer.pending = Some(Timer::from_seconds(CLIP_TAIL, TimerMode::Once));
+        }
+        buffer.since_hit = Some(0.0);
+    }
+}
+
+fn save_clip(time: Res<Time<Real>>, storage: Res<Storage>, mut buffer: ResMut<ClipBuffer>) {
+    let Some(pending) = buffer.pending.as_mut() else {
+        return;
+    };
+    if !pending.tick(time.delta()).finished() {
+        return;
+    }
+    buffer.pending = None;
+    let frames: Vec<Vec<u8>> = buffer.frames.iter().cloned().collect();
+    if frames.is_empty() {
+        return;
+    }
+    let dir = storage.pictures_dir().to_path_buf();
+    AsyncComputeTaskPool::get()
+        .spawn(async move {
+            if let Err(error) = write_gif(dir, frames) {
+                println!("Could not save clip: {error}");
+            }
+        })
+        .detach();
+}
+
+fn write_gif(dir: PathBuf, frames: Vec<Vec<u8>>) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(&dir)?;
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let path = dir.join(format!("duck-hunt-{}.gif", since_epoch.as_millis()));
+    let mut encoder = gif::Encoder::new(File::create(&path)?, WIDTH, HEIGHT, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for mut pixels in frames {
+        let mut frame = gif::Frame::from_rgba_speed(WIDTH, HEIGHT, &mut pixels, 10);
+        // In hundredths of a second
+        frame.delay = (100.0 / CLIP_FPS).round() as u16;
+        encoder.write_frame(&frame)?;
+    }
+    println!("Saved clip to {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! Clouds drifting across the sky, behind the ducks and the field. The
+//! level file has them in layers, see `level::CloudLayer`, and layers
+//! further back going slower is all the parallax there is. They dim with
+//! the rest of the field as night falls. They're too far off to move when
+//! the view pans.
+
+use bevy::prelude::*;
+
+use crate::{
+    day_night::TimeOfDay, level::Level, mode::GameMode, time_scale::GameTimeScale,
+    view::ScreenFixed, GameAssets, GameState, PauseState,
+};
+
+// Wide enough that a cloud is all the way off one side before it comes
+// back in at the other
+const WRAP_WIDTH: f32 = 256.0 + 64.0;
+// In front of the sky color and behind the ducks, with each layer a little
+// in front of the one before
+const CLOUD_Z: f32 = -1.0;
+const LAYER_Z: f32 = 0.01;
+
+#[derive(Component)]
+struct Cloud {
+    speed: f32,
+    alpha: f32,
+}
+
+pub struct CloudsPlugin;
+
+impl Plugin for CloudsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), spawn_clouds)
+            .add_systems(
+                Update,
+                (
+                    drift_clouds.run_if(in_state(PauseState::Running)),
+                    dim_clouds,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+// Spread out by their number rather than rolled, nothing here changes how
+// the game plays so it doesn't need the rng
+fn scatter(i: usize, step: f32) -> f32 {
+    (i as f32 * step).fract()
+}
+
+fn spawn_clouds(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    game_assets: Res<GameAssets>,
+    levels: Res<Assets<Level>>,
+) {
+    let Some(level) = levels.get(game_assets.level(*mode)) else {
+        return;
+    };
+    let mut i = 0;
+    for (layer_index, layer) in level.clouds.iter().enumerate() {
+        for _ in 0..layer.count {
+            i += 1;
+            let x = (scatter(i, 0.618_034) - 0.5) * WRAP_WIDTH;
+            let y = layer.min_y + (layer.max_y - layer.min_y) * scatter(i, 0.414_214);
+            let alpha = layer.alpha.clamp(0.0, 1.0);
+            commands.spawn((
+                Sprite {
+                    color: Color::WHITE.with_alpha(alpha),
+                    ..game_assets.cloud_sprite()
+                },
+                Transform::from_xyz(x.round(), y.round(), CLOUD_Z + layer_index as f32 * LAYER_Z)
+                    .with_scale(Vec3::splat(layer.scale)),
+                Cloud {
+                    speed: layer.speed,
+                    alpha,
+                },
+                ScreenFixed,
+                StateScoped(GameState::InGame),
+            ));
+        }
+    }
+}
+
+fn drift_clouds(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut cloud_query: Query<(&mut Transform, &Cloud)>,
+) {
+    let delta = time_scale.delta_secs(&time);
+    let half = WRAP_WIDTH / 2.0;
+    for (mut transform, cloud) in &mut cloud_query {
+        let x = transform.translation.x + cloud.speed * delta;
+        transform.translation.x = (x + half).rem_euclid(WRAP_WIDTH) - half;
+    }
+}
+
+fn dim_clouds(time_of_day: Option<Res<TimeOfDay>>, mut cloud_query: Query<(&mut Sprite, &Cloud)>) {
+    let Some(time_of_day) = time_of_day.filter(|time_of_day| time_of_day.is_changed()) else {
+        return;
+    };
+    for (mut sprite, cloud) in &mut cloud_query {
+        sprite.color = time_of_day.tint().with_alpha(cloud.alpha);
+    }
+}
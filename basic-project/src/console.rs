@@ -0,0 +1,309 @@
+//! Drop-down developer console, opened with the key left of 1. Commands live
+//! in `ConsoleCommands` and other plugins add their own with
+//! `App::add_console_command`, handlers get the whole world and the words
+//! after the command name.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use rand::Rng;
+
+use crate::{
+    duck_state::Heading,
+    rng::GameRng,
+    spawn::{Behaviour, SpawnDuckCommands, SpawnDuckEvent},
+    species::Species,
+    time_scale::GameTimeScale,
+    weapon::EquippedWeapon,
+    ExtraDuck, GameState, Round, GRASS_Y,
+};
+
+// Lines of output kept on screen
+const HISTORY_LINES: usize = 8;
+
+/// Runs a command, `Ok` and `Err` are both printed to the console
+pub type ConsoleHandler = fn(&mut World, &[&str]) -> Result<String, String>;
+
+#[derive(Clone, Copy)]
+pub struct ConsoleCommand {
+    pub usage: &'static str,
+    pub run: ConsoleHandler,
+}
+
+#[derive(Resource, Default)]
+pub struct ConsoleCommands(BTreeMap<&'static str, ConsoleCommand>);
+
+pub trait ConsoleAppExt {
+    fn add_console_command(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        run: ConsoleHandler,
+    ) -> &mut Self;
+}
+
+impl ConsoleAppExt for App {
+    fn add_console_command(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        run: ConsoleHandler,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<ConsoleCommands>()
+            .0
+            .insert(name, ConsoleCommand { usage, run });
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    input: String,
+    /// Entered lines waiting to be run
+    submitted: Vec<String>,
+    history: Vec<String>,
+}
+
+impl Console {
+    fn print(&mut self, line: impl Into<String>) {
+        self.history.push(line.into());
+        let overflow = self.history.len().saturating_sub(HISTORY_LINES);
+        self.history.drain(..overflow);
+    }
+}
+
+#[derive(Component)]
+pub struct ConsoleWindow;
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            .add_console_command("help", "help", help)
+            .add_console_command(
+                "spawn",
+                "spawn duck [regular|fast|golden|boss] [count]",
+                spawn,
+            )
+            .add_console_command("set", "set round <number>", set)
+            .add_console_command("give", "give ammo", give)
+            .add_console_command("timescale", "timescale <speed>", timescale)
+            .add_systems(Startup, setup_console)
+            .add_systems(
+                Update,
+                (
+                    toggle_console,
+                    type_in_console,
+                    run_console_commands,
+                    update_console_text,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn setup_console(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            GlobalZIndex(30),
+            Visibility::Hidden,
+            ConsoleWindow,
+        ))
+        .with_child((
+            Text::default(),
+            TextFont {
+                font_size: 6.0,
+                ..default()
+            },
+        ));
+}
+
+fn toggle_console(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut console: ResMut<Console>,
+    mut window_query: Query<&mut Visibility, With<ConsoleWindow>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Backquote) {
+        return;
+    }
+    console.open = !console.open;
+    for mut visibility in &mut window_query {
+        *visibility = if console.open {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn type_in_console(mut console: ResMut<Console>, mut keyboard_reader: EventReader<KeyboardInput>) {
+    for event in keyboard_reader.read() {
+        // The toggle key shouldn't end up in the input
+        if !console.open
+            || event.state != ButtonState::Pressed
+            || event.key_code == KeyCode::Backquote
+        {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                console.submitted.push(line);
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Space => console.input.push(' '),
+            Key::Character(characters) => console.input.push_str(characters),
+            _ => {}
+        }
+    }
+}
+
+fn run_console_commands(world: &mut World) {
+    let submitted = std::mem::take(&mut world.resource_mut::<Console>().submitted);
+    for line in submitted {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((name, args)) = words.split_first() else {
+            continue;
+        };
+        let command = world.resource::<ConsoleCommands>().0.get(name).copied();
+        let output = match command {
+            Some(command) => (command.run)(world, args).unwrap_or_else(|error| error),
+            None => format!("Unknown command `{name}`, try `help`"),
+        };
+        let mut console = world.resource_mut::<Console>();
+        console.print(format!("> {line}"));
+        if !output.is_empty() {
+            console.print(output);
+        }
+    }
+}
+
+fn update_console_text(
+    console: Res<Console>,
+    window_query: Query<&Children, With<ConsoleWindow>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    for children in &window_query {
+        for child in children {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.0 = console
+                    .history
+                    .iter()
+                    .map(String::as_str)
+                    .chain([format!("> {}_", console.input).as_str()])
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+        }
+    }
+}
+
+fn in_game(world: &World) -> Result<(), String> {
+    match world.get_resource::<State<GameState>>() {
+        Some(state) if *state.get() == GameState::InGame => Ok(()),
+        _ => Err("Only works during a game".to_string()),
+    }
+}
+
+fn parse<T: std::str::FromStr>(word: Option<&&str>, usage: &str) -> Result<T, String> {
+    word.and_then(|word| word.parse().ok())
+        .ok_or_else(|| format!("Usage: {usage}"))
+}
+
+fn help(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let commands = world.resource::<ConsoleCommands>();
+    Ok(commands
+        .0
+        .values()
+        .map(|command| command.usage)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn spawn(world: &mut World, args: &[&str]) -> Result<String, String> {
+    const USAGE: &str = "spawn duck [regular|fast|golden|boss] [count]";
+    in_game(world)?;
+    if args.first() != Some(&"duck") {
+        return Err(format!("Usage: {USAGE}"));
+    }
+    let kind = args.get(1).copied().unwrap_or("regular");
+    let count: u32 = match args.get(2) {
+        Some(_) => parse(args.get(2), USAGE)?,
+        None => 1,
+    };
+    let Ok(species) = kind.parse::<Species>() else {
+        return Err(format!("Usage: {USAGE}"));
+    };
+    for _ in 0..count {
+        let x = world.resource_mut::<GameRng>().gen_range(-120.0..120.0);
+        // Not part of the round, it would end early otherwise
+        world
+            .commands()
+            .spawn_duck(SpawnDuckEvent {
+                species,
+                position: Vec2::new(x, GRASS_Y),
+                behaviour: Behaviour::TakeOff(Heading::towards_center(x)),
+            })
+            .insert(ExtraDuck);
+    }
+    world.flush();
+    Ok(format!("Spawned {count} {kind} duck(s)"))
+}
+
+fn set(world: &mut World, args: &[&str]) -> Result<String, String> {
+    const USAGE: &str = "set round <number>";
+    in_game(world)?;
+    match args.first() {
+        Some(&"round") => {
+            let number: u32 = parse(args.get(1), USAGE)?;
+            world.insert_resource(Round::new(number.max(1)));
+            Ok(format!("Round {}", number.max(1)))
+        }
+        _ => Err(format!("Usage: {USAGE}")),
+    }
+}
+
+fn give(world: &mut World, args: &[&str]) -> Result<String, String> {
+    in_game(world)?;
+    if args.first() != Some(&"ammo") {
+        return Err("Usage: give ammo".to_string());
+    }
+    world.resource_mut::<EquippedWeapon>().refill();
+    let mut player_weapon_query = world.query::<&mut EquippedWeapon>();
+    for mut weapon in player_weapon_query.iter_mut(world) {
+        weapon.refill();
+    }
+    Ok("Ammo refilled".to_string())
+}
+
+fn timescale(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let speed: f32 = parse(args.first(), "timescale <speed>")?;
+    if speed < 0.0 {
+        return Err("Speed can't be negative".to_string());
+    }
+    world.resource_mut::<GameTimeScale>().base = speed;
+    Ok(format!("Time scale {speed}"))
+}
@@ -0,0 +1,51 @@
+//! What the OS cursor does in each part of the game. It's shown in the menus
+//! and hidden while playing if a crosshair is drawn for the mouse, and with
+//! the setting on it's kept inside the window while playing. Pausing, losing
+//! focus or landing on the error screen always gives it back.
+
+use bevy::{
+    prelude::*,
+    window::{CursorGrabMode, PrimaryWindow},
+};
+
+use crate::{events::PlayerId, settings::Settings, versus::Reticle, GameState, PauseState};
+
+pub struct CursorPlugin;
+
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_cursor);
+    }
+}
+
+fn update_cursor(
+    settings: Res<Settings>,
+    game_state: Res<State<GameState>>,
+    pause_state: Option<Res<State<PauseState>>>,
+    reticle_query: Query<&Reticle>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut win) = window_query.single_mut() else {
+        return;
+    };
+    let playing = win.focused
+        && *game_state.get() == GameState::InGame
+        && pause_state.is_some_and(|state| *state.get() == PauseState::Running);
+    let grab_mode = if settings.window.confine_cursor && playing {
+        CursorGrabMode::Confined
+    } else {
+        CursorGrabMode::None
+    };
+    // Without a reticle of its own the mouse aims with the OS cursor
+    let drawn = reticle_query
+        .iter()
+        .any(|reticle| reticle.player == PlayerId(0));
+    let visible = !(playing && drawn);
+    // Only touched when it changes, every write goes out to the OS
+    if win.cursor_options.grab_mode != grab_mode {
+        win.cursor_options.grab_mode = grab_mode;
+    }
+    if win.cursor_options.visible != visible {
+        win.cursor_options.visible = visible;
+    }
+}
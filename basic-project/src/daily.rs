@@ -0,0 +1,46 @@
+//! Daily challenge. The run seed comes from the UTC date so everyone gets the
+//! same ducks on the same day, and scores go on a board for that day only.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+/// Set while a daily challenge is being played, holds the day it's for
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyChallenge {
+    pub day: u64,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            day: since_epoch.as_secs() / 86_400,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        // splitmix64, so neighbouring days don't get neighbouring seeds
+        let mut z = self.day.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// The day as year, month and day of the month
+    pub fn date(&self) -> (i64, u32, u32) {
+        // Howard Hinnant's days-to-civil
+        let z = self.day as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        (year, month, day)
+    }
+}
@@ -0,0 +1,215 @@
+//! Day going to dusk and then night as the rounds go on, and back round to
+//! day again. The sky and the field darken with it over a few seconds
+//! after a round ends.
+//!
+//! At night, the darkest part, ducks are only silhouettes against the sky,
+//! every species the same flat black so the golden one doesn't give itself
+//! away. High visibility ducks keep their colors, and a duck that's hit
+//! still flashes white. Like the shot flash, the silhouette is a look of
+//! the duck sheet built on the CPU.
+
+use bevy::{color::Mix, prelude::*};
+
+use crate::{
+    alpha_mask::duck_image_to_build,
+    duck_look::{DuckLook, DuckLooks},
+    level::Level,
+    mode::GameMode,
+    settings::Settings,
+    Duck, GameAssets, GameState, Round,
+};
+
+// How dark it gets each round, from 0 for day to 1 for night, starting
+// over after the last
+const DARKNESS: [f32; 8] = [0.0, 0.0, 0.0, 0.35, 0.7, 1.0, 1.0, 0.4];
+// Darker than this and the ducks turn to silhouettes
+const NIGHT: f32 = 0.95;
+// Seconds to go from day all the way to night
+const FADE_TIME: f32 = 4.0;
+const DUSK_SKY: Color = Color::srgb(0.95, 0.6, 0.4);
+const NIGHT_SKY: Color = Color::srgb(0.12, 0.16, 0.35);
+const DUSK_TINT: Color = Color::srgb(1.0, 0.75, 0.6);
+const NIGHT_TINT: Color = Color::srgb(0.35, 0.4, 0.65);
+const SILHOUETTE_COLOR: Color = Color::srgb(0.02, 0.02, 0.05);
+
+/// How dark it is right now, from 0 for day to 1 for night
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimeOfDay {
+    pub darkness: f32,
+}
+
+impl TimeOfDay {
+    /// How dark it gets in `round`
+    pub fn for_round(round: u32) -> Self {
+        let i = round.saturating_sub(1) as usize % DARKNESS.len();
+        Self {
+            darkness: DARKNESS[i],
+        }
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.darkness > NIGHT
+    }
+
+    /// Day to dusk over the first half, dusk to night over the second
+    fn mix(&self, day: Color, dusk: Color, night: Color) -> Color {
+        if self.darkness < 0.5 {
+            day.mix(&dusk, self.darkness * 2.0)
+        } else {
+            dusk.mix(&night, (self.darkness - 0.5) * 2.0)
+        }
+    }
+
+    /// What the field and anything in front of the sky is tinted
+    pub fn tint(&self) -> Color {
+        self.mix(Color::WHITE, DUSK_TINT, NIGHT_TINT)
+    }
+}
+
+pub struct DayNightPlugin;
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(show_silhouette)
+            .add_systems(OnEnter(GameState::InGame), reset_time_of_day)
+            .add_systems(Update, build_silhouette_sheet)
+            .add_systems(
+                Update,
+                (pass_time, (darken_field, switch_silhouettes))
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+// A game starts at whatever time its first round is, without fading in
+fn reset_time_of_day(mut commands: Commands) {
+    commands.remove_resource::<TimeOfDay>();
+}
+
+fn pass_time(
+    mut commands: Commands,
+    time: Res<Time>,
+    round: Res<Round>,
+    time_of_day: Option<ResMut<TimeOfDay>>,
+) {
+    let target = TimeOfDay::for_round(round.number);
+    let Some(mut time_of_day) = time_of_day else {
+        commands.insert_resource(target);
+        return;
+    };
+    if *time_of_day == target {
+        return;
+    }
+    let step = time.delta_secs() / FADE_TIME;
+    time_of_day.darkness += (target.darkness - time_of_day.darkness).clamp(-step, step);
+}
+
+// The background and the grass over it are both cut from the background
+// sheet, everything showing it is tinted, and so is the level's panorama.
+// The rest of the packed image, like the font, is left alone.
+fn darken_field(
+    time_of_day: Option<Res<TimeOfDay>>,
+    mode: Res<GameMode>,
+    game_assets: Res<GameAssets>,
+    levels: Res<Assets<Level>>,
+    mut clear_color: ResMut<ClearColor>,
+    mut sprite_query: Query<&mut Sprite>,
+) {
+    let Some(time_of_day) = time_of_day.filter(|time_of_day| time_of_day.is_changed()) else {
+        return;
+    };
+    let level = levels.get(game_assets.level(*mode));
+    let [red, green, blue] = level
+        .and_then(|level| level.sky)
+        .unwrap_or(Level::DEFAULT_SKY);
+    let panorama = level
+        .and_then(|level| level.panorama.as_ref())
+        .map(|panorama| &panorama.handle);
+    clear_color.0 = time_of_day.mix(Color::linear_rgb(red, green, blue), DUSK_SKY, NIGHT_SKY);
+    let tint = time_of_day.tint();
+    for mut sprite in &mut sprite_query {
+        if game_assets.shows_background(&sprite) || Some(&sprite.image) == panorama {
+            sprite.color = tint;
+        }
+    }
+}
+
+fn build_silhouette_sheet(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut built_from: Local<Option<AssetId<Image>>>,
+    mut images: ResMut<Assets<Image>>,
+    mut looks: ResMut<DuckLooks>,
+    game_assets: Option<Res<GameAssets>>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    let Some(image) =
+        duck_image_to_build(&game_assets, &images, &mut built_from, &mut image_events)
+    else {
+        return;
+    };
+    let mut silhouette = image.clone();
+    let size = image.size();
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let alpha = image.get_color_at(x, y).map_or(0.0, |color| color.alpha());
+            let _ = silhouette.set_color_at(x, y, SILHOUETTE_COLOR.with_alpha(alpha));
+        }
+    }
+    looks.set(DuckLook::Silhouette, images.add(silhouette));
+}
+
+/// Whether ducks are drawn as silhouettes right now
+fn silhouettes(settings: &Settings, time_of_day: Option<&TimeOfDay>) -> bool {
+    !settings.accessibility.high_visibility && time_of_day.is_some_and(TimeOfDay::is_night)
+}
+
+fn show_silhouette(
+    trigger: Trigger<OnAdd, Duck>,
+    settings: Res<Settings>,
+    time_of_day: Option<Res<TimeOfDay>>,
+    game_assets: Option<Res<GameAssets>>,
+    looks: Res<DuckLooks>,
+    mut duck_query: Query<&mut Sprite>,
+) {
+    let (true, Some(game_assets)) = (silhouettes(&settings, time_of_day.as_deref()), game_assets)
+    else {
+        return;
+    };
+    let Ok(mut sprite) = duck_query.get_mut(trigger.target()) else {
+        return;
+    };
+    if game_assets.look_of(&looks, &sprite) == Some(DuckLook::Plain) {
+        game_assets.show_look(&looks, &mut sprite, DuckLook::Silhouette);
+    }
+}
+
+// Ducks already out when night falls or the sun comes up change over too.
+// Only sprites showing the other look are touched, a duck flashing white
+// keeps its flash.
+fn switch_silhouettes(
+    settings: Res<Settings>,
+    time_of_day: Option<Res<TimeOfDay>>,
+    game_assets: Res<GameAssets>,
+    looks: Res<DuckLooks>,
+    mut duck_query: Query<&mut Sprite, With<Duck>>,
+) {
+    let Some(time_of_day) = time_of_day else {
+        return;
+    };
+    if !time_of_day.is_changed() && !settings.is_changed() && !looks.is_changed() {
+        return;
+    }
+    let (from, to) = if silhouettes(&settings, Some(&time_of_day)) {
+        (DuckLook::Plain, DuckLook::Silhouette)
+    } else {
+        (DuckLook::Silhouette, DuckLook::Plain)
+    };
+    for mut sprite in &mut duck_query {
+        if game_assets.look_of(&looks, &sprite) == Some(from) {
+            game_assets.show_look(&looks, &mut sprite, to);
+        }
+    }
+}
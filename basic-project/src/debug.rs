@@ -0,0 +1,228 @@
+//! Contributor tools, only built with the `debug` feature.
+//!
+//! - F1 shows the entity inspector
+//! - F2 draws duck hitboxes, the last few shots and the level's spawn zones,
+//!   and each duck's velocity and the path it'll take until it next changes
+//!   its pattern
+//! - F6 spawns a regular duck, F7 a fast one and F8 a boss
+//! - F9 toggles god mode, escaping ducks don't cost lives
+//! - F10 toggles infinite ammo
+//!
+//! Spawned ducks don't count towards the round so they can't end it early.
+
+use std::collections::VecDeque;
+
+use bevy::{input::common_conditions::input_toggle_active, prelude::*};
+use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
+
+use crate::{
+    be_the_duck::PlayerDuck,
+    boss::Boss,
+    duck_state::{Flying, Heading},
+    endless::{lose_lives, Lives, STARTING_LIVES},
+    events::ShotFired,
+    flight::FlightPattern,
+    level::Level,
+    mode::GameMode,
+    spawn::{Behaviour, SpawnDuckCommands, SpawnDuckEvent},
+    species::Species,
+    training::draw_hitboxes,
+    view::View,
+    weapon::EquippedWeapon,
+    wind::Wind,
+    Duck, ExtraDuck, GameAssets, GameState, PauseState, GRASS_Y,
+};
+
+const RECENT_SHOTS: usize = 8;
+const SHOT_COLOR: Color = Color::srgb(1.0, 1.0, 0.2);
+const SPAWN_ZONE_COLOR: Color = Color::srgb(0.2, 1.0, 0.4);
+const PATH_COLOR: Color = Color::srgb(0.4, 0.6, 1.0);
+const VELOCITY_COLOR: Color = Color::WHITE;
+// Seconds of movement the velocity arrow is as long as
+const VELOCITY_ARROW: f32 = 0.5;
+
+/// Where the last `RECENT_SHOTS` shots went, oldest first
+#[derive(Resource, Debug, Default)]
+struct RecentShots(VecDeque<Vec2>);
+
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DebugToggles {
+    pub god_mode: bool,
+    pub infinite_ammo: bool,
+}
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            EguiPlugin {
+                enable_multipass_for_primary_context: true,
+            },
+            WorldInspectorPlugin::new().run_if(input_toggle_active(false, KeyCode::F1)),
+        ))
+        .init_resource::<DebugToggles>()
+        .init_resource::<RecentShots>()
+        .add_systems(Update, switch_debug_toggles)
+        .add_systems(OnEnter(GameState::InGame), clear_recent_shots)
+        .add_systems(
+            Update,
+            (
+                record_shots.after(crate::run_simulation),
+                // Still drawn while paused, the gizmos only last a frame
+                (
+                    draw_hitboxes,
+                    draw_recent_shots,
+                    draw_spawn_zones,
+                    draw_flight_paths,
+                )
+                    .after(record_shots)
+                    .run_if(input_toggle_active(false, KeyCode::F2)),
+            )
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            Update,
+            (
+                spawn_debug_ducks,
+                keep_lives.before(lose_lives),
+                refill_ammo_every_frame,
+            )
+                .run_if(in_state(PauseState::Running)),
+        );
+    }
+}
+
+fn switch_debug_toggles(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut toggles: ResMut<DebugToggles>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F9) {
+        toggles.god_mode = !toggles.god_mode;
+        println!("God mode {}", if toggles.god_mode { "on" } else { "off" });
+    }
+    if keyboard_input.just_pressed(KeyCode::F10) {
+        toggles.infinite_ammo = !toggles.infinite_ammo;
+        println!(
+            "Infinite ammo {}",
+            if toggles.infinite_ammo { "on" } else { "off" }
+        );
+    }
+}
+
+fn spawn_debug_ducks(mut commands: Commands, keyboard_input: Res<ButtonInput<KeyCode>>) {
+    let species = if keyboard_input.just_pressed(KeyCode::F6) {
+        Species::Regular
+    } else if keyboard_input.just_pressed(KeyCode::F7) {
+        Species::Fast
+    } else if keyboard_input.just_pressed(KeyCode::F8) {
+        Species::Boss
+    } else {
+        return;
+    };
+    commands
+        .spawn_duck(SpawnDuckEvent {
+            species,
+            position: Vec2::new(0.0, -40.0),
+            behaviour: Behaviour::Fly(Heading::Right),
+        })
+        .insert(ExtraDuck);
+}
+
+// Topped up before the escapes are counted so they never reach zero
+fn keep_lives(toggles: Res<DebugToggles>, lives: Option<ResMut<Lives>>) {
+    if let (true, Some(mut lives)) = (toggles.god_mode, lives) {
+        lives.0 = lives.0.max(STARTING_LIVES);
+    }
+}
+
+fn refill_ammo_every_frame(
+    toggles: Res<DebugToggles>,
+    mut equipped: ResMut<EquippedWeapon>,
+    mut player_weapon_query: Query<&mut EquippedWeapon>,
+) {
+    if !toggles.infinite_ammo {
+        return;
+    }
+    equipped.refill();
+    for mut weapon in &mut player_weapon_query {
+        weapon.refill();
+    }
+}
+
+fn clear_recent_shots(mut recent_shots: ResMut<RecentShots>) {
+    recent_shots.0.clear();
+}
+
+// Kept whether they're drawn or not, so turning it on shows the shots that
+// went wrong
+fn record_shots(
+    mut shot_fired_reader: EventReader<ShotFired>,
+    mut recent_shots: ResMut<RecentShots>,
+) {
+    for shot in shot_fired_reader.read() {
+        if recent_shots.0.len() == RECENT_SHOTS {
+            recent_shots.0.pop_front();
+        }
+        recent_shots.0.push_back(shot.position);
+    }
+}
+
+// Older shots fade out
+fn draw_recent_shots(mut gizmos: Gizmos, recent_shots: Res<RecentShots>) {
+    let count = recent_shots.0.len();
+    for (i, position) in recent_shots.0.iter().enumerate() {
+        let color = SHOT_COLOR.with_alpha((i + 1) as f32 / count as f32);
+        gizmos.cross_2d(Isometry2d::from_translation(*position), 3.0, color);
+    }
+}
+
+// Zones are from the middle of the screen
+fn draw_spawn_zones(
+    mut gizmos: Gizmos,
+    game_assets: Res<GameAssets>,
+    levels: Res<Assets<Level>>,
+    mode: Res<GameMode>,
+    view: Res<View>,
+) {
+    let Some(level) = levels.get(game_assets.level(*mode)) else {
+        return;
+    };
+    for zone in &level.spawn_zones {
+        gizmos.line_2d(
+            Vec2::new(view.to_world(zone.min_x), GRASS_Y),
+            Vec2::new(view.to_world(zone.max_x), GRASS_Y),
+            SPAWN_ZONE_COLOR,
+        );
+    }
+}
+
+// The boss and the possessed duck don't fly by pattern, they're left out
+#[allow(clippy::type_complexity)]
+fn draw_flight_paths(
+    mut gizmos: Gizmos,
+    wind: Res<Wind>,
+    view: Res<View>,
+    duck_query: Query<
+        (&Transform, &Duck, &Flying, &FlightPattern),
+        (Without<Boss>, Without<PlayerDuck>),
+    >,
+) {
+    for (transform, duck, flying, pattern) in &duck_query {
+        let position = transform.translation.xy();
+        let x_speed = match flying.heading {
+            Heading::Right => duck.speed,
+            Heading::Left => -duck.speed,
+        } + wind.0;
+        gizmos.linestrip_2d(
+            pattern.planned_path(position, x_speed, duck.speed, &view),
+            PATH_COLOR,
+        );
+        let velocity = Vec2::new(x_speed, pattern.vertical_speed(duck.speed));
+        gizmos.arrow_2d(
+            position,
+            position + velocity * VELOCITY_ARROW,
+            VELOCITY_COLOR,
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! A wooden decoy the player can set down on the grass once a round, with
+//! the middle mouse button, or whichever is bound to it, or X. Flying ducks
+//! every so often swoop down at it, and one that gets there sits on it for a
+//! moment, which makes for an easy shot if the player is ready for it.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use rand::Rng;
+
+use crate::{
+    duck_call::Lured,
+    duck_state::{DuckStateCommands, Flying, Perched},
+    events::{PlayerId, RoundEnded},
+    flight,
+    rng::GameRng,
+    settings::{MouseAction, Settings},
+    sinden::PlayArea,
+    spatial::SpatialIndex,
+    sprite_sheet::SpriteSheet,
+    time_scale::GameTimeScale,
+    Dead, FrameSet, GameAssets, GameInputEvent, GameState, PauseState, RoundPhase, Simulation,
+    SimulationDriver,
+};
+
+const DECOY_COLOR: Color = Color::srgb(0.7, 0.45, 0.25);
+// Sitting on top of the grass, in front of the swaying blades
+const DECOY_Y: f32 = -26.0;
+const DECOY_Z: f32 = 1.6;
+// Seconds between two rolls for which ducks go for it
+const SWOOP_EVERY: f32 = 1.5;
+const SWOOP_CHANCE: f64 = 0.25;
+const SWOOP_SECONDS: f32 = 1.5;
+// How close to the spot above the decoy a swooping duck has to get to sit
+const PERCH_RADIUS: f32 = 4.0;
+// Next to the weapon icon on the dirt, at half size
+const ICON_POSITION: Vec3 = Vec3::new(-16.0, -73.0, 2.0);
+
+/// Whether this round's decoy is still in hand
+#[derive(Resource)]
+pub struct DecoyStock {
+    pub available: bool,
+}
+
+impl Default for DecoyStock {
+    fn default() -> Self {
+        Self { available: true }
+    }
+}
+
+#[derive(Component)]
+pub struct Decoy {
+    swoop: Timer,
+}
+
+#[derive(Component)]
+struct DecoyIcon;
+
+pub struct DecoyPlugin;
+
+impl Plugin for DecoyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DecoyStock>()
+            .add_systems(OnEnter(GameState::InGame), (reset_decoys, spawn_decoy_icon))
+            .add_systems(
+                Update,
+                handle_decoy_input.in_set(FrameSet::Input).run_if(
+                    in_state(RoundPhase::Playing)
+                        .and(in_state(PauseState::Running))
+                        .and(not(resource_equals(SimulationDriver::Rollback))),
+                ),
+            )
+            .add_systems(
+                Update,
+                update_decoy_icon.run_if(in_state(PauseState::Running)),
+            )
+            .add_systems(
+                Simulation,
+                (place_decoys, lure_to_decoys, perch_on_decoys, clear_decoys)
+                    .chain()
+                    .before(crate::move_ducks),
+            )
+            // Joins the index after move_ducks has rebuilt it
+            .add_systems(Simulation, rest_perched_ducks.after(crate::move_ducks));
+    }
+}
+
+fn reset_decoys(mut stock: ResMut<DecoyStock>) {
+    *stock = DecoyStock::default();
+}
+
+fn decoy_sprite(game_assets: &GameAssets, sheets: &Assets<SpriteSheet>) -> Sprite {
+    Sprite {
+        color: DECOY_COLOR,
+        ..Sprite::from_atlas_image(
+            game_assets.duck_image().clone(),
+            TextureAtlas {
+                layout: game_assets.duck_layout().clone(),
+                index: game_assets.duck_frame(sheets, "duck_fly_0").unwrap_or(0),
+            },
+        )
+    }
+}
+
+fn spawn_decoy_icon(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    sheets: Res<Assets<SpriteSheet>>,
+) {
+    commands.spawn((
+        decoy_sprite(&game_assets, &sheets),
+        Transform::from_translation(ICON_POSITION).with_scale(Vec3::splat(0.5)),
+        DecoyIcon,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+// Faded out once it's been used
+fn update_decoy_icon(stock: Res<DecoyStock>, mut icon_query: Query<&mut Sprite, With<DecoyIcon>>) {
+    if !stock.is_changed() {
+        return;
+    }
+    for mut sprite in &mut icon_query {
+        sprite
+            .color
+            .set_alpha(if stock.available { 1.0 } else { 0.3 });
+    }
+}
+
+fn handle_decoy_input(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    play_area: Res<PlayArea>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut game_input_event_writer: EventWriter<GameInputEvent>,
+) {
+    if !mouse_input.just_pressed(settings.mouse.button(MouseAction::Decoy))
+        && !keyboard_input.just_pressed(KeyCode::KeyX)
+    {
+        return;
+    }
+    let Some(position) = window_query
+        .single()
+        .ok()
+        .and_then(|win| win.cursor_position())
+    else {
+        return;
+    };
+    let position = play_area.to_world(position);
+    game_input_event_writer.write(GameInputEvent::Decoy(PlayerId(0), position));
+}
+
+// Only the x of the cursor counts, it always goes on the grass
+fn place_decoys(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    sheets: Res<Assets<SpriteSheet>>,
+    mut stock: ResMut<DecoyStock>,
+    mut game_input_event_reader: EventReader<GameInputEvent>,
+) {
+    for event in game_input_event_reader.read() {
+        let GameInputEvent::Decoy(_, position) = *event else {
+            continue;
+        };
+        if !stock.available {
+            continue;
+        }
+        stock.available = false;
+        let x = position.x.clamp(-112.0, 112.0);
+        commands.spawn((
+            decoy_sprite(&game_assets, &sheets),
+            Transform::from_xyz(x, DECOY_Y, DECOY_Z),
+            Decoy {
+                swoop: Timer::from_seconds(SWOOP_EVERY, TimerMode::Repeating),
+            },
+            StateScoped(GameState::InGame),
+        ));
+    }
+}
+
+// Rolled from the game rng like the flight patterns, so it stays the same
+// on a replay
+fn lure_to_decoys(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut rng: ResMut<GameRng>,
+    mut decoy_query: Query<(&Transform, &mut Decoy)>,
+    duck_query: Query<Entity, (With<Flying>, Without<Lured>)>,
+) {
+    let delta = time_scale.delta(&time);
+    for (transform, mut decoy) in &mut decoy_query {
+        if !decoy.swoop.tick(delta).just_finished() {
+            continue;
+        }
+        // Low over it, ducks don't go down into the grass
+        let target = Vec2::new(transform.translation.x, flight::FLOOR);
+        for entity in &duck_query {
+            if rng.gen_bool(SWOOP_CHANCE) {
+                commands
+                    .entity(entity)
+                    .insert(Lured::new(target, SWOOP_SECONDS));
+            }
+        }
+    }
+}
+
+// Ducks swooping at a decoy that get right over it settle there
+#[allow(clippy::type_complexity)]
+fn perch_on_decoys(
+    mut commands: Commands,
+    decoy_query: Query<&Transform, With<Decoy>>,
+    mut duck_query: Query<
+        (Entity, &mut Transform, &Flying),
+        (With<Lured>, Without<Decoy>, Without<Dead>),
+    >,
+) {
+    for decoy in &decoy_query {
+        let spot = Vec2::new(decoy.translation.x, flight::FLOOR);
+        for (entity, mut transform, flying) in &mut duck_query {
+            if transform.translation.xy().distance(spot) > PERCH_RADIUS {
+                continue;
+            }
+            transform.translation.x = spot.x;
+            transform.translation.y = spot.y;
+            commands
+                .entity(entity)
+                .remove::<Lured>()
+                .set_duck_state(Perched::new(flying.heading));
+        }
+    }
+}
+
+// Takes off the way it came in once it's had its rest
+fn rest_perched_ducks(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut spatial_index: ResMut<SpatialIndex>,
+    mut duck_query: Query<(Entity, &Transform, &mut Perched), Without<Dead>>,
+) {
+    let delta = time_scale.delta(&time);
+    for (entity, transform, mut perched) in &mut duck_query {
+        spatial_index.insert(entity, transform.translation.xy());
+        if perched.rest.tick(delta).finished() {
+            commands.entity(entity).set_duck_state(Flying {
+                heading: perched.heading,
+            });
+        }
+    }
+}
+
+// Picked back up at the end of the round, with a new one for the next
+fn clear_decoys(
+    mut commands: Commands,
+    mut stock: ResMut<DecoyStock>,
+    decoy_query: Query<Entity, With<Decoy>>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+) {
+    if round_ended_reader.read().count() == 0 {
+        return;
+    }
+    stock.available = true;
+    for entity in &decoy_query {
+        commands.entity(entity).despawn();
+    }
+}
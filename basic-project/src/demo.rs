@@ -0,0 +1,134 @@
+//! Attract mode. Left alone on the title screen for a while, the game starts
+//! a classic game for the bot to play with DEMO over it. Any input, or the
+//! bot losing, goes back to the menu.
+//!
+//! Demos never reach the game over screen, so nothing the bot does ends up
+//! on the high score tables, and it doesn't earn achievements.
+
+use bevy::{input::mouse::AccumulatedMouseMotion, prelude::*};
+
+use crate::{
+    events::ResetGame, locale::Locale, menu::start_game, mode::GameMode, GameState, LaunchOptions,
+};
+
+const IDLE_SECONDS: f32 = 15.0;
+
+/// Present while the game being played is a demo
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct Demo;
+
+/// Time the title screen has gone without input
+#[derive(Resource, Deref, DerefMut)]
+pub struct MenuIdle(Timer);
+
+impl Default for MenuIdle {
+    fn default() -> Self {
+        Self(Timer::from_seconds(IDLE_SECONDS, TimerMode::Once))
+    }
+}
+
+pub struct DemoPlugin;
+
+impl Plugin for DemoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuIdle>()
+            .add_systems(OnEnter(GameState::Menu), reset_menu_idle)
+            .add_systems(
+                OnEnter(GameState::InGame),
+                setup_demo_text.run_if(resource_exists::<Demo>),
+            )
+            .add_systems(Update, start_demo.run_if(in_state(GameState::Menu)))
+            .add_systems(
+                Update,
+                stop_demo.run_if(in_state(GameState::InGame).and(resource_exists::<Demo>)),
+            )
+            // After everything that could have sent the game to game over
+            .add_systems(Last, skip_demo_game_over.run_if(resource_exists::<Demo>));
+    }
+}
+
+/// Whether anything was pressed or moved this frame
+fn any_input(
+    keyboard_input: &ButtonInput<KeyCode>,
+    mouse_input: &ButtonInput<MouseButton>,
+    mouse_motion: &AccumulatedMouseMotion,
+    gamepad_query: &Query<&Gamepad>,
+) -> bool {
+    keyboard_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+        || mouse_motion.delta != Vec2::ZERO
+        || gamepad_query
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+}
+
+fn reset_menu_idle(mut idle: ResMut<MenuIdle>) {
+    idle.reset();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn start_demo(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    launch: Res<LaunchOptions>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    gamepad_query: Query<&Gamepad>,
+    mut idle: ResMut<MenuIdle>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if any_input(&keyboard_input, &mouse_input, &mouse_motion, &gamepad_query) {
+        idle.reset();
+        return;
+    }
+    if !idle.tick(time.delta()).just_finished() {
+        return;
+    }
+    start_game(&mut commands, GameMode::Classic, &launch);
+    commands.insert_resource(Demo);
+    next_state.set(GameState::InGame);
+}
+
+fn setup_demo_text(mut commands: Commands, locale: Res<Locale>) {
+    commands.spawn((
+        Text::new(locale.get("demo.title")),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        StateScoped(GameState::InGame),
+    ));
+}
+
+fn stop_demo(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    gamepad_query: Query<&Gamepad>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if any_input(&keyboard_input, &mouse_input, &mouse_motion, &gamepad_query) {
+        end_demo(&mut commands, &mut next_state);
+    }
+}
+
+fn skip_demo_game_over(mut commands: Commands, mut next_state: ResMut<NextState<GameState>>) {
+    if matches!(*next_state, NextState::Pending(GameState::GameOver)) {
+        end_demo(&mut commands, &mut next_state);
+    }
+}
+
+fn end_demo(commands: &mut Commands, next_state: &mut NextState<GameState>) {
+    commands.trigger(ResetGame);
+    commands.remove_resource::<Demo>();
+    next_state.set(GameState::Menu);
+}
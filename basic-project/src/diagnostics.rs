@@ -0,0 +1,128 @@
+//! F3 overlay with the frame rate, frame time, how many ducks are alive and
+//! how many gameplay events go through per second. It's regular UI so it
+//! scales with the window like the rest of the game.
+
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin,
+        RegisterDiagnostic,
+    },
+    prelude::*,
+};
+
+use crate::{
+    events::{DuckDamaged, DuckEscaped, DuckHit, DuckSpawned, RoundEnded, ShotFired},
+    Duck,
+};
+
+pub const DUCK_COUNT: DiagnosticPath = DiagnosticPath::const_new("duck_count");
+pub const GAME_EVENTS: DiagnosticPath = DiagnosticPath::const_new("game_events_per_second");
+
+#[derive(Component)]
+pub struct DiagnosticsOverlay;
+
+pub struct DiagnosticsPlugin;
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+        app.register_diagnostic(Diagnostic::new(DUCK_COUNT))
+            .register_diagnostic(Diagnostic::new(GAME_EVENTS))
+            .add_systems(Startup, setup_diagnostics_overlay)
+            .add_systems(
+                Update,
+                (
+                    measure_ducks,
+                    measure_game_events,
+                    toggle_diagnostics_overlay,
+                    update_diagnostics_overlay,
+                ),
+            );
+    }
+}
+
+fn setup_diagnostics_overlay(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: 6.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(2.0),
+            left: Val::Px(2.0),
+            padding: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        GlobalZIndex(20),
+        Visibility::Hidden,
+        DiagnosticsOverlay,
+    ));
+}
+
+fn measure_ducks(mut diagnostics: Diagnostics, duck_query: Query<(), With<Duck>>) {
+    diagnostics.add_measurement(&DUCK_COUNT, || duck_query.iter().count() as f64);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn measure_game_events(
+    mut diagnostics: Diagnostics,
+    time: Res<Time>,
+    mut duck_spawned_reader: EventReader<DuckSpawned>,
+    mut shot_fired_reader: EventReader<ShotFired>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+    mut duck_damaged_reader: EventReader<DuckDamaged>,
+    mut duck_escaped_reader: EventReader<DuckEscaped>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+) {
+    let count = duck_spawned_reader.read().count()
+        + shot_fired_reader.read().count()
+        + duck_hit_reader.read().count()
+        + duck_damaged_reader.read().count()
+        + duck_escaped_reader.read().count()
+        + round_ended_reader.read().count();
+    let delta = time.delta_secs_f64();
+    if delta > 0.0 {
+        diagnostics.add_measurement(&GAME_EVENTS, || count as f64 / delta);
+    }
+}
+
+fn toggle_diagnostics_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlay_query: Query<&mut Visibility, With<DiagnosticsOverlay>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F3) {
+        return;
+    }
+    for mut visibility in &mut overlay_query {
+        visibility.toggle_visible_hidden();
+    }
+}
+
+fn update_diagnostics_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    mut overlay_query: Query<(&mut Text, &Visibility), With<DiagnosticsOverlay>>,
+) {
+    let smoothed = |path: &DiagnosticPath| {
+        diagnostics
+            .get(path)
+            .and_then(Diagnostic::smoothed)
+            .unwrap_or_default()
+    };
+    for (mut text, visibility) in &mut overlay_query {
+        if visibility == Visibility::Hidden {
+            continue;
+        }
+        text.0 = format!(
+            "FPS {:.0}\nFRAME {:.2} MS\nDUCKS {:.0}\nEVENTS/S {:.1}",
+            smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+            smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+            smoothed(&DUCK_COUNT),
+            smoothed(&GAME_EVENTS),
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Discord Rich Presence, shows the mode, round and score on the player's
+//! Discord profile. Needs `DISCORD_CLIENT_ID` set to the id of a Discord
+//! application, and does nothing when Discord isn't running.
+
+use bevy::prelude::*;
+use discord_rich_presence::{activity::Activity, DiscordIpc, DiscordIpcClient};
+
+use crate::{events::RoundEnded, mode::GameMode, score::Score, GameState, Round};
+
+#[derive(Resource)]
+pub struct DiscordPresence {
+    client: DiscordIpcClient,
+    connected: bool,
+}
+
+impl DiscordPresence {
+    fn publish(&mut self, details: &str, state: &str) {
+        // Discord may have been started after the game, try again each time
+        if !self.connected {
+            self.connected = self.client.connect().is_ok();
+        }
+        if !self.connected {
+            return;
+        }
+        let activity = Activity::new().details(details).state(state);
+        if let Err(error) = self.client.set_activity(activity) {
+            println!("Could not update Discord presence: {error}");
+            self.connected = false;
+        }
+    }
+}
+
+pub struct DiscordPlugin;
+
+impl Plugin for DiscordPlugin {
+    fn build(&self, app: &mut App) {
+        let Ok(client_id) = std::env::var("DISCORD_CLIENT_ID") else {
+            return;
+        };
+        app.insert_resource(DiscordPresence {
+            client: DiscordIpcClient::new(client_id),
+            connected: false,
+        })
+        .add_systems(OnEnter(GameState::Menu), show_menu_presence)
+        .add_systems(
+            OnEnter(GameState::InGame),
+            show_game_presence.after(crate::setup_world),
+        )
+        .add_systems(
+            Update,
+            show_round_presence.run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(OnEnter(GameState::GameOver), show_game_over_presence)
+        .add_systems(Last, clear_presence_on_exit);
+    }
+}
+
+fn show_menu_presence(mut presence: ResMut<DiscordPresence>) {
+    presence.publish("In the menu", "Picking a mode");
+}
+
+fn show_game_presence(
+    mut presence: ResMut<DiscordPresence>,
+    mode: Res<GameMode>,
+    round: Res<Round>,
+) {
+    presence.publish(mode.name(), &format!("Round {}", round.number));
+}
+
+// The round resource has already moved on when this runs, the event says
+// which one just ended
+fn show_round_presence(
+    mut presence: ResMut<DiscordPresence>,
+    mode: Res<GameMode>,
+    score: Res<Score>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+) {
+    if let Some(ended) = round_ended_reader.read().last() {
+        presence.publish(
+            mode.name(),
+            &format!("Round {} - {} points", ended.round + 1, score.0),
+        );
+    }
+}
+
+fn show_game_over_presence(
+    mut presence: ResMut<DiscordPresence>,
+    mode: Res<GameMode>,
+    score: Res<Score>,
+) {
+    presence.publish(mode.name(), &format!("Game over - {} points", score.0));
+}
+
+fn clear_presence_on_exit(
+    mut presence: ResMut<DiscordPresence>,
+    mut app_exit_reader: EventReader<AppExit>,
+) {
+    if app_exit_reader.read().next().is_some() && presence.connected {
+        let _ = presence.client.clear_activity();
+        let _ = presence.client.close();
+        presence.connected = false;
+    }
+}
@@ -0,0 +1,174 @@
+//! Which monitor the game goes fullscreen on and how, from
+//! `window.display` and `window.monitor`. Borderless covers the monitor
+//! with a window the size of the desktop, exclusive takes the monitor over
+//! at its current resolution, which can be quicker to draw but slower to
+//! switch away from.
+//!
+//! Monitors are numbered the way the OS lists them. The number is checked
+//! once they've been listed at startup, one that isn't there any more, like
+//! a second screen that's been unplugged, goes back to the first. Left
+//! windowed, the settings don't touch the window at startup, so
+//! `--fullscreen` still works.
+//!
+//! Windowed, `window.scale` picks the size, a whole multiple of the NES
+//! resolution so every pixel comes out the same size. The window is always
+//! 256 by 240 logical pixels, only the scale factor changes, so the field,
+//! the HUD and the aim all stay as they are. `ui.scale` sizes the menus and
+//! overlays on their own, through `UiScale`.
+
+use std::{fmt, str::FromStr};
+
+use bevy::{
+    prelude::*,
+    window::{Monitor, PrimaryWindow, VideoModeSelection, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+const SCREEN_SIZE: UVec2 = UVec2::new(256, 240);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Exclusive,
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [
+        DisplayMode::Windowed,
+        DisplayMode::Borderless,
+        DisplayMode::Exclusive,
+    ];
+
+    /// The one after this in `ALL`, wrapping round
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|mode| *mode == self);
+        Self::ALL[i.map_or(0, |i| (i + 1) % Self::ALL.len())]
+    }
+
+    /// The window mode for this on monitor number `monitor`
+    fn window_mode(self, monitor: usize) -> WindowMode {
+        let monitor = MonitorSelection::Index(monitor);
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen(monitor),
+            DisplayMode::Exclusive => WindowMode::Fullscreen(monitor, VideoModeSelection::Current),
+        }
+    }
+}
+
+impl FromStr for DisplayMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "windowed" => Ok(DisplayMode::Windowed),
+            "borderless" => Ok(DisplayMode::Borderless),
+            "exclusive" => Ok(DisplayMode::Exclusive),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for DisplayMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DisplayMode::Windowed => "windowed",
+            DisplayMode::Borderless => "borderless",
+            DisplayMode::Exclusive => "exclusive",
+        })
+    }
+}
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                apply_display,
+                apply_window_scale,
+                apply_ui_scale.run_if(resource_changed::<Settings>),
+            ),
+        );
+    }
+}
+
+// Only when the display or the monitor change, anything else in the
+// settings changing leaves the window as it is
+fn apply_display(
+    mut settings: ResMut<Settings>,
+    mut applied: Local<Option<(DisplayMode, usize)>>,
+    monitor_query: Query<(), With<Monitor>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    // Winit lists the monitors a frame or so in, nothing to check against
+    // before then
+    let monitors = monitor_query.iter().count();
+    if monitors == 0 {
+        return;
+    }
+    if applied.is_none() && settings.window.monitor >= monitors {
+        println!(
+            "Monitor {} isn't there, using the first of {monitors}",
+            settings.window.monitor
+        );
+        settings.window.monitor = 0;
+    }
+    let wanted = (settings.window.display, settings.window.monitor);
+    let first = applied.is_none();
+    if *applied == Some(wanted) {
+        return;
+    }
+    *applied = Some(wanted);
+    if first && wanted.0 == DisplayMode::Windowed {
+        return;
+    }
+    let Ok(mut window) = window_query.single_mut() else {
+        return;
+    };
+    // Set from the console it hasn't been checked, it can't go past the last
+    let monitor = wanted.1.min(monitors - 1);
+    let mode = wanted.0.window_mode(monitor);
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
+
+// Like the display, only a change is applied, the window is opened at the
+// right size in `main` unless `--scale` says otherwise. Fullscreen keeps
+// its size, a change made then waits until it's windowed again.
+fn apply_window_scale(
+    settings: Res<Settings>,
+    mut applied: Local<Option<u32>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window_query.single_mut() else {
+        return;
+    };
+    let scale = settings.window.scale;
+    if window.mode != WindowMode::Windowed || *applied == Some(scale) {
+        return;
+    }
+    let first = applied.replace(scale).is_none();
+    if first {
+        return;
+    }
+    window
+        .resolution
+        .set_scale_factor_override(Some(scale as f32));
+    window
+        .resolution
+        .set_physical_resolution(SCREEN_SIZE.x * scale, SCREEN_SIZE.y * scale);
+}
+
+fn apply_ui_scale(settings: Res<Settings>, mut ui_scale: ResMut<UiScale>) {
+    if ui_scale.0 != settings.ui.scale {
+        ui_scale.0 = settings.ui.scale;
+    }
+}
@@ -0,0 +1,193 @@
+//! The dog picking up after the player. It waits behind the grass and pops
+//! up where a shot duck landed, holding it up, or two of them when a second
+//! one comes down close behind the first. It laughs, flicking between its
+//! two frames, while it's up.
+
+use bevy::prelude::*;
+
+use crate::{
+    animation::{AnimationClip, ClipId, ClipMode, SpriteAnimation},
+    events::DuckLanded,
+    sprite_sheet::SpriteSheet,
+    time_scale::GameTimeScale,
+    view::View,
+    GameAssets, GameState, PauseState,
+};
+
+// Where the grass is low enough for the dog to come up through, the tree and
+// the bush are in the way past these
+const MIN_X: f32 = -48.0;
+const MAX_X: f32 = 72.0;
+// All of it behind the grass, and most of it above
+const HIDDEN_Y: f32 = -48.0;
+const SHOWN_Y: f32 = -8.0;
+// Pixels per second going up and back down
+const POP_SPEED: f32 = 120.0;
+// Seconds after a landing before it comes up, a duck landing in that time
+// gets picked up too
+const CATCH_WINDOW: f32 = 0.5;
+const HOLD_SECONDS: f32 = 1.0;
+const MAX_HELD: usize = 2;
+
+const IDLE: AnimationClip = AnimationClip {
+    id: ClipId("dog_idle"),
+    first: 0,
+    last: 0,
+    fps: 0.0,
+    mode: ClipMode::Once,
+};
+const LAUGH: AnimationClip = AnimationClip {
+    id: ClipId("dog_laugh"),
+    first: 0,
+    last: 1,
+    fps: 6.0,
+    mode: ClipMode::Loop,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum DogPhase {
+    #[default]
+    Hidden,
+    Waiting,
+    Rising,
+    Holding,
+    Sinking,
+}
+
+#[derive(Component, Default)]
+pub struct Dog {
+    phase: DogPhase,
+    timer: Timer,
+    // Where ducks landed that it still has to pick up
+    pending: Vec<f32>,
+}
+
+/// One of the ducks the dog is holding up
+#[derive(Component)]
+struct HeldDuck;
+
+pub struct DogPlugin;
+
+impl Plugin for DogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::InGame), spawn_dog)
+            .add_systems(
+                Update,
+                (collect_landings, retrieve_ducks)
+                    .chain()
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn spawn_dog(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.spawn((
+        Sprite::from_atlas_image(
+            game_assets.dog_image().clone(),
+            TextureAtlas {
+                layout: game_assets.dog_layout().clone(),
+                index: 0,
+            },
+        ),
+        // Behind the background, it only shows through the sky
+        Transform::from_xyz(0.0, HIDDEN_Y, 0.0),
+        SpriteAnimation::new(IDLE),
+        Dog::default(),
+        StateScoped(GameState::InGame),
+    ));
+}
+
+fn collect_landings(
+    view: Res<View>,
+    mut duck_landed_reader: EventReader<DuckLanded>,
+    mut dog_query: Query<&mut Dog>,
+) {
+    for landed in duck_landed_reader.read() {
+        for mut dog in &mut dog_query {
+            let x = landed
+                .position
+                .x
+                .clamp(view.to_world(MIN_X), view.to_world(MAX_X));
+            dog.pending.push(x);
+        }
+    }
+}
+
+fn retrieve_ducks(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    game_assets: Res<GameAssets>,
+    sheets: Res<Assets<SpriteSheet>>,
+    mut dog_query: Query<(Entity, &mut Dog, &mut Transform, &mut SpriteAnimation)>,
+    held_query: Query<Entity, With<HeldDuck>>,
+) {
+    let delta = time_scale.delta_secs(&time);
+    for (entity, mut dog, mut transform, mut animation) in &mut dog_query {
+        match dog.phase {
+            DogPhase::Hidden => {
+                let Some(&x) = dog.pending.first() else {
+                    continue;
+                };
+                transform.translation.x = x;
+                dog.timer = Timer::from_seconds(CATCH_WINDOW, TimerMode::Once);
+                dog.phase = DogPhase::Waiting;
+            }
+            DogPhase::Waiting => {
+                if !dog.timer.tick(time_scale.delta(&time)).finished() {
+                    continue;
+                }
+                let held = dog.pending.len().min(MAX_HELD);
+                dog.pending.drain(..held);
+                let frame = game_assets.duck_frame(&sheets, "duck_fall").unwrap_or(0);
+                commands.entity(entity).with_children(|parent| {
+                    // One in each paw, or the one in the middle
+                    for i in 0..held {
+                        let x = if held == 1 {
+                            0.0
+                        } else {
+                            i as f32 * 24.0 - 12.0
+                        };
+                        parent.spawn((
+                            Sprite::from_atlas_image(
+                                game_assets.duck_image().clone(),
+                                TextureAtlas {
+                                    layout: game_assets.duck_layout().clone(),
+                                    index: frame,
+                                },
+                            ),
+                            Transform::from_xyz(x, 20.0, 0.1),
+                            HeldDuck,
+                        ));
+                    }
+                });
+                animation.play(LAUGH);
+                dog.phase = DogPhase::Rising;
+            }
+            DogPhase::Rising => {
+                transform.translation.y =
+                    (transform.translation.y + POP_SPEED * delta).min(SHOWN_Y);
+                if transform.translation.y >= SHOWN_Y {
+                    dog.timer = Timer::from_seconds(HOLD_SECONDS, TimerMode::Once);
+                    dog.phase = DogPhase::Holding;
+                }
+            }
+            DogPhase::Holding => {
+                if dog.timer.tick(time_scale.delta(&time)).finished() {
+                    dog.phase = DogPhase::Sinking;
+                }
+            }
+            DogPhase::Sinking => {
+                transform.translation.y =
+                    (transform.translation.y - POP_SPEED * delta).max(HIDDEN_Y);
+                if transform.translation.y <= HIDDEN_Y {
+                    for held in &held_query {
+                        commands.entity(held).despawn();
+                    }
+                    animation.play(IDLE);
+                    dog.phase = DogPhase::Hidden;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! A duck call on the right mouse button, or whichever is bound to it, or
+//! C. Flying ducks close to the cursor turn towards it for a moment, which
+//! lets a good player line up a shot, and then the call needs a few seconds
+//! before it works again.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    duck_state::{Flying, Heading},
+    events::PlayerId,
+    settings::{MouseAction, Settings},
+    sinden::PlayArea,
+    spatial::SpatialIndex,
+    time_scale::GameTimeScale,
+    FrameSet, GameInputEvent, GameState, PauseState, RoundPhase, Simulation, SimulationDriver,
+};
+
+// Ducks further away than this don't hear it
+const CALL_RADIUS: f32 = 64.0;
+const LURE_SECONDS: f32 = 1.0;
+const COOLDOWN: f32 = 5.0;
+
+/// Flies towards `target` instead of following its flight pattern until
+/// the timer runs out
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Lured {
+    pub target: Vec2,
+    timer: Timer,
+}
+
+impl Lured {
+    pub fn new(target: Vec2, seconds: f32) -> Self {
+        Self {
+            target,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+
+    /// Whichever way gets it to the target from `x`
+    pub fn heading(&self, x: f32) -> Heading {
+        if self.target.x < x {
+            Heading::Left
+        } else {
+            Heading::Right
+        }
+    }
+
+    /// Up is positive, never faster than the duck's `speed`
+    pub fn vertical_speed(&self, y: f32, speed: f32) -> f32 {
+        ((self.target.y - y) * 2.0).clamp(-speed, speed)
+    }
+}
+
+#[derive(Resource)]
+pub struct DuckCall {
+    cooldown: Timer,
+}
+
+impl Default for DuckCall {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(COOLDOWN, TimerMode::Once);
+        // Ready from the start
+        let duration = cooldown.duration();
+        cooldown.tick(duration);
+        Self { cooldown }
+    }
+}
+
+impl DuckCall {
+    pub fn ready(&self) -> bool {
+        self.cooldown.finished()
+    }
+}
+
+pub struct DuckCallPlugin;
+
+impl Plugin for DuckCallPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DuckCall>()
+            .add_systems(OnEnter(GameState::InGame), reset_duck_call)
+            .add_systems(
+                Update,
+                handle_call_input.in_set(FrameSet::Input).run_if(
+                    in_state(RoundPhase::Playing)
+                        .and(in_state(PauseState::Running))
+                        .and(not(resource_equals(SimulationDriver::Rollback))),
+                ),
+            )
+            .add_systems(
+                Simulation,
+                (blow_duck_calls, wear_off_lures)
+                    .chain()
+                    .before(crate::move_ducks),
+            );
+    }
+}
+
+fn reset_duck_call(mut duck_call: ResMut<DuckCall>) {
+    *duck_call = DuckCall::default();
+}
+
+fn handle_call_input(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    play_area: Res<PlayArea>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut game_input_event_writer: EventWriter<GameInputEvent>,
+) {
+    if !mouse_input.just_pressed(settings.mouse.button(MouseAction::Call))
+        && !keyboard_input.just_pressed(KeyCode::KeyC)
+    {
+        return;
+    }
+    let Some(position) = window_query
+        .single()
+        .ok()
+        .and_then(|win| win.cursor_position())
+    else {
+        return;
+    };
+    let position = play_area.to_world(position);
+    game_input_event_writer.write(GameInputEvent::Call(PlayerId(0), position));
+}
+
+fn blow_duck_calls(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    spatial_index: Res<SpatialIndex>,
+    mut duck_call: ResMut<DuckCall>,
+    duck_query: Query<&Transform, With<Flying>>,
+    mut game_input_event_reader: EventReader<GameInputEvent>,
+) {
+    duck_call.cooldown.tick(time_scale.delta(&time));
+    for event in game_input_event_reader.read() {
+        let GameInputEvent::Call(_, position) = *event else {
+            continue;
+        };
+        if !duck_call.ready() {
+            continue;
+        }
+        duck_call.cooldown.reset();
+        let area = Rect::from_center_half_size(position, Vec2::splat(CALL_RADIUS));
+        for entity in spatial_index.query(area) {
+            let Ok(transform) = duck_query.get(entity) else {
+                continue;
+            };
+            if transform.translation.xy().distance(position) <= CALL_RADIUS {
+                commands
+                    .entity(entity)
+                    .insert(Lured::new(position, LURE_SECONDS));
+            }
+        }
+    }
+}
+
+fn wear_off_lures(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut lured_query: Query<(Entity, &mut Lured)>,
+) {
+    let delta = time_scale.delta(&time);
+    for (entity, mut lured) in &mut lured_query {
+        if lured.timer.tick(delta).finished() {
+            commands.entity(entity).remove::<Lured>();
+        }
+    }
+}
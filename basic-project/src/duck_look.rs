@@ -0,0 +1,41 @@
+//! The recoloured copies of the skin's duck sheet a duck can be drawn with
+//! instead of its own, the shot flash, high visibility and the night
+//! silhouettes. Each is built on the CPU by its own module and kept in
+//! [`DuckLooks`].
+//!
+//! A duck changes look through `GameAssets::show_look`, which once the
+//! sheets are packed only moves its atlas over to where that copy sits in
+//! the packed image, see `packing`.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DuckLook {
+    /// The skin's sheet as it is
+    #[default]
+    Plain,
+    /// Solid white, see `shot_flash`
+    Flash,
+    /// See `high_visibility`
+    HighVisibility,
+    /// Flat black, see `day_night`
+    Silhouette,
+}
+
+/// The copies built so far, a look that isn't here can't be shown yet
+#[derive(Resource, Default)]
+pub struct DuckLooks(HashMap<DuckLook, Handle<Image>>);
+
+impl DuckLooks {
+    pub fn get(&self, look: DuckLook) -> Option<&Handle<Image>> {
+        self.0.get(&look)
+    }
+
+    pub fn set(&mut self, look: DuckLook, image: Handle<Image>) {
+        self.0.insert(look, image);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (DuckLook, &Handle<Image>)> {
+        self.0.iter().map(|(look, image)| (*look, image))
+    }
+}
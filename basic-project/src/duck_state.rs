@@ -0,0 +1,109 @@
+//! What a duck is up to. Each state is its own component so systems only
+//! query the ducks in the state they care about, and a duck is only ever in
+//! one of them at a time. Going from one state to the next is always done
+//! through [`DuckStateCommands::set_duck_state`].
+
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use serde::{Deserialize, Serialize};
+
+/// Every duck state, a new state has to be added here so it gets cleared on
+/// the way out
+type DuckStates = (Launching, Flying, Escaping, Perched, Dying, Falling);
+
+/// The states a duck can be shot in
+pub type Shootable = Or<(With<Flying>, With<Escaping>, With<Perched>)>;
+
+// Seconds a shot duck hangs in the air before it drops, like on the NES
+const SHOT_PAUSE: f32 = 0.5;
+// Seconds a duck sits on the decoy before it takes off again
+const PERCH_SECONDS: f32 = 1.0;
+
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Heading {
+    #[default]
+    Left,
+    Right,
+}
+
+impl Heading {
+    /// Whichever way gets it to the middle of the field from `x`
+    pub fn towards_center(x: f32) -> Self {
+        if x < 0.0 {
+            Heading::Right
+        } else {
+            Heading::Left
+        }
+    }
+
+    // The sprite looks right
+    pub fn flip_x(&self) -> bool {
+        *self == Heading::Left
+    }
+}
+
+/// Bursting up out of the grass, hidden behind it so it can't be shot yet.
+/// It flies off towards `heading` once it's out.
+#[derive(Component, Default, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Launching {
+    pub heading: Heading,
+}
+
+/// Flapping around the field, bouncing off the sides
+#[derive(Component, Default, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Flying {
+    pub heading: Heading,
+}
+
+/// Done for the round, climbing straight out of the top of the screen. It
+/// can still be shot on the way.
+#[derive(Component, Default, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Escaping {
+    pub heading: Heading,
+}
+
+/// Sitting on the decoy, still and an easy shot, until `rest` is up and it
+/// flies off again towards `heading`
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Perched {
+    pub heading: Heading,
+    pub rest: Timer,
+}
+
+impl Perched {
+    pub fn new(heading: Heading) -> Self {
+        Self {
+            heading,
+            rest: Timer::from_seconds(PERCH_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+/// Just got shot, hangs in the air showing the shot frame until `pause` is
+/// up
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Dying {
+    pub pause: Timer,
+}
+
+impl Default for Dying {
+    fn default() -> Self {
+        Self {
+            pause: Timer::from_seconds(SHOT_PAUSE, TimerMode::Once),
+        }
+    }
+}
+
+/// Plummets down to the grass after the shot pause
+#[derive(Component, Default, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Falling;
+
+pub trait DuckStateCommands {
+    /// Leaves whatever state the duck was in and enters `state`
+    fn set_duck_state(&mut self, state: impl Component) -> &mut Self;
+}
+
+impl DuckStateCommands for EntityCommands<'_> {
+    fn set_duck_state(&mut self, state: impl Component) -> &mut Self {
+        self.remove::<DuckStates>().insert(state)
+    }
+}
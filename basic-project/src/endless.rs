@@ -0,0 +1,64 @@
+//! Endless mode controller. There are no rounds, the spawn rate and duck
+//! speed keep climbing, and the game is over once three ducks get away.
+
+use bevy::prelude::*;
+
+use crate::{
+    events::{DuckEscaped, ResetGame},
+    time_scale::GameTimeScale,
+    Difficulty, GameState, Round, SpawnTimer,
+};
+
+pub const STARTING_LIVES: u32 = 3;
+// Spawn interval shrinks by this many seconds for every second survived
+const SPAWN_RAMP: f32 = 0.01;
+const MIN_SPAWN_INTERVAL: f32 = 0.3;
+// Duck speed grows by this fraction for every second survived
+const SPEED_RAMP: f32 = 0.01;
+const MAX_SPEED: f32 = 3.0;
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lives(pub u32);
+
+#[derive(Resource, Default)]
+pub struct EndlessClock(f32);
+
+pub fn setup_endless(mut commands: Commands, mut round: ResMut<Round>) {
+    // Never runs out of ducks so the round never ends
+    round.ducks_total = u32::MAX;
+    commands.insert_resource(Lives(STARTING_LIVES));
+    commands.insert_resource(EndlessClock::default());
+}
+
+pub fn reset_endless(_trigger: Trigger<ResetGame>, mut commands: Commands) {
+    commands.remove_resource::<Lives>();
+    commands.remove_resource::<EndlessClock>();
+}
+
+pub fn escalate_endless(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut clock: ResMut<EndlessClock>,
+    mut timer: ResMut<SpawnTimer>,
+    mut difficulty: ResMut<Difficulty>,
+) {
+    clock.0 += time_scale.delta_secs(&time);
+    let interval = (1.0 - clock.0 * SPAWN_RAMP).max(MIN_SPAWN_INTERVAL);
+    timer
+        .0
+        .set_duration(std::time::Duration::from_secs_f32(interval));
+    difficulty.speed = (1.0 + clock.0 * SPEED_RAMP).min(MAX_SPEED);
+}
+
+pub fn lose_lives(
+    mut lives: ResMut<Lives>,
+    mut duck_escaped_reader: EventReader<DuckEscaped>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for _ in duck_escaped_reader.read() {
+        lives.0 = lives.0.saturating_sub(1);
+    }
+    if lives.0 == 0 {
+        next_state.set(GameState::GameOver);
+    }
+}
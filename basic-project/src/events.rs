@@ -0,0 +1,98 @@
+//! Gameplay events emitted by the core systems.
+//!
+//! UI, audio, stats and networking should be built as separate plugins that
+//! only read these, instead of reaching into the core components.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::power_up::PowerUpKind;
+
+/// Which player a shot came from, also the index into per player arrays.
+/// Player one is the mouse.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PlayerId(pub usize);
+
+/// A new duck entered the play field.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DuckSpawned {
+    pub entity: Entity,
+    pub position: Vec2,
+}
+
+/// The player pulled the trigger, whether or not anything was hit.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ShotFired {
+    pub player: PlayerId,
+    pub position: Vec2,
+}
+
+/// A shot took a duck down. The duck starts dying on the same frame.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DuckHit {
+    pub player: PlayerId,
+    pub entity: Entity,
+    pub position: Vec2,
+    pub points: u32,
+}
+
+/// A shot connected with a duck that has health left after it.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DuckDamaged {
+    pub player: PlayerId,
+    pub entity: Entity,
+    pub position: Vec2,
+    pub health: f32,
+}
+
+/// A duck flew off the top of the screen. The entity is despawned right
+/// after this is sent, so only use it as an identifier.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DuckEscaped {
+    pub entity: Entity,
+    pub position: Vec2,
+}
+
+/// A shot duck hit the ground out of sight below the grass. The entity is
+/// despawned right after this is sent, so only use it as an identifier.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DuckLanded {
+    pub entity: Entity,
+    pub position: Vec2,
+}
+
+/// Every duck of the round has either been hit or escaped.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RoundEnded {
+    pub round: u32,
+    pub ducks_hit: u32,
+    pub ducks_escaped: u32,
+}
+
+/// A power-up was shot and its effect started.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PowerUpCollected {
+    pub kind: PowerUpKind,
+    pub position: Vec2,
+}
+
+/// A shell pickup was shot, `shells` went into the player's weapon.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AmmoCollected {
+    pub player: PlayerId,
+    pub position: Vec2,
+    pub shells: u32,
+}
+
+/// An achievement was earned for the first time. `id` is the API name it
+/// has on Steam as well.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AchievementUnlocked {
+    pub id: &'static str,
+}
+
+/// Everything the last game left behind should be put back, sent before a
+/// restart or going back to the menu. It's triggered rather than written so
+/// the observers are done before the next state is entered.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ResetGame;
@@ -0,0 +1,186 @@
+//! How a flying duck moves up and down. Every duck gets a pattern when it
+//! spawns and picks another one every so often, until it's been out long
+//! enough and goes into `Escaping`, climbing away off the top of the screen.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    be_the_duck::PlayerDuck,
+    boss::Boss,
+    duck_state::{DuckStateCommands, Escaping, Flying},
+    rng::GameRng,
+    time_scale::GameTimeScale,
+    view::View,
+    Simulation,
+};
+
+// Seconds between two pattern changes
+const SWITCH_EVERY: f32 = 1.5;
+// Seconds a duck sticks around before making a run for it
+const STAY: f32 = 5.0;
+// Radians per second of the bobbing
+const BOB_RATE: f32 = 3.0;
+/// Ducks turn around at these before they'd leave the field, the floor is
+/// just above the grass
+pub const CEILING: f32 = 100.0;
+pub const FLOOR: f32 = -30.0;
+// Seconds between the points of a predicted path
+const PATH_STEP: f32 = 1.0 / 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Vertical {
+    Level,
+    Bob,
+    Dive,
+    Climb,
+}
+
+impl Vertical {
+    pub const ALL: [Vertical; 4] = [
+        Vertical::Level,
+        Vertical::Bob,
+        Vertical::Dive,
+        Vertical::Climb,
+    ];
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct FlightPattern {
+    pub vertical: Vertical,
+    switch: Timer,
+    stay: Timer,
+}
+
+impl Default for FlightPattern {
+    // The old straight climb
+    fn default() -> Self {
+        Self::new(Vertical::Climb)
+    }
+}
+
+impl FlightPattern {
+    pub fn new(vertical: Vertical) -> Self {
+        Self {
+            vertical,
+            switch: Timer::from_seconds(SWITCH_EVERY, TimerMode::Repeating),
+            stay: Timer::from_seconds(STAY, TimerMode::Once),
+        }
+    }
+
+    /// How long it sticks around before flying away
+    pub fn stay_duration(&self) -> Duration {
+        self.stay.duration()
+    }
+
+    /// Sticks around for `duration` from when it came out instead
+    pub fn set_stay_duration(&mut self, duration: Duration) {
+        self.stay.set_duration(duration);
+    }
+
+    /// Done for the round, it stops turning at the ceiling and flies away
+    pub fn leaving(&self) -> bool {
+        self.stay.finished()
+    }
+
+    /// Up is positive, at the duck's `speed`
+    pub fn vertical_speed(&self, speed: f32) -> f32 {
+        if self.leaving() {
+            return speed;
+        }
+        match self.vertical {
+            Vertical::Level => 0.0,
+            Vertical::Bob => (self.stay.elapsed_secs() * BOB_RATE).cos() * speed,
+            Vertical::Dive => -speed,
+            Vertical::Climb => speed,
+        }
+    }
+
+    /// Where a duck at `start` goes until the pattern next changes, if
+    /// nothing lures it away. `x_speed` is its speed across, wind and all,
+    /// and it turns at the same edges `move_ducks` turns it at.
+    pub fn planned_path(&self, start: Vec2, x_speed: f32, speed: f32, view: &View) -> Vec<Vec2> {
+        let mut pattern = self.clone();
+        let mut position = start;
+        let mut x_speed = x_speed;
+        let steps = (self.switch.remaining_secs() / PATH_STEP).ceil() as usize;
+        let mut path = Vec::with_capacity(steps + 1);
+        path.push(start);
+        for _ in 0..steps {
+            pattern.stay.tick(Duration::from_secs_f32(PATH_STEP));
+            position += Vec2::new(x_speed, pattern.vertical_speed(speed)) * PATH_STEP;
+            if position.x > view.max_x() {
+                x_speed = -x_speed.abs();
+            } else if position.x < view.min_x() {
+                x_speed = x_speed.abs();
+            }
+            pattern.keep_inside(position.y);
+            path.push(position);
+        }
+        path
+    }
+
+    /// Turns it around when it's about to leave the field through the top
+    /// or bottom
+    pub fn keep_inside(&mut self, y: f32) {
+        if self.leaving() {
+            return;
+        }
+        if y > CEILING {
+            self.vertical = Vertical::Dive;
+        } else if y < FLOOR {
+            self.vertical = Vertical::Climb;
+        }
+    }
+}
+
+pub struct FlightPlugin;
+
+impl Plugin for FlightPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Simulation,
+            (switch_flight_patterns, start_escaping)
+                .chain()
+                .before(crate::move_ducks),
+        );
+    }
+}
+
+// Rolled from the game rng, the spawn rng only rolls at spawn so the duck
+// sequence doesn't depend on how long the ducks are kept alive
+fn switch_flight_patterns(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut rng: ResMut<GameRng>,
+    mut duck_query: Query<(&mut FlightPattern, &Transform), With<Flying>>,
+) {
+    let delta = time_scale.delta(&time);
+    for (mut pattern, transform) in &mut duck_query {
+        pattern.stay.tick(delta);
+        if !pattern.switch.tick(delta).just_finished() || pattern.leaving() {
+            continue;
+        }
+        pattern.vertical = Vertical::ALL[rng.gen_range(0..Vertical::ALL.len())];
+        pattern.keep_inside(transform.translation.y);
+    }
+}
+
+// The boss and a duck flown by a player don't follow their pattern, so they
+// never run out of time
+#[allow(clippy::type_complexity)]
+fn start_escaping(
+    mut commands: Commands,
+    duck_query: Query<(Entity, &Flying, &FlightPattern), (Without<Boss>, Without<PlayerDuck>)>,
+) {
+    for (entity, flying, pattern) in &duck_query {
+        if pattern.leaving() {
+            commands.entity(entity).set_duck_state(Escaping {
+                heading: flying.heading,
+            });
+        }
+    }
+}
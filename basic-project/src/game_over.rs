@@ -0,0 +1,182 @@
+//! Final score and the high score table for the mode that was played. Time
+//! attack gets a results breakdown instead of the plain game over. From here
+//! the same mode can be played again or it's back to the menu.
+
+use bevy::prelude::*;
+
+use crate::{
+    daily::DailyChallenge,
+    high_score::{HighScoreEntry, HighScores, ScoreBoard},
+    hotseat::Hotseat,
+    locale::Locale,
+    menu::spawn_menu_button,
+    mode::GameMode,
+    score::{Score, SessionStats},
+    session::SessionButton,
+    speedrun::{format_run_time, SpeedrunTimer},
+    versus::VersusScores,
+    GameState, SimulationDriver,
+};
+
+#[derive(Component)]
+pub struct GameOverScreen;
+
+#[allow(clippy::too_many_arguments)]
+pub fn setup_game_over(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    mode: Res<GameMode>,
+    daily: Option<Res<DailyChallenge>>,
+    score: Res<Score>,
+    stats: Res<SessionStats>,
+    speedrun: Res<SpeedrunTimer>,
+    hotseat: Option<Res<Hotseat>>,
+    versus: Option<Res<VersusScores>>,
+    mut high_scores: ResMut<HighScores>,
+    driver: Res<SimulationDriver>,
+) {
+    let board = ScoreBoard::for_game(*mode, daily.as_deref());
+    let time = speedrun.final_time();
+    let mut lines = Vec::new();
+    let two_players = match (*mode, hotseat, versus) {
+        (GameMode::Hotseat, Some(hotseat), _) => Some(
+            hotseat
+                .players
+                .map(|player| (player.score.0, player.stats.ducks_hit)),
+        ),
+        (GameMode::Versus, _, Some(versus)) => {
+            Some([0, 1].map(|i| (versus.scores[i], versus.ducks_hit[i])))
+        }
+        _ => None,
+    };
+    let place = match two_players {
+        Some(players) => {
+            // Both players go on the board, so there's no single place to mark
+            for (i, (score, ducks_hit)) in players.into_iter().enumerate() {
+                lines.push(locale.format(
+                    "game_over.player_score",
+                    &[
+                        ("player", &(i + 1)),
+                        ("score", &score),
+                        ("ducks", &ducks_hit),
+                    ],
+                ));
+                high_scores.submit(board, HighScoreEntry { score, time: None });
+            }
+            let [(first, _), (second, _)] = players;
+            lines.push(
+                locale
+                    .get(match first.cmp(&second) {
+                        std::cmp::Ordering::Greater => "game_over.player_1_wins",
+                        std::cmp::Ordering::Less => "game_over.player_2_wins",
+                        std::cmp::Ordering::Equal => "game_over.draw",
+                    })
+                    .to_string(),
+            );
+            None
+        }
+        _ => {
+            lines.push(locale.format("game_over.score", &[("score", &score.0)]));
+            high_scores.submit(
+                board,
+                HighScoreEntry {
+                    score: score.0,
+                    time,
+                },
+            )
+        }
+    };
+
+    let title = locale.get(match *mode {
+        GameMode::TimeAttack => "game_over.time_up",
+        _ => "game_over.title",
+    });
+    if *mode == GameMode::BeTheDuck {
+        lines.push(locale.format("game_over.ducks_shot", &[("ducks", &stats.ducks_hit)]));
+        lines.push(locale.format(
+            "game_over.ducks_escaped",
+            &[("ducks", &stats.ducks_escaped)],
+        ));
+    }
+    if *mode == GameMode::TimeAttack {
+        lines.push(locale.format("game_over.ducks", &[("ducks", &stats.ducks_hit)]));
+        lines.push(locale.format("game_over.shots", &[("shots", &stats.shots_fired)]));
+        lines.push(locale.format(
+            "game_over.accuracy",
+            &[("accuracy", &format!("{:.0}", stats.accuracy() * 100.0))],
+        ));
+    }
+    if let Some(time) = time {
+        lines.push(locale.format("game_over.time", &[("time", &format_run_time(time))]));
+        for (round, split) in speedrun.splits().iter().enumerate() {
+            lines.push(locale.format(
+                "speedrun.split",
+                &[("round", &(round + 1)), ("time", &format_run_time(*split))],
+            ));
+        }
+    }
+    if place.is_some() {
+        lines.push(locale.get("game_over.new_high_score").to_string());
+    }
+    lines.push(String::new());
+    lines.push(match board {
+        ScoreBoard::Daily(day) => {
+            let (year, month, day) = DailyChallenge { day }.date();
+            locale.format(
+                "game_over.daily",
+                &[("date", &format!("{year}-{month:02}-{day:02}"))],
+            )
+        }
+        ScoreBoard::Mode(_) => locale.get("game_over.top_scores").to_string(),
+    });
+    for (i, entry) in high_scores.table(board).iter().enumerate() {
+        let marker = if Some(i) == place { ">" } else { " " };
+        let mut line = format!("{marker}{:>2}. {:>6}", i + 1, entry.score);
+        if let Some(time) = entry.time {
+            line.push_str(&format!(" {}", format_run_time(time)));
+        }
+        lines.push(line);
+    }
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.7)),
+            GameOverScreen,
+            StateScoped(GameState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(lines.join("\n")),
+                TextFont {
+                    font_size: 10.0,
+                    ..default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            // Networked games are set up from the menu by both players
+            if *driver == SimulationDriver::Local {
+                spawn_menu_button(
+                    parent,
+                    locale.get("session.restart"),
+                    SessionButton::Restart,
+                );
+            }
+            spawn_menu_button(parent, locale.get("session.menu"), SessionButton::Menu);
+        });
+}
@@ -0,0 +1,140 @@
+//! Gyro aiming for controllers that have one, like the DualSense or the
+//! Switch Pro controller. gilrs doesn't read motion sensors, so SDL2 is
+//! opened next to it just for the gyro, the buttons and sticks still come
+//! through Bevy.
+//!
+//! Turning the controller moves the stick aimed reticle on top of what the
+//! stick does. Gyros drift a little at rest, holding Y with the controller
+//! still measures that and takes it off from then on, and puts the reticle
+//! back in the middle.
+
+use std::time::Duration;
+
+use bevy::{input::InputSystem, prelude::*};
+use sdl2::{controller::GameController, sensor::SensorType, GameControllerSubsystem, Sdl};
+
+use crate::{aim::GyroMotion, settings::Settings, versus::versus_gamepad};
+
+// How far the reticle goes for a turn of one radian, about a quarter turn
+// crosses the whole field
+const PIXELS_PER_RADIAN: f32 = 160.0;
+// Radians per second below this are hand tremor, not aiming
+const DEADZONE: f32 = 0.01;
+const RECENTER_BUTTON: GamepadButton = GamepadButton::North;
+// Opening a controller to look for a gyro isn't free, so while there isn't
+// one this is how often it looks again
+const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// SDL's handles, which have to stay on the thread that opened them
+struct GyroController {
+    _sdl: Sdl,
+    controllers: GameControllerSubsystem,
+    open: Option<GameController>,
+    // Until it looks for a controller again while none is open
+    rescan: Timer,
+}
+
+impl GyroController {
+    /// The first controller that has a gyro, opened and with it turned on
+    fn find(&self) -> Option<GameController> {
+        let count = self.controllers.num_joysticks().ok()?;
+        (0..count)
+            .filter(|&index| self.controllers.is_game_controller(index))
+            .filter_map(|index| self.controllers.open(index).ok())
+            .find(|controller| {
+                controller.has_sensor(SensorType::Gyroscope)
+                    && controller
+                        .sensor_set_enabled(SensorType::Gyroscope, true)
+                        .is_ok()
+            })
+    }
+}
+
+/// Rest reading taken off every sample, and the samples being averaged for
+/// a new one while the recenter button is held
+#[derive(Resource, Debug, Default)]
+struct GyroCalibration {
+    bias: Vec3,
+    sum: Vec3,
+    samples: u32,
+}
+
+pub struct GyroPlugin;
+
+impl Plugin for GyroPlugin {
+    fn build(&self, app: &mut App) {
+        let opened = sdl2::init().and_then(|sdl| Ok((sdl.game_controller()?, sdl)));
+        let (controllers, sdl) = match opened {
+            Ok(opened) => opened,
+            Err(error) => {
+                println!("Gyro aiming is not available: {error}");
+                return;
+            }
+        };
+        // Only the state is read, SDL's own events would pile up unread
+        controllers.set_event_state(false);
+        app.insert_non_send_resource(GyroController {
+            _sdl: sdl,
+            controllers,
+            open: None,
+            // Finished from the start so the first frame looks straight away
+            rescan: Timer::new(Duration::ZERO, TimerMode::Once),
+        })
+        .init_resource::<GyroCalibration>()
+        .add_systems(PreUpdate, read_gyro.after(InputSystem));
+    }
+}
+
+fn read_gyro(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    gamepad_query: Query<(Entity, &Gamepad)>,
+    mut gyro: NonSendMut<GyroController>,
+    mut calibration: ResMut<GyroCalibration>,
+    mut motion: ResMut<GyroMotion>,
+) {
+    *motion = GyroMotion::default();
+    if !settings.gyro.enabled {
+        return;
+    }
+    gyro.controllers.update();
+    if !gyro.open.as_ref().is_some_and(GameController::attached)
+        && gyro.rescan.tick(time.delta()).finished()
+    {
+        let found = gyro.find();
+        gyro.open = found;
+        gyro.rescan = Timer::new(RESCAN_INTERVAL, TimerMode::Once);
+    }
+    let Some(controller) = gyro.open.as_ref() else {
+        return;
+    };
+    let mut data = [0.0; 3];
+    if controller
+        .sensor_get_data(SensorType::Gyroscope, &mut data)
+        .is_err()
+    {
+        return;
+    }
+    let rate = Vec3::from_array(data);
+
+    let recenter = versus_gamepad(&gamepad_query).map(|(_, gamepad)| gamepad);
+    if recenter.is_some_and(|gamepad| gamepad.just_pressed(RECENTER_BUTTON)) {
+        motion.recenter = true;
+        calibration.sum = Vec3::ZERO;
+        calibration.samples = 0;
+    }
+    if recenter.is_some_and(|gamepad| gamepad.pressed(RECENTER_BUTTON)) {
+        calibration.sum += rate;
+        calibration.samples += 1;
+        calibration.bias = calibration.sum / calibration.samples as f32;
+        return;
+    }
+
+    let rate = rate - calibration.bias;
+    if rate.length() < DEADZONE {
+        return;
+    }
+    // Pitching up raises the reticle, turning left moves it left
+    let turn = Vec2::new(-rate.y, rate.x) * time.delta_secs();
+    motion.delta = turn * PIXELS_PER_RADIAN * settings.gyro.sensitivity;
+}
@@ -0,0 +1,61 @@
+//! Best scores per game mode, plus one board per day of daily challenges.
+
+use std::time::Duration;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{daily::DailyChallenge, mode::GameMode};
+
+const HIGH_SCORES_KEPT: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScoreBoard {
+    Mode(GameMode),
+    /// Days since the unix epoch, see `DailyChallenge`
+    Daily(u64),
+}
+
+impl ScoreBoard {
+    /// The board a game in `mode` goes on
+    pub fn for_game(mode: GameMode, daily: Option<&DailyChallenge>) -> Self {
+        match (mode, daily) {
+            (GameMode::Daily, Some(daily)) => Self::Daily(daily.day),
+            (mode, _) => Self::Mode(mode),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub score: u32,
+    /// Speedrun time, when the timer was on for the run
+    pub time: Option<Duration>,
+}
+
+#[derive(Resource, Default, Debug, Clone, Serialize, Deserialize)]
+pub struct HighScores(HashMap<ScoreBoard, Vec<HighScoreEntry>>);
+
+impl HighScores {
+    /// Adds a score to the board, returns its place if it made it in. Ties
+    /// go to the faster time.
+    pub fn submit(&mut self, board: ScoreBoard, entry: HighScoreEntry) -> Option<usize> {
+        let table = self.0.entry(board).or_default();
+        let place = table.partition_point(|existing| {
+            existing.score > entry.score
+                || (existing.score == entry.score
+                    && existing.time.unwrap_or(Duration::MAX)
+                        <= entry.time.unwrap_or(Duration::MAX))
+        });
+        if place >= HIGH_SCORES_KEPT {
+            return None;
+        }
+        table.insert(place, entry);
+        table.truncate(HIGH_SCORES_KEPT);
+        Some(place)
+    }
+
+    pub fn table(&self, board: ScoreBoard) -> &[HighScoreEntry] {
+        self.0.get(&board).map_or(&[], Vec::as_slice)
+    }
+}
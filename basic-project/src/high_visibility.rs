@@ -0,0 +1,132 @@
+//! High visibility ducks for colorblind and low vision players, turned on
+//! from the accessibility page. The dark green of the duck is hard to tell
+//! from the sky for a lot of people, so the sheet is recolored to black,
+//! yellow and orange and every duck gets a thin light outline.
+//!
+//! Like the shot flash, it's a look of the duck sheet built on the CPU once
+//! the sheet has loaded, and the ducks' sprites are switched over to it.
+
+use bevy::prelude::*;
+
+use crate::{
+    alpha_mask::duck_image_to_build,
+    duck_look::{DuckLook, DuckLooks},
+    settings::Settings,
+    Duck, GameAssets,
+};
+
+const BODY_COLOR: Color = Color::srgb(1.0, 0.85, 0.0);
+const BEAK_COLOR: Color = Color::srgb(1.0, 0.35, 0.0);
+const OUTLINE_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+
+pub struct HighVisibilityPlugin;
+
+impl Plugin for HighVisibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(show_high_vis_duck)
+            .add_systems(Update, (build_high_vis_sheet, switch_duck_sheets).chain());
+    }
+}
+
+/// Where the pixel goes in the high visibility palette. Black and white stay,
+/// greens become the body color and reds the beak color.
+fn high_vis_color(color: Color) -> Color {
+    let Srgba {
+        red,
+        green,
+        blue,
+        alpha,
+    } = color.to_srgba();
+    let color = if red.max(green).max(blue) < 0.15 {
+        Color::BLACK
+    } else if green > red && green > blue {
+        BODY_COLOR
+    } else if red > green && red > blue && red - blue > 0.2 {
+        BEAK_COLOR
+    } else {
+        Color::WHITE
+    };
+    color.with_alpha(alpha)
+}
+
+fn build_high_vis_sheet(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut built_from: Local<Option<AssetId<Image>>>,
+    mut images: ResMut<Assets<Image>>,
+    mut looks: ResMut<DuckLooks>,
+    game_assets: Option<Res<GameAssets>>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    let Some(image) =
+        duck_image_to_build(&game_assets, &images, &mut built_from, &mut image_events)
+    else {
+        return;
+    };
+    let mut high_vis = image.clone();
+    let size = image.size();
+    let opaque = |x: u32, y: u32| image.get_color_at(x, y).is_ok_and(|c| c.alpha() > 0.5);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let color = image.get_color_at(x, y).unwrap_or(Color::NONE);
+            let color = if color.alpha() > 0.5 {
+                high_vis_color(color)
+            } else if [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().any(|(dx, dy)| {
+                let (nx, ny) = (x.wrapping_add_signed(*dx), y.wrapping_add_signed(*dy));
+                nx < size.x && ny < size.y && opaque(nx, ny)
+            }) {
+                OUTLINE_COLOR
+            } else {
+                color
+            };
+            let _ = high_vis.set_color_at(x, y, color);
+        }
+    }
+    looks.set(DuckLook::HighVisibility, images.add(high_vis));
+}
+
+fn show_high_vis_duck(
+    trigger: Trigger<OnAdd, Duck>,
+    settings: Res<Settings>,
+    game_assets: Option<Res<GameAssets>>,
+    looks: Res<DuckLooks>,
+    mut duck_query: Query<&mut Sprite>,
+) {
+    let (true, Some(game_assets)) = (settings.accessibility.high_visibility, game_assets) else {
+        return;
+    };
+    let Ok(mut sprite) = duck_query.get_mut(trigger.target()) else {
+        return;
+    };
+    if game_assets.look_of(&looks, &sprite) == Some(DuckLook::Plain) {
+        game_assets.show_look(&looks, &mut sprite, DuckLook::HighVisibility);
+    }
+}
+
+// Ducks already out when it's turned on or off, or when the look gets
+// rebuilt, change over too. Only sprites showing the other look are
+// touched, a duck flashing white keeps its flash.
+fn switch_duck_sheets(
+    settings: Res<Settings>,
+    game_assets: Option<Res<GameAssets>>,
+    looks: Res<DuckLooks>,
+    mut duck_query: Query<&mut Sprite, With<Duck>>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    if !settings.is_changed() && !looks.is_changed() {
+        return;
+    }
+    let (from, to) = if settings.accessibility.high_visibility {
+        (DuckLook::Plain, DuckLook::HighVisibility)
+    } else {
+        (DuckLook::HighVisibility, DuckLook::Plain)
+    };
+    for mut sprite in &mut duck_query {
+        if game_assets.look_of(&looks, &sprite) == Some(from) {
+            game_assets.show_look(&looks, &mut sprite, to);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Two players taking turns on one mouse. Each turn is a round, both players
+//! play the same round number before it goes up, and each keeps their own
+//! score, shells and tally.
+
+use bevy::prelude::*;
+
+use crate::{
+    banner::{Banners, BANNER_SECONDS},
+    events::{ResetGame, RoundEnded},
+    locale::Locale,
+    score::{Score, SessionStats},
+    weapon::EquippedWeapon,
+    GameState, Round,
+};
+
+// Rounds each player gets before the game ends
+pub const HOTSEAT_ROUNDS: u32 = 5;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlayerSlot {
+    pub score: Score,
+    pub stats: SessionStats,
+    ammo: Option<u32>,
+}
+
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Hotseat {
+    /// Index into `players` of whoever is shooting
+    pub current: usize,
+    pub players: [PlayerSlot; 2],
+}
+
+pub fn setup_hotseat(mut commands: Commands, locale: Res<Locale>, mut banners: ResMut<Banners>) {
+    commands.insert_resource(Hotseat::default());
+    show_turn_banner(&mut banners, &locale, 0);
+}
+
+pub fn reset_hotseat(_trigger: Trigger<ResetGame>, mut commands: Commands) {
+    commands.remove_resource::<Hotseat>();
+}
+
+// Goes up ahead of the round intro, which holds the ducks back until it's
+// gone
+fn show_turn_banner(banners: &mut Banners, locale: &Locale, player: usize) {
+    banners.show(
+        locale.format("hotseat.player", &[("player", &(player + 1))]),
+        BANNER_SECONDS,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn switch_turns(
+    locale: Res<Locale>,
+    mut banners: ResMut<Banners>,
+    mut hotseat: ResMut<Hotseat>,
+    mut round: ResMut<Round>,
+    mut score: ResMut<Score>,
+    mut stats: ResMut<SessionStats>,
+    mut equipped: ResMut<EquippedWeapon>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for ended in round_ended_reader.read() {
+        // Put away what the player who just finished had
+        let current = hotseat.current;
+        hotseat.players[current] = PlayerSlot {
+            score: *score,
+            stats: *stats,
+            ammo: Some(equipped.ammo),
+        };
+
+        let next = 1 - current;
+        if next == 0 && ended.round >= HOTSEAT_ROUNDS {
+            next_state.set(GameState::GameOver);
+            return;
+        }
+        // Player 2 replays the round player 1 just had, after that it moves on
+        if next == 1 {
+            *round = Round::new(ended.round);
+        }
+
+        let slot = hotseat.players[next];
+        *score = slot.score;
+        *stats = slot.stats;
+        if let Some(ammo) = slot.ammo {
+            equipped.ammo = ammo;
+        } else {
+            equipped.refill();
+        }
+        hotseat.current = next;
+        show_turn_banner(&mut banners, &locale, next);
+    }
+}
@@ -0,0 +1,152 @@
+//! Play field overlays drawn in world space on top of the background's HUD
+//! strip, so they scale with the rest of the pixels.
+
+use bevy::prelude::*;
+
+use crate::{
+    bitmap_text::{BitmapText, TextAlign},
+    locale::Locale,
+    view::ScreenFixed,
+    weapon::EquippedWeapon,
+    GameAssets, GameState, Round, Score,
+};
+
+/// On everything drawn over the play field rather than in it, so photo mode
+/// can take it away. It stays put on the screen when the view pans.
+#[derive(Component)]
+#[require(ScreenFixed)]
+pub struct Hud;
+
+#[derive(Component)]
+pub struct WeaponIcon;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+#[derive(Component)]
+pub struct RoundText;
+
+#[derive(Component)]
+pub struct ReloadPrompt;
+
+const WEAPON_ICON_Y: f32 = -73.0;
+// Pixels the icon dips on every pump of a reload
+const PUMP_DEPTH: f32 = 2.0;
+
+pub fn setup_hud(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    locale: Res<Locale>,
+    equipped: Res<EquippedWeapon>,
+    score: Res<Score>,
+    round: Res<Round>,
+) {
+    // Sits on the dirt right of the "R=" box
+    commands.spawn((
+        Sprite::from_atlas_image(
+            game_assets.weapon_icons_spritesheet.clone(),
+            TextureAtlas {
+                layout: game_assets.weapon_icons_layout.clone(),
+                index: equipped.weapon.icon,
+            },
+        ),
+        Transform::from_xyz(-40.0, WEAPON_ICON_Y, 2.0),
+        WeaponIcon,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+    // Above the "SCORE" label, lined up with its right edge
+    commands.spawn((
+        BitmapText::new(format_score(score.0)).with_align(TextAlign::Right),
+        Transform::from_xyz(112.0, -88.0, 2.0),
+        ScoreText,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+    // In the black space after "R="
+    commands.spawn((
+        BitmapText::new(round.number.to_string()).with_align(TextAlign::Left),
+        Transform::from_xyz(-88.0, -72.0, 2.0),
+        RoundText,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+    // Over the sky, above where the banners come down to
+    commands.spawn((
+        BitmapText::new(locale.get("hud.reload")),
+        Transform::from_xyz(0.0, 80.0, 3.0),
+        Visibility::Hidden,
+        ReloadPrompt,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+fn format_score(score: u32) -> String {
+    format!("{score:06}")
+}
+
+pub fn update_score_text(
+    score: Res<Score>,
+    mut text_query: Query<&mut BitmapText, With<ScoreText>>,
+) {
+    if !score.is_changed() {
+        return;
+    }
+    for mut text in &mut text_query {
+        BitmapText::set(&mut text, format_score(score.0));
+    }
+}
+
+pub fn update_round_text(
+    round: Res<Round>,
+    mut text_query: Query<&mut BitmapText, With<RoundText>>,
+) {
+    if !round.is_changed() {
+        return;
+    }
+    for mut text in &mut text_query {
+        BitmapText::set(&mut text, round.number.to_string());
+    }
+}
+
+pub fn update_weapon_icon(
+    equipped: Res<EquippedWeapon>,
+    mut icon_query: Query<&mut Sprite, With<WeaponIcon>>,
+) {
+    if !equipped.is_changed() {
+        return;
+    }
+    for mut sprite in &mut icon_query {
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            if atlas.index != equipped.weapon.icon {
+                atlas.index = equipped.weapon.icon;
+            }
+        }
+    }
+}
+
+// The icon pumps twice while shells go in, and "RELOAD" blinks while the
+// weapon is empty
+pub fn animate_reload(
+    time: Res<Time>,
+    equipped: Res<EquippedWeapon>,
+    mut icon_query: Query<&mut Transform, With<WeaponIcon>>,
+    mut prompt_query: Query<&mut Visibility, With<ReloadPrompt>>,
+) {
+    let dip = equipped.reload_progress().map_or(0.0, |progress| {
+        (progress * std::f32::consts::TAU).sin().abs() * PUMP_DEPTH
+    });
+    for mut transform in &mut icon_query {
+        transform.translation.y = (WEAPON_ICON_Y - dip).round();
+    }
+    let blink = ((time.elapsed_secs() * 4.0) as u32).is_multiple_of(2);
+    let empty = equipped.ammo == 0 && !equipped.reloading();
+    for mut visibility in &mut prompt_query {
+        visibility.set_if_neq(if empty && blink {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
@@ -0,0 +1,237 @@
+//! Online leaderboard. Scores are sent off when a game ends and the best
+//! ones are shown on the title screen. Requests run on the IO task pool so
+//! a slow server never holds up a frame.
+//!
+//! `HttpLeaderboard` talks to the server at `DUCK_HUNT_LEADERBOARD_URL`.
+//! To use another backend implement `LeaderboardBackend` and insert a
+//! `Leaderboard` with it before the game starts.
+
+use std::sync::Arc;
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, IoTaskPool, Task},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    daily::DailyChallenge,
+    high_score::ScoreBoard,
+    locale::Locale,
+    menu::MenuScreen,
+    mode::GameMode,
+    rng::RunSeed,
+    score::{Score, SessionStats},
+    speedrun::SpeedrunTimer,
+    GameState,
+};
+
+const TOP_ENTRIES: usize = 5;
+
+/// A score as it's sent to the backend
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoreSubmission {
+    pub board: String,
+    pub name: String,
+    pub score: u32,
+    pub ducks_hit: u32,
+    pub shots_fired: u32,
+    /// Speedrun time in milliseconds, when the timer was on
+    pub time_ms: Option<u64>,
+    pub seed: u64,
+    pub version: String,
+}
+
+/// A row of the leaderboard as the backend returns it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: u32,
+}
+
+#[derive(Debug, Error)]
+pub enum LeaderboardError {
+    #[error("leaderboard request failed: {0}")]
+    Http(#[from] Box<ureq::Error>),
+    #[error("could not read leaderboard response: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where scores go. Calls are made from a background task and may block.
+pub trait LeaderboardBackend: Send + Sync + 'static {
+    fn submit(&self, submission: &ScoreSubmission) -> Result<(), LeaderboardError>;
+    fn top(&self, board: &str, count: usize) -> Result<Vec<LeaderboardEntry>, LeaderboardError>;
+}
+
+/// Posts to `{url}/scores` and reads the top entries back from
+/// `{url}/scores?board=..&count=..`, both as JSON
+pub struct HttpLeaderboard {
+    url: String,
+}
+
+impl HttpLeaderboard {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+impl LeaderboardBackend for HttpLeaderboard {
+    fn submit(&self, submission: &ScoreSubmission) -> Result<(), LeaderboardError> {
+        ureq::post(&format!("{}/scores", self.url))
+            .send_json(submission)
+            .map_err(Box::new)?;
+        Ok(())
+    }
+
+    fn top(&self, board: &str, count: usize) -> Result<Vec<LeaderboardEntry>, LeaderboardError> {
+        let response = ureq::get(&format!("{}/scores", self.url))
+            .query("board", board)
+            .query("count", &count.to_string())
+            .call()
+            .map_err(Box::new)?;
+        Ok(response.into_json()?)
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct Leaderboard(Arc<dyn LeaderboardBackend>);
+
+impl Leaderboard {
+    pub fn new(backend: impl LeaderboardBackend) -> Self {
+        Self(Arc::new(backend))
+    }
+}
+
+#[derive(Resource)]
+pub struct PendingTop(Task<Result<Vec<LeaderboardEntry>, LeaderboardError>>);
+
+#[derive(Resource, Default)]
+pub struct PendingSubmissions(Vec<Task<Result<(), LeaderboardError>>>);
+
+#[derive(Component)]
+pub struct LeaderboardText;
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        if let Ok(url) = std::env::var("DUCK_HUNT_LEADERBOARD_URL") {
+            app.insert_resource(Leaderboard::new(HttpLeaderboard::new(url)));
+        }
+        app.init_resource::<PendingSubmissions>()
+            .add_systems(
+                OnEnter(GameState::Menu),
+                fetch_top
+                    .after(crate::menu::setup_menu)
+                    .run_if(resource_exists::<Leaderboard>),
+            )
+            .add_systems(
+                Update,
+                show_top.run_if(in_state(GameState::Menu).and(resource_exists::<PendingTop>)),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                submit_score.run_if(resource_exists::<Leaderboard>),
+            )
+            .add_systems(Update, finish_submissions);
+    }
+}
+
+fn board_key(board: ScoreBoard) -> String {
+    match board {
+        ScoreBoard::Mode(mode) => format!("{mode:?}").to_lowercase(),
+        ScoreBoard::Daily(day) => format!("daily-{day}"),
+    }
+}
+
+// Title screen shows the classic board, that's the one everyone plays
+fn fetch_top(mut commands: Commands, leaderboard: Res<Leaderboard>) {
+    let backend = leaderboard.0.clone();
+    let board = board_key(ScoreBoard::Mode(GameMode::Classic));
+    let task = IoTaskPool::get().spawn(async move { backend.top(&board, TOP_ENTRIES) });
+    commands.insert_resource(PendingTop(task));
+}
+
+fn show_top(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    mut pending: ResMut<PendingTop>,
+    menu_query: Query<Entity, With<MenuScreen>>,
+) {
+    let Some(result) = block_on(future::poll_once(&mut pending.0)) else {
+        return;
+    };
+    commands.remove_resource::<PendingTop>();
+    let entries = match result {
+        Ok(entries) => entries,
+        Err(error) => {
+            println!("{error}");
+            return;
+        }
+    };
+    let mut lines = vec![locale.get("leaderboard.title").to_string()];
+    for (i, entry) in entries.iter().enumerate() {
+        lines.push(format!(
+            "{:>2}. {:<8} {:>6}",
+            i + 1,
+            entry.name,
+            entry.score
+        ));
+    }
+    for entity in &menu_query {
+        commands.entity(entity).with_child((
+            Text::new(lines.join("\n")),
+            TextFont {
+                font_size: 8.0,
+                ..default()
+            },
+            TextLayout::new_with_justify(JustifyText::Center),
+            Node {
+                margin: UiRect::top(Val::Px(12.0)),
+                ..default()
+            },
+            LeaderboardText,
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn submit_score(
+    leaderboard: Res<Leaderboard>,
+    mode: Res<GameMode>,
+    daily: Option<Res<DailyChallenge>>,
+    seed: Res<RunSeed>,
+    score: Res<Score>,
+    stats: Res<SessionStats>,
+    speedrun: Res<SpeedrunTimer>,
+    mut pending: ResMut<PendingSubmissions>,
+) {
+    let submission = ScoreSubmission {
+        board: board_key(ScoreBoard::for_game(*mode, daily.as_deref())),
+        name: std::env::var("DUCK_HUNT_NAME").unwrap_or_else(|_| "PLAYER".to_string()),
+        score: score.0,
+        ducks_hit: stats.ducks_hit,
+        shots_fired: stats.shots_fired,
+        time_ms: speedrun.final_time().map(|time| time.as_millis() as u64),
+        seed: seed.0,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let backend = leaderboard.0.clone();
+    pending
+        .0
+        .push(IoTaskPool::get().spawn(async move { backend.submit(&submission) }));
+}
+
+fn finish_submissions(mut pending: ResMut<PendingSubmissions>) {
+    pending
+        .0
+        .retain_mut(|task| match block_on(future::poll_once(task)) {
+            Some(Ok(())) => false,
+            Some(Err(error)) => {
+                println!("{error}");
+                false
+            }
+            None => true,
+        });
+}
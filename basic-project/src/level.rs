@@ -0,0 +1,177 @@
+//! Layout of the field, read from `assets/data/field.level.ron`, or
+//! `marsh.level.ron` for the marsh. For now that's where the ducks take off
+//! from, the color of the sky, the clouds in it, the weather as the game
+//! goes on and, for a field wider than the screen, the panorama the view
+//! pans across.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::duck_state::Heading;
+
+/// A stretch of the grass line ducks can take off from
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnZone {
+    pub name: String,
+    pub min_x: f32,
+    pub max_x: f32,
+    /// How often ducks come from here compared to the other zones
+    pub weight: f32,
+    /// Which way they fly off, towards the middle when left out
+    #[serde(default)]
+    pub heading: Option<Heading>,
+}
+
+impl SpawnZone {
+    /// Point along the zone for `roll` between 0 and 1
+    pub fn x(&self, roll: f32) -> f32 {
+        self.min_x + (self.max_x - self.min_x) * roll
+    }
+
+    pub fn heading(&self, x: f32) -> Heading {
+        self.heading.unwrap_or(Heading::towards_center(x))
+    }
+}
+
+/// A band of clouds drifting across the sky, behind the ducks
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct CloudLayer {
+    pub count: u32,
+    /// Between these heights, in world y from -120 to 120
+    pub min_y: f32,
+    pub max_y: f32,
+    /// Pixels per second, positive drifts to the right. Layers further back
+    /// want to be slower and smaller.
+    pub speed: f32,
+    #[serde(default = "CloudLayer::default_scale")]
+    pub scale: f32,
+    /// From 0 for see-through to 1
+    #[serde(default = "CloudLayer::default_alpha")]
+    pub alpha: f32,
+}
+
+impl CloudLayer {
+    fn default_scale() -> f32 {
+        1.0
+    }
+
+    fn default_alpha() -> f32 {
+        1.0
+    }
+}
+
+/// A field wider than the screen, drawn instead of the usual background
+/// above its HUD strip and panned across, see `view`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Panorama {
+    /// Loaded along with the level, 184 pixels high to leave the strip
+    pub image: String,
+    /// Pixels per second the view pans
+    pub speed: f32,
+    #[serde(skip)]
+    pub handle: Handle<Image>,
+}
+
+/// Rain and fog from a point in the game on, until a later stage is reached
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct WeatherStage {
+    pub from_round: u32,
+    /// Or once ducks are this many times their usual speed, which is how far
+    /// endless mode has got since its round never changes
+    #[serde(default)]
+    pub from_speed: Option<f32>,
+    /// How hard it rains, from 0 to 1
+    #[serde(default)]
+    pub rain: f32,
+    /// How thick the fog over the top of the field is, from 0 to 1
+    #[serde(default)]
+    pub fog: f32,
+}
+
+impl WeatherStage {
+    fn reached(&self, round: u32, speed: f32) -> bool {
+        round >= self.from_round || self.from_speed.is_some_and(|from| speed >= from)
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct Level {
+    pub spawn_zones: Vec<SpawnZone>,
+    /// Behind the field, the usual #40c0ff when left out
+    #[serde(default)]
+    pub sky: Option<[f32; 3]>,
+    /// Back to front, a clear sky when left out
+    #[serde(default)]
+    pub clouds: Vec<CloudLayer>,
+    #[serde(default)]
+    pub panorama: Option<Panorama>,
+    /// In the order they're reached, clear skies when left out
+    #[serde(default)]
+    pub weather: Vec<WeatherStage>,
+}
+
+impl Level {
+    pub const DEFAULT_SKY: [f32; 3] = [0.251, 0.753, 1.0];
+
+    /// Zone for `roll` between 0 and 1, picked by weight
+    pub fn spawn_zone(&self, roll: f32) -> Option<&SpawnZone> {
+        let total: f32 = self.spawn_zones.iter().map(|zone| zone.weight).sum();
+        let mut left = roll * total;
+        for zone in &self.spawn_zones {
+            if left < zone.weight {
+                return Some(zone);
+            }
+            left -= zone.weight;
+        }
+        // Rounding can leave a sliver past the last one
+        self.spawn_zones.last()
+    }
+
+    /// The last stage reached by `round` or the difficulty's `speed`, if any
+    pub fn weather(&self, round: u32, speed: f32) -> Option<&WeatherStage> {
+        self.weather
+            .iter()
+            .rev()
+            .find(|stage| stage.reached(round, speed))
+    }
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+#[derive(Debug, Error)]
+pub enum LevelLoaderError {
+    #[error("could not read level: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse level: {0}")]
+    Ron(#[from] ron::de::SpannedError),
+}
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut level: Level = ron::de::from_bytes(&bytes)?;
+        if let Some(panorama) = &mut level.panorama {
+            panorama.handle = load_context.load(&panorama.image);
+        }
+        Ok(level)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
@@ -1,88 +1,1081 @@
-use std::time::Duration;
-
 use bevy::{
+    ecs::{component::HookContext, schedule::ScheduleLabel, world::DeferredWorld},
+    platform::{
+        collections::{HashMap, HashSet},
+        time::Instant,
+    },
     prelude::*,
-    window::{PrimaryWindow, WindowResolution},
+    sprite::Anchor,
+    window::PrimaryWindow,
+};
+
+pub mod accessibility;
+pub mod achievements;
+pub mod aim;
+pub mod alpha_mask;
+pub mod ammo_pickup;
+pub mod animation;
+#[cfg(feature = "aseprite")]
+pub mod aseprite;
+pub mod asset_pack;
+pub mod atlas;
+pub mod banner;
+pub mod be_the_duck;
+pub mod bitmap_text;
+pub mod boss;
+pub mod bot;
+#[cfg(feature = "clips")]
+pub mod clips;
+pub mod clouds;
+pub mod console;
+pub mod cursor;
+pub mod daily;
+pub mod day_night;
+#[cfg(feature = "debug")]
+pub mod debug;
+pub mod decoy;
+pub mod demo;
+pub mod diagnostics;
+#[cfg(feature = "discord")]
+pub mod discord;
+pub mod display;
+pub mod dog;
+pub mod duck_call;
+pub mod duck_look;
+pub mod duck_state;
+pub mod endless;
+pub mod events;
+pub mod flight;
+pub mod game_over;
+#[cfg(feature = "gyro")]
+pub mod gyro;
+pub mod high_score;
+pub mod high_visibility;
+pub mod hotseat;
+pub mod hud;
+#[cfg(feature = "leaderboard")]
+pub mod leaderboard;
+pub mod level;
+pub mod lifetime;
+#[cfg(feature = "light_gun")]
+pub mod light_gun;
+pub mod loading;
+pub mod locale;
+pub mod menu;
+pub mod mode;
+pub mod nes_timing;
+#[cfg(feature = "online")]
+pub mod online;
+pub mod packing;
+pub mod pause;
+pub mod photo;
+#[cfg(feature = "physics")]
+pub mod physics;
+pub mod power_up;
+pub mod profile;
+pub mod rng;
+#[cfg(feature = "rollback")]
+pub mod rollback;
+pub mod round_intro;
+pub mod rumble;
+pub mod score;
+pub mod screenshot;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod season;
+pub mod session;
+pub mod settings;
+pub mod shot_flash;
+pub mod sinden;
+pub mod skin;
+pub mod snapshot;
+pub mod sounds;
+pub mod spatial;
+pub mod spawn;
+pub mod species;
+pub mod speedrun;
+pub mod sprite_sheet;
+#[cfg(feature = "steam")]
+pub mod steam;
+pub mod storage;
+pub mod tally;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+pub mod time_attack;
+pub mod time_scale;
+pub mod training;
+pub mod tutorial;
+#[cfg(feature = "twitch")]
+pub mod twitch;
+pub mod versus;
+pub mod view;
+pub mod weapon;
+pub mod weather;
+pub mod wind;
+
+use bevy_asset_loader::prelude::*;
+use iyes_progress::ProgressPlugin;
+
+use accessibility::assisted_aim;
+use alpha_mask::{build_duck_alpha_mask, DuckAlphaMask, HitDetection};
+use animation::{
+    play_frame_animations, play_sprite_animations, AnimationClip, AnimationFinished, ClipId,
+    ClipMode, SpriteAnimation,
+};
+use atlas::rebuild_atlas_layouts;
+use be_the_duck::{
+    end_be_the_duck, possess_duck, setup_be_the_duck, steer_player_duck, update_stamina_bar,
+    PlayerDuck,
+};
+use boss::{move_boss, Boss, BOSS_EVERY};
+use duck_call::Lured;
+use duck_look::{DuckLook, DuckLooks};
+use duck_state::{
+    DuckStateCommands, Dying, Escaping, Falling, Flying, Heading, Launching, Perched, Shootable,
+};
+use endless::{escalate_endless, lose_lives, reset_endless, setup_endless};
+use events::{
+    AchievementUnlocked, AmmoCollected, DuckDamaged, DuckEscaped, DuckHit, DuckLanded, DuckSpawned,
+    PlayerId, PowerUpCollected, ResetGame, RoundEnded, ShotFired,
+};
+use flight::FlightPattern;
+use game_over::setup_game_over;
+use high_score::HighScores;
+use hotseat::{reset_hotseat, setup_hotseat, switch_turns};
+use hud::{animate_reload, setup_hud, update_round_text, update_score_text, update_weapon_icon};
+use level::{Level, LevelLoader};
+use loading::{
+    record_failed_assets, setup_error_screen, setup_loading_screen, track_loading, FailedAssets,
+};
+use menu::{handle_menu_buttons, highlight_menu_buttons, setup_menu};
+use mode::GameMode;
+use nes_timing::NesTiming;
+use packing::PackedSheet;
+use pause::{
+    auto_pause_on_focus_loss, count_down_to_resume, handle_resume_button, reset_auto_pause,
+    setup_pause_menu, toggle_pause, AutoPause,
+};
+use power_up::{apply_effects, collect_power_ups, drop_power_ups, float_power_ups, ActiveEffects};
+use rand::Rng;
+use rng::{seed_rngs, GameRng, RunSeed, SpawnRng};
+use round_intro::{end_round_intro, next_round_intro, start_round_intro};
+use score::{award_points, track_session_stats, Score, SessionStats};
+use serde::{Deserialize, Serialize};
+use session::{finish_restart, handle_session_buttons};
+use settings::{MouseAction, Settings};
+use skin::Skin;
+use spatial::SpatialIndex;
+use spawn::{species_sprite, Behaviour, SpawnDuckCommands, SpawnDuckEvent};
+use species::{Hitbox, Species, SpeciesTable};
+use speedrun::{setup_speedrun_timer, tick_speedrun_timer, SpeedrunTimer};
+use sprite_sheet::{SpriteSheet, SpriteSheetLoader};
+use storage::Storage;
+use time_attack::{reset_time_attack, setup_time_attack, tick_time_attack};
+use time_scale::{tick_game_time_scale, GameTimeScale};
+use training::{
+    draw_hitboxes, keep_training_targets, refill_training_ammo, setup_training,
+    update_accuracy_text,
 };
+use versus::{
+    award_versus_points, end_versus, handle_gamepad_reticle, move_mouse_reticle, reset_versus,
+    setup_versus, update_versus_score_text, Reticle,
+};
+use view::{ScreenFixed, View};
+use weapon::{
+    apply_weapon_table, equip_mode_loadout, handle_reload, switch_weapon, tick_weapon_cooldown,
+    EquippedWeapon, WeaponLoadout, WeaponModifiers, WeaponTable, WeaponTableLoader,
+};
+use wind::Wind;
 
 pub struct SimpleGamePlugin;
 
 impl Plugin for SimpleGamePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>()
+            .add_sub_state::<PauseState>()
+            .add_sub_state::<RoundPhase>()
             .add_event::<GameInputEvent>()
-            .add_systems(Startup, load_assets)
-            .add_systems(OnEnter(GameState::InGame), setup_world)
+            .configure_sets(
+                Update,
+                (
+                    FrameSet::Input,
+                    FrameSet::Simulation,
+                    FrameSet::Presentation,
+                )
+                    .chain(),
+            )
+            .add_event::<DuckSpawned>()
+            .add_event::<ShotFired>()
+            .add_event::<DuckHit>()
+            .add_event::<DuckDamaged>()
+            .add_event::<DuckEscaped>()
+            .add_event::<DuckLanded>()
+            .add_event::<RoundEnded>()
+            .add_event::<PowerUpCollected>()
+            .add_event::<AmmoCollected>()
+            .add_event::<AchievementUnlocked>()
+            .add_event::<AnimationFinished>()
+            .init_resource::<RunSeed>()
+            .init_resource::<GameRng>()
+            .init_resource::<GameTimeScale>()
+            .init_resource::<Score>()
+            .init_resource::<AutoPause>()
+            .init_resource::<SessionStats>()
+            .init_resource::<SpeedrunTimer>()
+            .init_resource::<GameMode>()
+            .init_resource::<HighScores>()
+            .init_resource::<Difficulty>()
+            .init_resource::<SimulationDriver>()
+            .init_resource::<Storage>()
+            .init_resource::<LaunchOptions>()
+            .init_resource::<SpatialIndex>()
+            .init_resource::<EquippedWeapon>()
+            .init_resource::<WeaponLoadout>()
+            .init_resource::<WeaponModifiers>()
+            .init_resource::<ActiveEffects>()
+            .init_resource::<DuckLooks>()
+            .init_asset::<WeaponTable>()
+            .init_asset_loader::<WeaponTableLoader>()
+            .init_asset::<Level>()
+            .init_asset_loader::<LevelLoader>()
+            .init_asset::<SpriteSheet>()
+            .init_asset_loader::<SpriteSheetLoader>()
+            .add_systems(Startup, setup_camera)
+            .add_systems(PreUpdate, tick_game_time_scale)
+            .add_systems(Update, (build_duck_alpha_mask, rebuild_atlas_layouts))
+            .add_plugins(ProgressPlugin::<GameState>::new())
+            .add_loading_state(
+                LoadingState::new(GameState::Loading)
+                    .on_failure_continue_to_state(GameState::Error)
+                    .load_collection::<GameAssets>(),
+            )
+            .add_systems(OnEnter(GameState::Loading), setup_loading_screen)
+            .init_resource::<FailedAssets>()
+            .add_systems(
+                Update,
+                (track_loading, record_failed_assets).run_if(in_state(GameState::Loading)),
+            )
+            .add_systems(OnEnter(GameState::Error), setup_error_screen)
+            .add_systems(OnEnter(GameState::Menu), setup_menu)
+            .add_systems(
+                Update,
+                handle_menu_buttons.run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(Update, (highlight_menu_buttons, handle_session_buttons))
+            .add_systems(OnEnter(GameState::Restarting), finish_restart)
+            .add_systems(OnEnter(PauseState::Paused), setup_pause_menu)
+            .add_systems(OnExit(PauseState::Paused), reset_auto_pause)
+            .add_systems(OnEnter(RoundPhase::Intro), start_round_intro)
             .add_systems(
                 Update,
                 (
-                    spawn_ducks,
-                    animate_ducks,
+                    end_round_intro.run_if(in_state(RoundPhase::Intro)),
+                    next_round_intro
+                        .after(run_simulation)
+                        .run_if(in_state(RoundPhase::Playing)),
+                )
+                    .run_if(in_state(PauseState::Running)),
+            )
+            .add_systems(
+                Update,
+                (
+                    (toggle_pause, auto_pause_on_focus_loss, count_down_to_resume)
+                        .chain()
+                        .run_if(resource_equals(SimulationDriver::Local)),
+                    handle_resume_button,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                OnEnter(GameState::InGame),
+                (
+                    seed_rngs,
+                    setup_world,
+                    equip_mode_loadout,
+                    // Shows the round setup_world starts on
+                    setup_hud.after(setup_world),
+                    setup_speedrun_timer,
+                ),
+            )
+            .add_systems(
+                OnEnter(GameState::InGame),
+                (
+                    setup_endless.run_if(resource_equals(GameMode::Endless)),
+                    setup_time_attack.run_if(resource_equals(GameMode::TimeAttack)),
+                    setup_hotseat.run_if(resource_equals(GameMode::Hotseat)),
+                    setup_versus.run_if(resource_equals(GameMode::Versus)),
+                    setup_be_the_duck.run_if(resource_equals(GameMode::BeTheDuck)),
+                    setup_training.run_if(resource_equals(GameMode::Training)),
+                )
+                    .after(setup_world),
+            )
+            .add_observer(reset_session)
+            .add_observer(reset_endless)
+            .add_observer(reset_hotseat)
+            .add_observer(reset_time_attack)
+            .add_observer(reset_versus)
+            .add_systems(OnEnter(GameState::GameOver), setup_game_over)
+            .init_schedule(Simulation)
+            .add_systems(
+                Simulation,
+                (
+                    spawn_ducks.run_if(in_state(RoundPhase::Playing)),
+                    (
+                        animate_launching_ducks,
+                        animate_flying_ducks,
+                        animate_perched_ducks,
+                        animate_dying_ducks,
+                        animate_falling_ducks,
+                        face_headings,
+                    )
+                        .before(play_sprite_animations),
+                    play_sprite_animations,
+                    end_shot_pause,
+                    launch_ducks,
+                    // Against where the ducks were drawn, before they move on,
+                    // and back along their last step to when the trigger was
+                    // pulled, see `PreviousPosition`
+                    handle_shoot_duck.before(remember_positions),
+                    remember_positions.before(move_ducks),
                     move_ducks,
-                    handle_mouse_clicks,
-                    handle_shoot_duck,
-                    handle_dying,
+                    move_boss.after(move_ducks),
+                    escape_ducks.after(move_ducks),
+                    animate_hit_flash,
+                    award_points,
+                    track_session_stats,
+                    handle_escaping,
                     handle_dead,
-                    animate_dog,
+                    track_round,
+                ),
+            )
+            .add_systems(
+                Simulation,
+                (
+                    tick_weapon_cooldown,
+                    handle_reload,
+                    drop_power_ups,
+                    float_power_ups,
+                    collect_power_ups,
+                    apply_effects,
+                ),
+            )
+            .add_systems(
+                Update,
+                run_simulation.in_set(FrameSet::Simulation).run_if(
+                    in_state(PauseState::Running)
+                        .and(resource_equals(SimulationDriver::Local))
+                        .and(not(resource_exists::<NesTiming>)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_mouse_clicks.in_set(FrameSet::Input).run_if(
+                        not(resource_equals(SimulationDriver::Rollback))
+                            .and(in_state(RoundPhase::Playing)),
+                    ),
+                    apply_weapon_table,
+                    switch_weapon,
+                    (
+                        update_weapon_icon,
+                        animate_reload,
+                        update_score_text,
+                        update_round_text,
+                        play_frame_animations,
+                    )
+                        .in_set(FrameSet::Presentation),
+                    tick_speedrun_timer,
                 )
-                    .run_if(in_state(GameState::InGame)),
+                    .run_if(in_state(PauseState::Running)),
+            )
+            .add_systems(
+                Update,
+                (escalate_endless, lose_lives)
+                    .run_if(in_state(PauseState::Running).and(resource_equals(GameMode::Endless))),
+            )
+            .add_systems(
+                Update,
+                // The turn's last points have to land before it's put away
+                switch_turns
+                    .after(run_simulation)
+                    .run_if(in_state(PauseState::Running).and(resource_equals(GameMode::Hotseat))),
+            )
+            .add_systems(
+                Update,
+                (move_mouse_reticle, handle_gamepad_reticle)
+                    .in_set(FrameSet::Input)
+                    .run_if(
+                        in_state(PauseState::Running)
+                            .and(in_state(RoundPhase::Playing))
+                            .and(resource_equals(GameMode::Versus))
+                            .and(resource_equals(SimulationDriver::Local)),
+                    ),
+            )
+            .add_systems(
+                Simulation,
+                (award_versus_points, end_versus).run_if(resource_equals(GameMode::Versus)),
+            )
+            .add_systems(
+                Update,
+                update_versus_score_text
+                    .in_set(FrameSet::Presentation)
+                    .run_if(in_state(PauseState::Running).and(resource_equals(GameMode::Versus))),
+            )
+            .add_systems(
+                Update,
+                (possess_duck, update_stamina_bar, end_be_the_duck).run_if(
+                    in_state(PauseState::Running).and(resource_equals(GameMode::BeTheDuck)),
+                ),
+            )
+            .add_systems(
+                Simulation,
+                // Joins the index after move_ducks has rebuilt it, and is
+                // shot at there next step
+                steer_player_duck
+                    .after(move_ducks)
+                    .run_if(resource_equals(GameMode::BeTheDuck)),
+            )
+            .add_systems(
+                Simulation,
+                (
+                    keep_training_targets,
+                    refill_training_ammo.after(handle_shoot_duck),
+                )
+                    .run_if(resource_equals(GameMode::Training)),
+            )
+            .add_systems(
+                Update,
+                (
+                    // Still drawn while paused, the gizmos only last a frame
+                    draw_hitboxes.run_if(in_state(GameState::InGame)),
+                    update_accuracy_text.run_if(in_state(PauseState::Running)),
+                )
+                    .run_if(resource_equals(GameMode::Training)),
+            )
+            .add_systems(
+                Update,
+                // The clock waits out the round intros
+                tick_time_attack.run_if(
+                    in_state(RoundPhase::Playing)
+                        .and(in_state(PauseState::Running))
+                        .and(resource_equals(GameMode::TimeAttack)),
+                ),
             );
+
+        app.add_plugins((
+            accessibility::AccessibilityPlugin,
+            achievements::AchievementsPlugin,
+            aim::AimPlugin,
+            ammo_pickup::AmmoPickupPlugin,
+            banner::BannerPlugin,
+            bitmap_text::BitmapTextPlugin,
+            bot::BotPlugin,
+            clouds::CloudsPlugin,
+            console::ConsolePlugin,
+            cursor::CursorPlugin,
+            day_night::DayNightPlugin,
+            decoy::DecoyPlugin,
+            demo::DemoPlugin,
+            diagnostics::DiagnosticsPlugin,
+            display::DisplayPlugin,
+        ))
+        // One tuple only takes so many plugins
+        .add_plugins((
+            dog::DogPlugin,
+            duck_call::DuckCallPlugin,
+            flight::FlightPlugin,
+            high_visibility::HighVisibilityPlugin,
+            lifetime::LifetimeStatsPlugin,
+            nes_timing::NesTimingPlugin,
+            packing::PackingPlugin,
+            photo::PhotoPlugin,
+            profile::ProfilePlugin,
+            rumble::RumblePlugin,
+            screenshot::ScreenshotPlugin,
+            settings::SettingsPlugin,
+            // Picks its language from the settings
+            locale::LocalePlugin,
+            shot_flash::ShotFlashPlugin,
+        ))
+        .add_plugins((
+            sinden::SindenPlugin,
+            skin::SkinPlugin,
+            sounds::SoundsPlugin,
+            spawn::SpawnPlugin,
+            species::SpeciesPlugin,
+            tally::TallyPlugin,
+            tutorial::TutorialPlugin,
+            view::ViewPlugin,
+            weather::WeatherPlugin,
+            wind::WindPlugin,
+        ));
+        #[cfg(feature = "aseprite")]
+        app.add_plugins(aseprite::AsepritePlugin);
+        #[cfg(feature = "clips")]
+        app.add_plugins(clips::ClipsPlugin);
+        #[cfg(feature = "debug")]
+        app.add_plugins(debug::DebugPlugin);
+        #[cfg(feature = "discord")]
+        app.add_plugins(discord::DiscordPlugin);
+        #[cfg(feature = "gyro")]
+        app.add_plugins(gyro::GyroPlugin);
+        #[cfg(feature = "leaderboard")]
+        app.add_plugins(leaderboard::LeaderboardPlugin);
+        #[cfg(feature = "light_gun")]
+        app.add_plugins(light_gun::LightGunPlugin);
+        #[cfg(feature = "online")]
+        app.add_plugins(online::OnlinePlugin);
+        #[cfg(feature = "physics")]
+        app.add_plugins(physics::DuckPhysicsPlugin);
+        // Rigid bodies take over once the duck starts falling
+        #[cfg(not(feature = "physics"))]
+        app.add_systems(Simulation, handle_dying);
+        #[cfg(feature = "rollback")]
+        app.add_plugins(rollback::RollbackPlugin);
+        #[cfg(feature = "scripting")]
+        app.add_plugins(scripting::ScriptingPlugin);
+        #[cfg(feature = "steam")]
+        app.add_plugins(steam::SteamPlugin);
+        #[cfg(feature = "telemetry")]
+        app.add_plugins(telemetry::TelemetryPlugin);
+        #[cfg(feature = "twitch")]
+        app.add_plugins(twitch::TwitchPlugin);
     }
 }
 
+/// Entities tagged `StateScoped` with one of these are despawned when the
+/// game leaves it
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+#[states(scoped_entities)]
 pub enum GameState {
     #[default]
     Loading,
+    Menu,
     InGame,
+    GameOver,
+    /// Passed through on a restart so leaving and entering `InGame` run again
+    Restarting,
+    /// Some of the game's files couldn't be loaded, nothing to do but quit
+    Error,
 }
 
-#[derive(Resource)]
+/// Only exists during a game, gameplay runs in `Running` so it stops while
+/// the pause menu is up
+#[derive(SubStates, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[source(GameState = GameState::InGame)]
+#[states(scoped_entities)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// Each round opens with its intro, ducks only come out while `Playing`
+#[derive(SubStates, Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[source(GameState = GameState::InGame)]
+#[states(scoped_entities)]
+pub enum RoundPhase {
+    #[default]
+    Intro,
+    Playing,
+}
+
+/// Everything the game draws or reads from disk, loaded by the `Loading`
+/// state and inserted once all of it is in
+#[derive(AssetCollection, Resource)]
 pub struct GameAssets {
+    #[asset(path = "textures/background_spritesheet.png")]
     background_spritesheet: Handle<Image>,
+    #[asset(texture_atlas_layout(tile_size_x = 256, tile_size_y = 240, columns = 3, rows = 2))]
     background_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "textures/duck.atlas.json")]
+    duck_sheet: Handle<SpriteSheet>,
+    #[asset(path = "textures/duck_single_spritesheet.png")]
     duck_spritesheet: Handle<Image>,
+    #[asset(path = "textures/duck.atlas.json#layout")]
     duck_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "textures/dawg_spritesheet.png")]
     dog_spritesheet: Handle<Image>,
+    #[asset(texture_atlas_layout(tile_size_x = 32, tile_size_y = 32, columns = 2, rows = 1))]
     dog_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "textures/weapon_icons.png")]
+    weapon_icons_spritesheet: Handle<Image>,
+    #[asset(texture_atlas_layout(tile_size_x = 16, tile_size_y = 8, columns = 3, rows = 1))]
+    weapon_icons_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "data/field.level.ron")]
+    level: Handle<Level>,
+    #[asset(path = "data/marsh.level.ron")]
+    marsh_level: Handle<Level>,
+    #[asset(path = "data/default.weapons.ron")]
+    weapon_table: Handle<WeaponTable>,
+    #[asset(path = "data/default.species.ron")]
+    species_table: Handle<SpeciesTable>,
+    #[asset(path = "textures/power_ups.png")]
+    power_ups_spritesheet: Handle<Image>,
+    #[asset(texture_atlas_layout(tile_size_x = 16, tile_size_y = 16, columns = 3, rows = 1))]
+    power_ups_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "textures/cloud.png")]
+    cloud: Handle<Image>,
+    #[asset(path = "textures/crosshair.png")]
+    crosshair: Handle<Image>,
+    #[asset(path = "textures/font.png")]
+    font: Handle<Image>,
+    #[asset(texture_atlas_layout(tile_size_x = 8, tile_size_y = 8, columns = 16, rows = 4))]
+    font_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "textures/skins/rubber_duck.atlas.json")]
+    rubber_duck_sheet: Handle<SpriteSheet>,
+    #[asset(path = "textures/skins/rubber_duck.png")]
+    rubber_duck_spritesheet: Handle<Image>,
+    #[asset(path = "textures/skins/rubber_duck.atlas.json#layout")]
+    rubber_duck_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "textures/skins/rubber_duck_dog.png")]
+    rubber_duck_dog_spritesheet: Handle<Image>,
+    #[asset(path = "textures/skins/goose.atlas.json")]
+    goose_sheet: Handle<SpriteSheet>,
+    #[asset(path = "textures/skins/goose.png")]
+    goose_spritesheet: Handle<Image>,
+    #[asset(path = "textures/skins/goose.atlas.json#layout")]
+    goose_layout: Handle<TextureAtlasLayout>,
+    #[asset(path = "textures/skins/goose_dog.png")]
+    goose_dog_spritesheet: Handle<Image>,
+    #[asset(path = "sounds/rain.wav")]
+    rain_sound: Handle<AudioSource>,
+    #[asset(path = "sounds/wing_flap.wav")]
+    wing_flap_sound: Handle<AudioSource>,
+    #[asset(path = "sounds/falling_whistle.wav")]
+    falling_whistle_sound: Handle<AudioSource>,
+    #[asset(path = "sounds/pump.wav")]
+    pump_sound: Handle<AudioSource>,
+    /// Which of the sheets above the duck and dog are drawn with, set by
+    /// `skin::apply_skin`
+    skin: Skin,
+    /// Where each sheet is in the packed image, empty until `packing` has
+    /// packed them
+    packed: HashMap<PackedSheet, URect>,
+    /// The packed image, the ducks and the dog are drawn from it once it's
+    /// there
+    packed_image: Option<Handle<Image>>,
+    /// Each duck look's frames, moved to where it is in the packed image
+    duck_layouts: HashMap<DuckLook, Handle<TextureAtlasLayout>>,
+}
+
+/// The handles one skin draws the ducks and the dog with
+struct SkinAssets<'a> {
+    duck_sheet: &'a Handle<SpriteSheet>,
+    duck_image: &'a Handle<Image>,
+    duck_layout: &'a Handle<TextureAtlasLayout>,
+    dog_image: &'a Handle<Image>,
+}
+
+impl GameAssets {
+    /// Each grid spritesheet with the layout cut from it. The ducks' layout
+    /// comes from their JSON, which is reloaded along with it.
+    fn atlases(&self) -> [(&Handle<Image>, &Handle<TextureAtlasLayout>); 5] {
+        [
+            (&self.background_spritesheet, &self.background_layout),
+            (self.dog_image(), &self.dog_layout),
+            (&self.weapon_icons_spritesheet, &self.weapon_icons_layout),
+            (&self.power_ups_spritesheet, &self.power_ups_layout),
+            (&self.font, &self.font_layout),
+        ]
+    }
+
+    fn skin_assets(&self) -> SkinAssets<'_> {
+        match self.skin {
+            Skin::Classic => SkinAssets {
+                duck_sheet: &self.duck_sheet,
+                duck_image: &self.duck_spritesheet,
+                duck_layout: &self.duck_layout,
+                dog_image: &self.dog_spritesheet,
+            },
+            Skin::RubberDuck => SkinAssets {
+                duck_sheet: &self.rubber_duck_sheet,
+                duck_image: &self.rubber_duck_spritesheet,
+                duck_layout: &self.rubber_duck_layout,
+                dog_image: &self.rubber_duck_dog_spritesheet,
+            },
+            Skin::Goose => SkinAssets {
+                duck_sheet: &self.goose_sheet,
+                duck_image: &self.goose_spritesheet,
+                duck_layout: &self.goose_layout,
+                dog_image: &self.goose_dog_spritesheet,
+            },
+        }
+    }
+
+    pub fn skin(&self) -> Skin {
+        self.skin
+    }
+
+    /// `rect` of the background spritesheet, moved to wherever it went in
+    /// the packed image
+    pub fn background_rect(&self, rect: Rect) -> Rect {
+        let offset = self
+            .packed
+            .get(&PackedSheet::Background)
+            .map_or(Vec2::ZERO, |packed| packed.min.as_vec2());
+        Rect {
+            min: rect.min + offset,
+            max: rect.max + offset,
+        }
+    }
+
+    /// Whether `sprite` is cut from the background spritesheet, the packed
+    /// image has the other sheets in it too
+    pub fn shows_background(&self, sprite: &Sprite) -> bool {
+        if sprite.image != self.background_spritesheet {
+            return false;
+        }
+        let Some(packed) = self.packed.get(&PackedSheet::Background) else {
+            return true;
+        };
+        match (&sprite.texture_atlas, sprite.rect) {
+            (Some(atlas), _) => atlas.layout == self.background_layout,
+            (None, Some(rect)) => packed.as_rect().contains(rect.center()),
+            (None, None) => false,
+        }
+    }
+
+    /// All of a sheet that's drawn whole
+    fn whole_sprite(&self, image: &Handle<Image>, sheet: PackedSheet) -> Sprite {
+        Sprite {
+            image: image.clone(),
+            rect: self.packed.get(&sheet).map(URect::as_rect),
+            ..default()
+        }
+    }
+
+    pub fn cloud_sprite(&self) -> Sprite {
+        self.whole_sprite(&self.cloud, PackedSheet::Cloud)
+    }
+
+    pub fn crosshair_sprite(&self) -> Sprite {
+        self.whole_sprite(&self.crosshair, PackedSheet::Crosshair)
+    }
+
+    /// The field `mode` is played on
+    pub fn level(&self, mode: GameMode) -> &Handle<Level> {
+        match mode {
+            GameMode::Marsh => &self.marsh_level,
+            _ => &self.level,
+        }
+    }
+
+    /// Ducks and the dog spawned from now on are drawn with `skin`, the
+    /// ones already out are left to `skin::apply_skin`
+    pub fn set_skin(&mut self, skin: Skin) {
+        self.skin = skin;
+    }
+
+    /// What the ducks are drawn from, the packed image once the skin's duck
+    /// sheet is in it
+    pub fn duck_image(&self) -> &Handle<Image> {
+        match &self.packed_image {
+            Some(image) if self.duck_layouts.contains_key(&DuckLook::Plain) => image,
+            _ => self.skin_duck_image(),
+        }
+    }
+
+    pub fn duck_layout(&self) -> &Handle<TextureAtlasLayout> {
+        self.duck_layouts
+            .get(&DuckLook::Plain)
+            .unwrap_or_else(|| self.skin_duck_layout())
+    }
+
+    /// The duck spritesheet of the skin in use as it was loaded, the looks
+    /// and the alpha mask are built from it
+    pub fn skin_duck_image(&self) -> &Handle<Image> {
+        self.skin_assets().duck_image
+    }
+
+    /// The frames of the duck spritesheet as it was loaded
+    pub fn skin_duck_layout(&self) -> &Handle<TextureAtlasLayout> {
+        self.skin_assets().duck_layout
+    }
+
+    /// What the dog is drawn from, the packed image once the skin's dog sheet
+    /// is in it. Every skin's is cut with the same grid.
+    pub fn dog_image(&self) -> &Handle<Image> {
+        match &self.packed_image {
+            Some(image) if self.packed.contains_key(&PackedSheet::Dog) => image,
+            _ => self.skin_dog_image(),
+        }
+    }
+
+    /// The dog spritesheet of the skin in use as it was loaded
+    pub fn skin_dog_image(&self) -> &Handle<Image> {
+        self.skin_assets().dog_image
+    }
+
+    /// The image and the layout to draw a duck showing `look` with, none if
+    /// that look isn't built yet
+    fn duck_look(
+        &self,
+        looks: &DuckLooks,
+        look: DuckLook,
+    ) -> Option<(Handle<Image>, Handle<TextureAtlasLayout>)> {
+        if let (Some(image), Some(layout)) = (&self.packed_image, self.duck_layouts.get(&look)) {
+            return Some((image.clone(), layout.clone()));
+        }
+        let image = match look {
+            DuckLook::Plain => self.skin_duck_image(),
+            _ => looks.get(look)?,
+        };
+        Some((image.clone(), self.skin_duck_layout().clone()))
+    }
+
+    /// Which look `sprite` is showing, none if it isn't drawn from the
+    /// ducks' sheets
+    pub fn look_of(&self, looks: &DuckLooks, sprite: &Sprite) -> Option<DuckLook> {
+        if self.packed_image.as_ref() == Some(&sprite.image) {
+            let layout = &sprite.texture_atlas.as_ref()?.layout;
+            return self
+                .duck_layouts
+                .iter()
+                .find(|(_, packed)| *packed == layout)
+                .map(|(look, _)| *look);
+        }
+        if sprite.image == *self.skin_duck_image() {
+            return Some(DuckLook::Plain);
+        }
+        looks
+            .iter()
+            .find(|(_, image)| **image == sprite.image)
+            .map(|(look, _)| look)
+    }
+
+    /// Draws `sprite` with `look` if it's built, keeping the frame it's on
+    pub fn show_look(&self, looks: &DuckLooks, sprite: &mut Sprite, look: DuckLook) {
+        let Some((image, layout)) = self.duck_look(looks, look) else {
+            return;
+        };
+        sprite.image = image;
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            atlas.layout = layout;
+        }
+    }
+
+    pub fn dog_layout(&self) -> &Handle<TextureAtlasLayout> {
+        &self.dog_layout
+    }
+
+    /// First frame of the duck spritesheet, the animation takes it from there
+    pub fn duck_sprite(&self) -> Sprite {
+        Sprite::from_atlas_image(
+            self.duck_image().clone(),
+            TextureAtlas {
+                layout: self.duck_layout().clone(),
+                index: 0,
+            },
+        )
+    }
+
+    /// Index of a frame of the skin's duck spritesheet by its name in its
+    /// JSON, e.g. `duck_fly_0`. Every skin's clips are cut from these names.
+    pub fn duck_frame(&self, sheets: &Assets<SpriteSheet>, name: &str) -> Option<usize> {
+        sheets.get(self.skin_assets().duck_sheet)?.index(name)
+    }
 }
 
-#[derive(Component, Deref, DerefMut)]
-struct AnimationTimer(Timer);
+// Frames a second of the duck clips, the boss flaps twice as fast
+const DUCK_FPS: f32 = 2.0;
 
-#[derive(Component)]
+/// Whatever spawns a duck gets the rest of what one needs, and it goes
+/// away with the rest of the world. It has to come with one of the states
+/// in `duck_state` though. One spawned without a sprite of its own gets its
+/// species' sprite, see `fill_in_duck_sprite`.
+#[derive(Component, Clone, Serialize, Deserialize)]
+#[component(on_add = fill_in_duck_sprite)]
+#[require(
+    Sprite,
+    Transform,
+    SpriteAnimation,
+    FlightPattern,
+    PreviousPosition,
+    Species,
+    Hitbox,
+    StateScoped<GameState> = StateScoped(GameState::InGame)
+)]
 pub struct Duck {
-    behaviour: DuckBehaviour,
     speed: f32,
+    points: u32,
 }
 
 impl Default for Duck {
     fn default() -> Self {
         Self {
-            behaviour: Default::default(),
             speed: 20.0,
+            points: 500,
+        }
+    }
+}
+
+/// The required `Sprite` is blank, this swaps it for the species' sprite
+/// before any `OnAdd` observer looks at it. A sprite that came with the duck
+/// is left alone.
+fn fill_in_duck_sprite(mut world: DeferredWorld, context: HookContext) {
+    let Some(&species) = world.get::<Species>(context.entity) else {
+        return;
+    };
+    let Some(sprite) = world
+        .get_resource::<GameAssets>()
+        .map(|game_assets| species_sprite(game_assets, species))
+    else {
+        return;
+    };
+    if let Some(mut current) = world.get_mut::<Sprite>(context.entity) {
+        if current.image == Handle::default() {
+            *current = sprite;
+        }
+    }
+}
+
+/// Where a duck was drawn the frame before last. Shots are tested before
+/// the ducks move, and one fired part way through the last frame is tested
+/// the same part of the way from here to where the duck was last drawn, so
+/// fast ducks at low frame rates are where they were when the trigger was
+/// pulled.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct PreviousPosition(pub Vec2);
+
+/// How the game was started, usually from the command line. Insert it before
+/// adding the plugin to change it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// Seed for every game instead of a random one, daily challenges keep
+    /// their own
+    pub seed: Option<u64>,
+    /// Skips the menu and goes straight into a game of this mode
+    pub mode: Option<GameMode>,
+    /// Round games start on
+    pub round: u32,
+    /// Steps the game at the NES's frame rate, see `nes_timing`
+    pub nes_timing: bool,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            mode: None,
+            round: 1,
+            nes_timing: false,
         }
     }
 }
 
-#[derive(Default, PartialEq)]
-pub enum DuckBehaviour {
+/// Whether this app runs the duck simulation itself or only shows what an
+/// online host sends it. Input, the HUD and the mode's presentation keep
+/// running either way.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SimulationDriver {
+    /// Stepped once a frame from `Update`, or once an NES frame from
+    /// `FixedUpdate` with `--nes-timing`
     #[default]
-    FlyingLeft,
-    FlyingRight,
-    Dying,
+    Local,
+    Remote,
+    /// Stepped at a fixed rate by rollback netcode, which may run it again
+    /// for frames it has to correct
+    Rollback,
 }
 
-#[derive(Component, Default)]
-struct Dog;
+/// Everything that moves the ducks along and changes the score. Keeping it
+/// apart from input and presentation lets it be stepped by something other
+/// than the frame loop.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Simulation;
 
-pub fn setup_world(mut commands: Commands, game_assets: Res<GameAssets>) {
-    // Create a 2d camera
-    commands.spawn((Camera2d));
-    // Duck hunt background colour is #40c0ff
-    commands.insert_resource(ClearColor(Color::linear_rgb(0.251, 0.753, 1.0)));
+/// The order `Update` goes in each frame. Input only reads what the player
+/// did, so shots are aimed at the ducks as they were last drawn, the
+/// simulation then takes them in before anything moves, and presentation
+/// shows whatever came of it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FrameSet {
+    Input,
+    Simulation,
+    Presentation,
+}
+
+pub fn run_simulation(world: &mut World) {
+    world.run_schedule(Simulation);
+}
+
+// Multiplies the speed of newly spawned ducks, modes ramp this up
+#[derive(Resource, Clone)]
+pub struct Difficulty {
+    pub speed: f32,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self { speed: 1.0 }
+    }
+}
+
+// Ducks without health go down to a single full shot
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Health {
+    pub current: f32,
+}
+
+// Blinks the sprite for a moment after taking a hit that didn't kill
+#[derive(Component, Clone, Deref, DerefMut)]
+pub struct HitFlash(Timer);
+
+pub fn setup_camera(mut commands: Commands) {
+    // Create a 2d camera, it sticks around for every screen
+    commands.spawn(Camera2d);
+}
+
+pub fn setup_world(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    levels: Res<Assets<Level>>,
+    launch: Res<LaunchOptions>,
+    mode: Res<GameMode>,
+) {
+    let level = levels.get(game_assets.level(*mode));
+    // Duck hunt background colour is #40c0ff, unless the level has its own
+    let [red, green, blue] = level
+        .and_then(|level| level.sky)
+        .unwrap_or(Level::DEFAULT_SKY);
+    commands.insert_resource(ClearColor(Color::linear_rgb(red, green, blue)));
     // Duck spawn timer
     commands.insert_resource(SpawnTimer(Timer::from_seconds(1.0, TimerMode::Repeating)));
+    commands.insert_resource(Round::new(launch.round));
+    commands.insert_resource(Score::default());
+    commands.insert_resource(SessionStats::default());
+    commands.insert_resource(Difficulty::default());
+    // A panorama goes above the background's HUD strip, which stays on
+    // screen while the view pans
+    if let Some(panorama) = level.and_then(|level| level.panorama.as_ref()) {
+        commands.spawn((
+            Sprite {
+                image: panorama.handle.clone(),
+                anchor: Anchor::TopCenter,
+                ..default()
+            },
+            Transform::from_xyz(0.0, 120.0, 1.0),
+            StateScoped(GameState::InGame),
+        ));
+        commands.spawn((
+            Sprite {
+                image: game_assets.background_spritesheet.clone(),
+                rect: Some(game_assets.background_rect(Rect::new(
+                    0.0,
+                    HUD_STRIP_TOP,
+                    256.0,
+                    240.0,
+                ))),
+                anchor: Anchor::BottomCenter,
+                ..default()
+            },
+            Transform::from_xyz(0.0, -120.0, 1.0),
+            ScreenFixed,
+            StateScoped(GameState::InGame),
+        ));
+        return;
+    }
     // Duck hunt background
     commands.spawn((
         Sprite::from_atlas_image(
@@ -93,244 +1086,865 @@ pub fn setup_world(mut commands: Commands, game_assets: Res<GameAssets>) {
             },
         ),
         Transform::from_xyz(0.0, 0.0, 1.0),
-    ));
-
-    commands.spawn((
-        Sprite::from_atlas_image(
-            game_assets.dog_spritesheet.clone(),
-            TextureAtlas {
-                layout: game_assets.dog_layout.clone(),
-                index: 0,
-            },
-        ),
-        Transform::from_xyz(0.0, -20.0, 0.0),
-        Dog::default(),
+        StateScoped(GameState::InGame),
     ));
 }
 
-pub fn load_assets(
+/// Puts back what a game leaves behind that the next one doesn't set up
+/// itself. Runs on `ResetGame` rather than leaving `InGame` since the game
+/// over screen still reads the score.
+pub fn reset_session(
+    _trigger: Trigger<ResetGame>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    mut next_state: ResMut<NextState<GameState>>,
+    mut equipped: ResMut<EquippedWeapon>,
+    mut time_scale: ResMut<GameTimeScale>,
 ) {
-    println!("Loading assets");
-    let bg_texture = asset_server.load("textures/background_spritesheet.png");
-    let bg_layout = TextureAtlasLayout::from_grid(UVec2::new(256, 240), 3, 2, None, None);
-    let bg_texture_atlas_layout = texture_atlas_layouts.add(bg_layout);
-
-    let ducks_texture = asset_server.load("textures/duck_single_spritesheet.png");
-    let ducks_layout = TextureAtlasLayout::from_grid(UVec2::new(32, 32), 5, 1, None, None);
-    let ducks_texture_atlas_layout = texture_atlas_layouts.add(ducks_layout);
-
-    let dog_texture = asset_server.load("textures/dawg_spritesheet.png");
-    let dog_layout = TextureAtlasLayout::from_grid(UVec2::new(32, 32), 2, 1, None, None);
-    let dog_texture_atlas_layout = texture_atlas_layouts.add(dog_layout);
-
-    commands.insert_resource(GameAssets {
-        background_spritesheet: bg_texture,
-        background_layout: bg_texture_atlas_layout,
-        duck_spritesheet: ducks_texture,
-        duck_layout: ducks_texture_atlas_layout,
-        dog_spritesheet: dog_texture,
-        dog_layout: dog_texture_atlas_layout,
-    });
-    println!("Finished loading");
-    next_state.set(GameState::InGame);
-}
-
-fn animate_ducks(
-    time: Res<Time>,
-    mut duck_query: Query<(&mut AnimationTimer, &mut Sprite, &Duck)>,
+    commands.remove_resource::<SpawnTimer>();
+    commands.insert_resource(SpatialIndex::default());
+    commands.insert_resource(ActiveEffects::default());
+    commands.insert_resource(WeaponModifiers::default());
+    commands.insert_resource(Score::default());
+    commands.insert_resource(SessionStats::default());
+    equipped.refill();
+    time_scale.reset();
+}
+
+/// The ducks' clips, cut from the named frames of the duck sheet
+struct DuckClips {
+    takeoff: AnimationClip,
+    fly: AnimationClip,
+    perched: AnimationClip,
+    splat: AnimationClip,
+    falling: AnimationClip,
+}
+
+impl DuckClips {
+    const SPLAT: ClipId = ClipId("duck_splat");
+
+    fn new(game_assets: &GameAssets, sheets: &Assets<SpriteSheet>) -> Option<Self> {
+        let frame = |name| game_assets.duck_frame(sheets, name);
+        let (fly_first, fly_last) = (frame("duck_fly_0")?, frame("duck_fly_2")?);
+        let (hit, fall) = (frame("duck_hit")?, frame("duck_fall")?);
+        Some(Self {
+            // Same flap, just frantic
+            takeoff: AnimationClip::new(
+                ClipId("duck_takeoff"),
+                fly_first,
+                fly_last,
+                DUCK_FPS * 4.0,
+                ClipMode::Loop,
+            ),
+            fly: AnimationClip::new(
+                ClipId("duck_fly"),
+                fly_first,
+                fly_last,
+                DUCK_FPS,
+                ClipMode::Loop,
+            ),
+            // Wings still, there's no sitting frame
+            perched: AnimationClip::new(
+                ClipId("duck_perched"),
+                fly_first,
+                fly_first,
+                DUCK_FPS,
+                ClipMode::Once,
+            ),
+            splat: AnimationClip::new(Self::SPLAT, hit, hit, DUCK_FPS, ClipMode::Once),
+            falling: AnimationClip::new(
+                ClipId("duck_falling"),
+                fall,
+                fall,
+                DUCK_FPS,
+                ClipMode::Loop,
+            ),
+        })
+    }
+}
+
+// The clip only has to be picked when a duck enters a state, the states are
+// taken off and put back on so `Added` catches every one
+fn animate_launching_ducks(
+    game_assets: Res<GameAssets>,
+    sheets: Res<Assets<SpriteSheet>>,
+    mut duck_query: Query<&mut SpriteAnimation, Added<Launching>>,
 ) {
-    for (mut timer, mut sprite, duck) in &mut duck_query {
-        timer.tick(time.delta());
-        // Animate duck depending on behaviour
-        match duck.behaviour {
-            DuckBehaviour::FlyingLeft => {
-                sprite.flip_x = true;
-
-                // If the timer finished show the next animation frame
-                if timer.just_finished() {
-                    if let Some(atlas) = &mut sprite.texture_atlas {
-                        atlas.index += 1;
-                        if atlas.index >= 3 {
-                            atlas.index = 0;
-                        }
-                    }
-                }
-            }
-            DuckBehaviour::FlyingRight => {
-                sprite.flip_x = false;
-
-                // If the timer finished show the next animation frame
-                if timer.just_finished() {
-                    if let Some(atlas) = &mut sprite.texture_atlas {
-                        atlas.index += 1;
-                        if atlas.index >= 3 {
-                            atlas.index = 0;
-                        }
-                    }
-                }
-            }
-            DuckBehaviour::Dying => {
-                // Let the splat animation play once
-                if let Some(atlas) = &mut sprite.texture_atlas {
-                    if atlas.index < 3 {
-                        atlas.index = 3;
-                        timer.reset();
-                    }
-                }
-                // If the timer finished show the next animation frame
-                if timer.just_finished() {
-                    sprite.flip_x = !sprite.flip_x;
-                    if let Some(atlas) = &mut sprite.texture_atlas {
-                        atlas.index += 1;
-
-                        if atlas.index >= 4 {
-                            atlas.index = 4;
-                        }
-                    }
-                }
-            }
+    let Some(clips) = DuckClips::new(&game_assets, &sheets) else {
+        return;
+    };
+    for mut animation in &mut duck_query {
+        animation.play(clips.takeoff);
+    }
+}
+
+// Flies out the same way it flies around
+#[allow(clippy::type_complexity)]
+fn animate_flying_ducks(
+    game_assets: Res<GameAssets>,
+    sheets: Res<Assets<SpriteSheet>>,
+    mut duck_query: Query<&mut SpriteAnimation, Or<(Added<Flying>, Added<Escaping>)>>,
+) {
+    let _span = info_span!("animate_flying_ducks").entered();
+    let Some(clips) = DuckClips::new(&game_assets, &sheets) else {
+        return;
+    };
+    for mut animation in &mut duck_query {
+        animation.play(clips.fly);
+    }
+}
+
+fn animate_perched_ducks(
+    game_assets: Res<GameAssets>,
+    sheets: Res<Assets<SpriteSheet>>,
+    mut duck_query: Query<&mut SpriteAnimation, Added<Perched>>,
+) {
+    let Some(clips) = DuckClips::new(&game_assets, &sheets) else {
+        return;
+    };
+    for mut animation in &mut duck_query {
+        animation.play(clips.perched);
+    }
+}
+
+// Only ducks whose heading could have changed, whatever moves them only
+// writes it when it does
+fn face_headings(
+    mut launching_query: Query<(&mut Sprite, &Launching), Changed<Launching>>,
+    mut flying_query: Query<(&mut Sprite, &Flying), Changed<Flying>>,
+    mut escaping_query: Query<(&mut Sprite, &Escaping), Changed<Escaping>>,
+    mut perched_query: Query<(&mut Sprite, &Perched), Added<Perched>>,
+) {
+    let headings = launching_query
+        .iter_mut()
+        .map(|(sprite, launching)| (sprite, launching.heading))
+        .chain(
+            flying_query
+                .iter_mut()
+                .map(|(sprite, flying)| (sprite, flying.heading)),
+        )
+        .chain(
+            escaping_query
+                .iter_mut()
+                .map(|(sprite, escaping)| (sprite, escaping.heading)),
+        )
+        .chain(
+            perched_query
+                .iter_mut()
+                .map(|(sprite, perched)| (sprite, perched.heading)),
+        );
+    for (mut sprite, heading) in headings {
+        if sprite.flip_x != heading.flip_x() {
+            sprite.flip_x = heading.flip_x();
+        }
+    }
+}
+
+fn animate_dying_ducks(
+    game_assets: Res<GameAssets>,
+    sheets: Res<Assets<SpriteSheet>>,
+    mut duck_query: Query<&mut SpriteAnimation, Added<Dying>>,
+) {
+    let Some(clips) = DuckClips::new(&game_assets, &sheets) else {
+        return;
+    };
+    for mut animation in &mut duck_query {
+        animation.play(clips.splat);
+    }
+}
+
+fn animate_falling_ducks(
+    game_assets: Res<GameAssets>,
+    sheets: Res<Assets<SpriteSheet>>,
+    mut duck_query: Query<(&mut SpriteAnimation, &mut Sprite), With<Falling>>,
+) {
+    let Some(clips) = DuckClips::new(&game_assets, &sheets) else {
+        return;
+    };
+    for (mut animation, mut sprite) in &mut duck_query {
+        // Tumbles on the way down
+        if animation.clip() != clips.falling {
+            animation.play(clips.falling);
+        } else if animation.stepped() {
+            sprite.flip_x = !sprite.flip_x;
         }
+    }
+}
 
-        // If the timer finished show the next animation frame
-        if timer.just_finished() {
-            if let Some(atlas) = &mut sprite.texture_atlas {}
+fn end_shot_pause(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut commands: Commands,
+    mut duck_query: Query<(Entity, &mut Dying)>,
+) {
+    let delta = time_scale.delta(&time);
+    for (entity, mut dying) in &mut duck_query {
+        if dying.pause.tick(delta).finished() {
+            commands.entity(entity).set_duck_state(Falling);
         }
     }
 }
 
-#[derive(Resource)]
-struct SpawnTimer(Timer);
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct SpawnTimer(Timer);
+
+// The NES releases 10 ducks per round
+const DUCKS_PER_ROUND: u32 = 10;
+/// Ducks take off from here, just out of sight behind the grass
+pub const GRASS_Y: f32 = -56.0;
+// Background rows from here down are the HUD strip, a panorama stops here
+const HUD_STRIP_TOP: f32 = 184.0;
+// Pixels per second of the burst out of the grass
+const LAUNCH_SPEED: f32 = 90.0;
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct Round {
+    pub number: u32,
+    pub ducks_total: u32,
+    pub ducks_spawned: u32,
+    pub ducks_hit: u32,
+    pub ducks_escaped: u32,
+}
+
+impl Round {
+    pub fn new(number: u32) -> Self {
+        let mut round = Self {
+            number,
+            ducks_total: DUCKS_PER_ROUND,
+            ducks_spawned: 0,
+            ducks_hit: 0,
+            ducks_escaped: 0,
+        };
+        // Boss rounds are just the one duck
+        if round.is_boss_round() {
+            round.ducks_total = 1;
+        }
+        round
+    }
+
+    pub fn is_boss_round(&self) -> bool {
+        self.number.is_multiple_of(BOSS_EVERY)
+    }
+
+    /// Hits the NES asks for to clear the round, it goes up in the later
+    /// rounds until every duck has to go down
+    pub fn ducks_required(&self) -> u32 {
+        let required = match self.number {
+            0..=10 => 6,
+            11..=12 => 7,
+            13..=14 => 8,
+            15..=19 => 9,
+            _ => 10,
+        };
+        required.min(self.ducks_total)
+    }
+}
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_ducks(
     mut commands: Commands,
     mut timer: ResMut<SpawnTimer>,
+    mut round: ResMut<Round>,
+    mut spawn_rng: ResMut<SpawnRng>,
+    difficulty: Res<Difficulty>,
     game_assets: Res<GameAssets>,
+    levels: Res<Assets<Level>>,
+    mode: Res<GameMode>,
     time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
 ) {
-    timer.0.tick(time.delta());
-    if timer.0.just_finished() {
-        // Spawn a duck
-        let our_sins = time.elapsed_secs().sin();
-        let x = our_sins * 120.0;
-        commands.spawn((
-            Sprite::from_atlas_image(
-                game_assets.duck_spritesheet.clone(),
-                TextureAtlas {
-                    layout: game_assets.duck_layout.clone(),
-                    index: 0,
-                },
-            ),
-            Transform::from_xyz(x, -40.0, 0.0),
-            Duck {
-                behaviour: match our_sins {
-                    -1.0..0.0 => DuckBehaviour::FlyingRight,
-                    0.0..1.0 => DuckBehaviour::FlyingLeft,
-                    _ => panic!("WHAT???"),
-                },
-                speed: (our_sins * our_sins) * 80.0 + 20.0,
-            },
-            AnimationTimer(Timer::from_seconds(0.5, TimerMode::Repeating)),
-        ));
+    timer.0.tick(time_scale.delta(&time));
+    if timer.0.just_finished() && round.ducks_spawned < round.ducks_total {
+        let _span = info_span!("spawn_duck", round = round.number).entered();
+        if round.is_boss_round() {
+            commands.spawn_duck(SpawnDuckEvent {
+                species: Species::Boss,
+                position: Vec2::new(0.0, -40.0),
+                behaviour: Behaviour::Fly(Heading::Right),
+            });
+            round.ducks_spawned += 1;
+            return;
+        }
+        // Spawn a duck, where and how fast comes from the run seed
+        let roll: f32 = spawn_rng.gen_range(-1.0..1.0);
+        let (zone_roll, x_roll): (f32, f32) = (spawn_rng.gen(), spawn_rng.gen());
+        let zone = levels
+            .get(game_assets.level(*mode))
+            .and_then(|level| level.spawn_zone(zone_roll));
+        // Anywhere along the grass if the level has no zones
+        let x = zone.map_or(roll * 120.0, |zone| zone.x(x_roll));
+        let heading = zone.map_or(Heading::towards_center(x), |zone| zone.heading(x));
+        commands
+            .spawn_duck(SpawnDuckEvent {
+                species: Species::Regular,
+                position: Vec2::new(x, GRASS_Y),
+                behaviour: Behaviour::TakeOff(heading),
+            })
+            .insert(Duck {
+                speed: ((roll * roll) * 80.0 + 20.0) * difficulty.speed,
+                ..default()
+            });
+        round.ducks_spawned += 1;
     }
 }
 
-fn move_ducks(time: Res<Time>, mut duck_query: Query<(&mut Transform, &mut Duck), Without<Dead>>) {
-    for (mut transform, mut duck) in duck_query {
-        if duck.behaviour == DuckBehaviour::Dying {
-            continue;
+// Straight up out of the grass, then off the way it was facing
+fn launch_ducks(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    duck_query: Query<(Entity, &mut Transform, &Launching), Without<Dead>>,
+) {
+    let delta = time_scale.delta_secs(&time);
+    for (entity, mut transform, launching) in duck_query {
+        transform.translation.y += LAUNCH_SPEED * delta;
+        if transform.translation.y >= flight::FLOOR {
+            commands.entity(entity).set_duck_state(Flying {
+                heading: launching.heading,
+            });
         }
-        let x_speed = if duck.behaviour == DuckBehaviour::FlyingRight {
-            duck.speed
-        } else {
-            -duck.speed
+    }
+}
+
+// Straight up and out, the index has been rebuilt by `move_ducks` by now
+#[allow(clippy::type_complexity)]
+fn escape_ducks(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut spatial_index: ResMut<SpatialIndex>,
+    duck_query: Query<(Entity, &mut Transform, &Duck), (With<Escaping>, Without<Dead>)>,
+) {
+    let delta = time_scale.delta_secs(&time);
+    for (entity, mut transform, duck) in duck_query {
+        transform.translation.y += duck.speed * delta;
+        spatial_index.insert(entity, transform.translation.xy());
+    }
+}
+
+// After the shots, before anything moves them this step
+fn remember_positions(mut duck_query: Query<(&Transform, &mut PreviousPosition), With<Duck>>) {
+    for (transform, mut previous) in &mut duck_query {
+        previous.0 = transform.translation.xy();
+    }
+}
+
+/// Flies every duck that isn't the boss or played by someone, and fills the
+/// `SpatialIndex` for the shots after it. Public for the benchmarks.
+#[allow(clippy::type_complexity)]
+pub fn move_ducks(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    wind: Res<Wind>,
+    view: Res<View>,
+    mut spatial_index: ResMut<SpatialIndex>,
+    duck_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &Duck,
+            &mut Flying,
+            &mut FlightPattern,
+            Option<&Lured>,
+        ),
+        (Without<Dead>, Without<Boss>, Without<PlayerDuck>),
+    >,
+) {
+    let _span = info_span!("move_ducks").entered();
+    // Only ducks that can be shot go in the index, the ones escaping or
+    // perched are added after
+    spatial_index.clear();
+    for (entity, mut transform, duck, mut flying, mut pattern, lured) in duck_query {
+        // A call or a decoy turns it towards the spot for a moment
+        let y_speed = match lured {
+            Some(lured) => {
+                let heading = lured.heading(transform.translation.x);
+                if flying.heading != heading {
+                    flying.heading = heading;
+                }
+                lured.vertical_speed(transform.translation.y, duck.speed)
+            }
+            None => pattern.vertical_speed(duck.speed),
         };
-        transform.translation.x += x_speed * time.delta_secs();
-        transform.translation.y += duck.speed * time.delta_secs();
-        if transform.translation.x > 120.0 {
-            duck.behaviour = DuckBehaviour::FlyingLeft;
+        let x_speed = match flying.heading {
+            Heading::Right => duck.speed,
+            Heading::Left => -duck.speed,
+        };
+        let delta = time_scale.delta_secs(&time);
+        transform.translation.x += (x_speed + wind.0) * delta;
+        transform.translation.y += y_speed * delta;
+        if transform.translation.x > view.max_x() && flying.heading != Heading::Left {
+            flying.heading = Heading::Left;
         }
-        if transform.translation.x < -120.0 {
-            duck.behaviour = DuckBehaviour::FlyingRight;
+        if transform.translation.x < view.min_x() && flying.heading != Heading::Right {
+            flying.heading = Heading::Right;
         }
+        // Wind stronger than the duck would carry it off the edge it just
+        // turned around at
+        transform.translation.x = transform.translation.x.clamp(view.min_x(), view.max_x());
+        pattern.keep_inside(transform.translation.y);
+        spatial_index.insert(entity, transform.translation.xy());
     }
 }
 
-fn animate_dog(time: Res<Time>, mut dog_query: Query<(&mut Transform, &mut Dog)>) {
-    for (mut transform, mut duck) in dog_query {
-        transform.translation.y = (time.elapsed_secs() * 20.0).sin() * 4.0 - 20.0;
-    }
+/// Raw input from any of the pointers, before the weapon decides whether
+/// it actually fires
+#[derive(Event, Debug, Clone, Copy)]
+pub enum GameInputEvent {
+    /// Fires at a spot. The time is when the trigger was pulled, for inputs
+    /// that know better than the frame they were read on
+    Shoot(PlayerId, Vec2, Option<Instant>),
+    /// Blows the duck call at a spot
+    Call(PlayerId, Vec2),
+    /// Sets the decoy down on the grass below a spot
+    Decoy(PlayerId, Vec2),
+    /// Puts fresh shells in
+    Reload(PlayerId),
 }
 
-#[derive(Event)]
-enum GameInputEvent {
-    Shoot(Vec2),
+/// Best guess at when a button read this frame was pressed. The window only
+/// passes input on between frames, so it was some time during the last one
+/// and half way through is as close as it gets.
+pub fn mid_frame(real: &Time<Real>) -> Option<Instant> {
+    real.last_update()?.checked_sub(real.delta() / 2)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_mouse_clicks(
+    real: Res<Time<Real>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
     window_query: Query<&Window, With<PrimaryWindow>>,
+    equipped: Res<EquippedWeapon>,
+    modifiers: Res<WeaponModifiers>,
+    settings: Res<Settings>,
+    play_area: Res<sinden::PlayArea>,
     mut game_input_event_writer: EventWriter<GameInputEvent>,
 ) {
-    let win = window_query.get_single().unwrap();
-    if mouse_input.just_pressed(MouseButton::Left) {
+    let shoot = settings.mouse.button(MouseAction::Shoot);
+    // Headless runs have no window to click in
+    let Ok(win) = window_query.single() else {
+        return;
+    };
+    // Shooting off the screen reloads, like pointing a light gun away
+    if keyboard_input.just_pressed(KeyCode::KeyR)
+        || (mouse_input.just_pressed(shoot) && win.cursor_position().is_none())
+    {
+        game_input_event_writer.write(GameInputEvent::Reload(PlayerId(0)));
+        return;
+    }
+    // Automatic weapons keep pulling the trigger while held, the weapon
+    // cooldown takes care of the fire rate
+    let trigger = if equipped.automatic(&modifiers) {
+        mouse_input.pressed(shoot)
+    } else {
+        mouse_input.just_pressed(shoot)
+    };
+    if trigger {
         let position = win.cursor_position();
         if let Some(position) = position {
             // This will be offset by the window size, lets translate it to
             // our world. There is a more universal solution to this but since
             // we have the precise window size we can use this easier "hack"
-            let position = Vec2::new(position.x - 256.0 / 2.0, 240.0 / 2.0 - position.y);
-            println!("click at world position: {:?}", position);
-            game_input_event_writer.write(GameInputEvent::Shoot(position));
+            let position = play_area.to_world(position);
+            game_input_event_writer.write(GameInputEvent::Shoot(
+                PlayerId(0),
+                position,
+                mid_frame(&real),
+            ));
         }
     }
 }
 
-fn handle_shoot_duck(
+/// Fires the weapon for every shot and downs the ducks it hits. Public for
+/// the benchmarks.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn handle_shoot_duck(
     mut commands: Commands,
-    mut duck_query: Query<(Entity, &Transform, &mut Duck)>,
+    real: Res<Time<Real>>,
+    spatial_index: Res<SpatialIndex>,
+    alpha_mask: Option<Res<DuckAlphaMask>>,
+    mut equipped: ResMut<EquippedWeapon>,
+    mut player_weapon_query: Query<(&Reticle, &mut EquippedWeapon)>,
+    modifiers: Res<WeaponModifiers>,
+    settings: Res<Settings>,
+    driver: Res<SimulationDriver>,
+    nes_timing: Option<Res<NesTiming>>,
+    mut duck_query: Query<
+        (
+            &Transform,
+            &PreviousPosition,
+            &Hitbox,
+            &Sprite,
+            &Duck,
+            Option<&mut Health>,
+        ),
+        Shootable,
+    >,
     mut game_input_event_reader: EventReader<GameInputEvent>,
+    mut shot_fired_writer: EventWriter<ShotFired>,
+    mut duck_hit_writer: EventWriter<DuckHit>,
+    mut duck_damaged_writer: EventWriter<DuckDamaged>,
 ) {
+    // The state change only lands after this system, don't count a duck
+    // twice when several shots come in on the same step
+    let mut downed: HashSet<Entity> = HashSet::default();
+    // The index has where the ducks were last drawn, a search has to reach
+    // as far back as any of them came on the step before
+    let reach = duck_query
+        .iter()
+        .map(|(transform, previous, ..)| transform.translation.xy().distance(previous.0))
+        .fold(0.0, f32::max);
+    // And as far out as the biggest hitbox goes
+    let largest = duck_query
+        .iter()
+        .map(|(transform, _, hitbox, ..)| (hitbox.size * transform.scale.xy()).max_element())
+        .fold(0.0, f32::max);
     for event in game_input_event_reader.read() {
-        if let GameInputEvent::Shoot(shot_pos) = event {
-            // Go through each duck and find one hit
-            // Hitbox is the 32x32 tile of the sprite
-            for (entity, transform, mut duck) in &mut duck_query {
-                let pos = transform.translation.xy();
-                let hitbox = Rect::new(pos.x - 16.0, pos.y - 16.0, pos.x + 16.0, pos.y + 16.0);
-                println!("{:?} {:?} {:?}", pos, hitbox, shot_pos);
-                if hitbox.contains(shot_pos.clone()) {
-                    duck.behaviour = DuckBehaviour::Dying;
-                    println!("Hit duck")
+        match event {
+            GameInputEvent::Shoot(player, shot_pos, pulled) => {
+                // Players with a weapon of their own shoot that, everyone
+                // else shares the equipped one
+                let mut player_weapon = player_weapon_query
+                    .iter_mut()
+                    .find(|(reticle, _)| reticle.player == *player)
+                    .map(|(_, weapon)| weapon);
+                let weapon = match player_weapon.as_deref_mut() {
+                    Some(weapon) => weapon,
+                    None => &mut *equipped,
+                };
+                if !weapon.try_fire(&modifiers) {
+                    continue;
+                }
+                shot_fired_writer.write(ShotFired {
+                    player: *player,
+                    position: *shot_pos,
+                });
+                let _span = info_span!("hit_test", player = player.0).entered();
+                // How far between the last two drawn positions the ducks had
+                // got when the trigger was pulled. Rollback peers and NES
+                // timing step on their own clocks, those games and shots
+                // without a time take them as they were last drawn
+                let stepped = *driver == SimulationDriver::Rollback || nes_timing.is_some();
+                let progress = match (stepped, *pulled, real.last_update()) {
+                    (true, ..) | (_, None, _) | (_, _, None) => 1.0,
+                    (_, Some(pulled), Some(now)) => {
+                        let ago = now.saturating_duration_since(pulled).as_secs_f32();
+                        1.0 - ago / real.delta_secs().max(f32::EPSILON)
+                    }
+                };
+                let progress = progress.clamp(0.0, 1.0);
+                let at_shot = |transform: &Transform, previous: &PreviousPosition| {
+                    previous.0.lerp(transform.translation.xy(), progress)
+                };
+                // Rollback peers each have their own settings but have to
+                // agree on every hit, the assist stays out of those games
+                let aim = if *driver == SimulationDriver::Rollback {
+                    *shot_pos
+                } else {
+                    let radius = settings.accessibility.assist_radius;
+                    let near = Rect::from_center_half_size(*shot_pos, Vec2::splat(radius + reach));
+                    let ducks = spatial_index
+                        .query(near)
+                        .filter(|entity| !downed.contains(entity))
+                        .filter_map(|entity| duck_query.get(entity).ok())
+                        .map(|(transform, previous, ..)| at_shot(transform, previous));
+                    assisted_aim(&settings.accessibility, *shot_pos, ducks)
+                };
+                // Every pellet is its own hit test, a duck goes down once the
+                // pellets that hit it add up to a full shot
+                let mut damage: HashMap<Entity, f32> = HashMap::default();
+                let pierce = weapon.weapon.pierce;
+                for pellet in modifiers.pellets(&weapon.weapon) {
+                    let pellet_pos = aim + pellet.offset;
+                    // Go through the ducks near the pellet and find the ones hit
+                    let mut hits = Vec::new();
+                    let search_area =
+                        Rect::from_center_half_size(pellet_pos, Vec2::splat(largest / 2.0 + reach));
+                    for entity in spatial_index.query(search_area) {
+                        if downed.contains(&entity) {
+                            continue;
+                        }
+                        let Ok((transform, previous, hitbox, sprite, _, _)) =
+                            duck_query.get(entity)
+                        else {
+                            continue;
+                        };
+                        let pos = at_shot(transform, previous);
+                        let scale = transform.scale.xy();
+                        if !hitbox.rect(pos, transform).contains(pellet_pos) {
+                            continue;
+                        }
+                        // Narrow it down to the pixels of the current frame, if the
+                        // mask isn't built yet fall back to the plain hitbox
+                        if let (HitDetection::AlphaMask, Some(alpha_mask)) =
+                            (settings.accessibility.hit_detection, alpha_mask.as_deref())
+                        {
+                            if let Some(atlas) = &sprite.texture_atlas {
+                                if !alpha_mask.is_opaque(
+                                    atlas.index,
+                                    (pellet_pos - pos) / scale,
+                                    sprite.flip_x,
+                                ) {
+                                    continue;
+                                }
+                            }
+                        }
+                        hits.push((entity, pos.extend(transform.translation.z)));
+                    }
+                    if pierce {
+                        for (entity, _) in hits {
+                            *damage.entry(entity).or_default() += pellet.power;
+                        }
+                    } else if let Some(entity) = pellet_target(&hits, pellet_pos) {
+                        *damage.entry(entity).or_default() += pellet.power;
+                    }
+                }
+                for (entity, damage) in damage {
+                    let Ok((transform, _, _, _, duck, health)) = duck_query.get_mut(entity) else {
+                        continue;
+                    };
+                    let position = transform.translation.xy();
+                    if let Some(mut health) = health {
+                        health.current -= damage;
+                        if health.current > 0.0 {
+                            commands
+                                .entity(entity)
+                                .insert(HitFlash(Timer::from_seconds(0.3, TimerMode::Once)));
+                            duck_damaged_writer.write(DuckDamaged {
+                                player: *player,
+                                entity,
+                                position,
+                                health: health.current,
+                            });
+                            continue;
+                        }
+                    } else if damage < 1.0 {
+                        continue;
+                    }
+                    downed.insert(entity);
+                    commands.entity(entity).set_duck_state(Dying::default());
+                    duck_hit_writer.write(DuckHit {
+                        player: *player,
+                        entity,
+                        position,
+                        points: duck.points,
+                    });
                 }
             }
+            // Handled by their own plugins
+            GameInputEvent::Call(..) | GameInputEvent::Decoy(..) | GameInputEvent::Reload(_) => {}
         }
     }
 }
 
+#[cfg(not(feature = "physics"))]
+#[allow(clippy::type_complexity)]
 fn handle_dying(
     time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    wind: Res<Wind>,
     mut commands: Commands,
-    mut duck_query: Query<(Entity, &mut Transform, &Duck), Without<Dead>>,
+    mut duck_query: Query<(Entity, &mut Transform), (With<Falling>, Without<Dead>)>,
+    mut duck_landed_writer: EventWriter<DuckLanded>,
 ) {
-    for (entity, mut transform, duck) in &mut duck_query {
-        if duck.behaviour == DuckBehaviour::Dying {
-            transform.translation.y -= 80.0 * time.delta_secs();
-            if transform.translation.y < -240.0 {
-                commands.entity(entity).insert(Dead);
-            }
+    let delta = time_scale.delta_secs(&time);
+    for (entity, mut transform) in &mut duck_query {
+        transform.translation.x += wind.0 * delta;
+        transform.translation.y -= 80.0 * delta;
+        if transform.translation.y < -240.0 {
+            commands.entity(entity).insert(Dead);
+            duck_landed_writer.write(DuckLanded {
+                entity,
+                position: transform.translation.xy(),
+            });
         }
     }
 }
 
-#[derive(Component)]
-struct Dead;
+#[derive(Component, Clone)]
+pub struct Dead;
+
+/// Duck that isn't part of the round. Hitting it or letting it get away
+/// doesn't bring the round closer to its end.
+#[derive(Component, Clone)]
+pub struct ExtraDuck;
 
 fn handle_dead(mut commands: Commands, dead_query: Query<Entity, With<Dead>>) {
-    for (entity) in dead_query.iter() {
+    for entity in dead_query.iter() {
         commands.entity(entity).despawn();
     }
 }
+
+#[allow(clippy::type_complexity)]
+fn handle_escaping(
+    mut commands: Commands,
+    duck_query: Query<(Entity, &Transform), (Or<(With<Flying>, With<Escaping>)>, Without<Dead>)>,
+    mut duck_escaped_writer: EventWriter<DuckEscaped>,
+) {
+    for (entity, transform) in &duck_query {
+        // Fully out past the top of the screen
+        if transform.translation.y > 240.0 / 2.0 + 16.0 {
+            commands.entity(entity).insert(Dead);
+            duck_escaped_writer.write(DuckEscaped {
+                entity,
+                position: transform.translation.xy(),
+            });
+        }
+    }
+}
+
+fn track_round(
+    mut round: ResMut<Round>,
+    extra_query: Query<(), With<ExtraDuck>>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+    mut duck_escaped_reader: EventReader<DuckEscaped>,
+    mut round_ended_writer: EventWriter<RoundEnded>,
+) {
+    round.ducks_hit += duck_hit_reader
+        .read()
+        .filter(|hit| !extra_query.contains(hit.entity))
+        .count() as u32;
+    round.ducks_escaped += duck_escaped_reader
+        .read()
+        .filter(|escaped| !extra_query.contains(escaped.entity))
+        .count() as u32;
+    if round.ducks_hit + round.ducks_escaped >= round.ducks_total {
+        round_ended_writer.write(RoundEnded {
+            round: round.number,
+            ducks_hit: round.ducks_hit,
+            ducks_escaped: round.ducks_escaped,
+        });
+        *round = Round::new(round.number + 1);
+    }
+}
+
+/// The one duck a pellet that doesn't pierce hits out of those under it,
+/// the one drawn on top and of those the one whose middle it's closest to
+fn pellet_target(hits: &[(Entity, Vec3)], pellet: Vec2) -> Option<Entity> {
+    hits.iter()
+        .min_by(|(_, a), (_, b)| {
+            b.z.total_cmp(&a.z).then_with(|| {
+                let to_a = a.xy().distance_squared(pellet);
+                to_a.total_cmp(&b.xy().distance_squared(pellet))
+            })
+        })
+        .map(|(entity, _)| *entity)
+}
+
+fn animate_hit_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut flash_query: Query<(Entity, &mut Sprite, &mut HitFlash)>,
+) {
+    let delta = time_scale.delta(&time);
+    for (entity, mut sprite, mut flash) in &mut flash_query {
+        flash.tick(delta);
+        let visible = flash.finished() || !((flash.elapsed_secs() * 20.0) as u32).is_multiple_of(2);
+        sprite.color.set_alpha(if visible { 1.0 } else { 0.2 });
+        if flash.finished() {
+            commands.entity(entity).remove::<HitFlash>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_ducks_only_lose_one() {
+        let (far, near) = (Entity::from_raw(1), Entity::from_raw(2));
+        let hits = [
+            (far, Vec3::new(8.0, 0.0, 1.0)),
+            (near, Vec3::new(2.0, 0.0, 1.0)),
+        ];
+        assert_eq!(pellet_target(&hits, Vec2::ZERO), Some(near));
+    }
+
+    #[test]
+    fn the_duck_on_top_takes_the_pellet() {
+        let (below, above) = (Entity::from_raw(1), Entity::from_raw(2));
+        let hits = [
+            (below, Vec3::new(0.0, 0.0, 1.0)),
+            (above, Vec3::new(8.0, 0.0, 3.0)),
+        ];
+        assert_eq!(pellet_target(&hits, Vec2::ZERO), Some(above));
+        assert_eq!(pellet_target(&[], Vec2::ZERO), None);
+    }
+
+    #[test]
+    fn the_built_in_weapon_table_has_every_weapon() {
+        use crate::weapon::{Weapon, WeaponKind};
+
+        for kind in [WeaponKind::Rifle, WeaponKind::Shotgun, WeaponKind::Auto] {
+            assert_eq!(Weapon::new(kind).kind, kind);
+        }
+    }
+
+    #[test]
+    fn each_mode_hands_out_its_own_weapons() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        use crate::weapon::WeaponKind;
+
+        let mut world = World::new();
+        world.init_resource::<Assets<WeaponTable>>();
+        world.insert_resource(EquippedWeapon::new(WeaponKind::Shotgun));
+        world.insert_resource(GameMode::Classic);
+        world.run_system_once(equip_mode_loadout).unwrap();
+        assert_eq!(world.resource::<WeaponLoadout>().0, [WeaponKind::Rifle]);
+        assert_eq!(
+            world.resource::<EquippedWeapon>().weapon.kind,
+            WeaponKind::Rifle
+        );
+
+        world.insert_resource(GameMode::Endless);
+        world.run_system_once(equip_mode_loadout).unwrap();
+        assert_eq!(
+            world.resource::<WeaponLoadout>().0,
+            [WeaponKind::Rifle, WeaponKind::Shotgun, WeaponKind::Auto]
+        );
+    }
+
+    #[test]
+    fn a_restored_snapshot_takes_the_same_again() {
+        use std::time::Duration;
+
+        use crate::{
+            power_up::PowerUpKind, rng::SpawnRng, snapshot::GameSnapshot, weapon::WeaponKind,
+        };
+
+        let mut world = World::new();
+        let mut spawn_timer = SpawnTimer(Timer::from_seconds(1.0, TimerMode::Repeating));
+        spawn_timer.0.tick(Duration::from_millis(400));
+        let mut effects = ActiveEffects::default();
+        effects.grant(PowerUpKind::SlowMo);
+        world.insert_resource(Round::new(3));
+        world.insert_resource(State::new(RoundPhase::Playing));
+        world.insert_resource(spawn_timer);
+        world.insert_resource(Score::default());
+        world.insert_resource(EquippedWeapon::default());
+        world.insert_resource(effects);
+        world.insert_resource(GameRng::from_seed(7));
+        world.insert_resource(SpawnRng::from_seed(7));
+        world.insert_resource(GameTimeScale::default());
+        world.spawn((
+            Duck::default(),
+            Flying::default(),
+            Lured::new(Vec2::ONE, 1.0),
+        ));
+        world.spawn((
+            Reticle {
+                player: PlayerId(1),
+            },
+            EquippedWeapon::new(WeaponKind::Shotgun),
+        ));
+
+        let taken = GameSnapshot::take(&mut world).unwrap();
+        taken.restore(&mut world);
+        let again = GameSnapshot::take(&mut world).unwrap();
+        assert_eq!(
+            serde_json::to_string(&taken).unwrap(),
+            serde_json::to_string(&again).unwrap()
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Stats over every game played, kept in the profile, and the page off the
+//! title menu that shows them. A game's `SessionStats` are added in when
+//! it's left, from the pause menu or the game over screen, so a game closed
+//! halfway through doesn't count. Demo games never do.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    demo::Demo,
+    events::ResetGame,
+    locale::Locale,
+    menu::{spawn_menu_button, spawn_menu_page, MenuScreen},
+    mode::GameMode,
+    score::SessionStats,
+    GameState, Round,
+};
+
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub shots_fired: u64,
+    pub ducks_hit: u64,
+    pub ducks_escaped: u64,
+    pub best_round: u32,
+    pub games_played: HashMap<GameMode, u32>,
+}
+
+impl LifetimeStats {
+    pub fn add_game(&mut self, mode: GameMode, stats: &SessionStats, round: u32) {
+        self.shots_fired += u64::from(stats.shots_fired);
+        self.ducks_hit += u64::from(stats.ducks_hit);
+        self.ducks_escaped += u64::from(stats.ducks_escaped);
+        self.best_round = self.best_round.max(round);
+        *self.games_played.entry(mode).or_default() += 1;
+    }
+
+    /// The mode played the most, ties go to the one listed first in `GameMode`
+    pub fn favorite_mode(&self) -> Option<GameMode> {
+        self.games_played
+            .iter()
+            .max_by_key(|(mode, games)| (**games, std::cmp::Reverse(**mode as u8)))
+            .map(|(mode, _)| *mode)
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.ducks_hit as f32 / self.shots_fired as f32
+        }
+    }
+}
+
+#[derive(Component)]
+struct StatsButton;
+
+#[derive(Component)]
+struct StatsPage;
+
+#[derive(Component)]
+struct StatsBackButton;
+
+pub struct LifetimeStatsPlugin;
+
+impl Plugin for LifetimeStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(record_game)
+            .add_systems(
+                OnEnter(GameState::Menu),
+                add_stats_button.after(crate::menu::setup_menu),
+            )
+            .add_systems(
+                Update,
+                (open_stats_page, close_stats_page).run_if(in_state(GameState::Menu)),
+            );
+    }
+}
+
+// Runs before `reset_session` has put the session stats back, it only
+// queues that up
+fn record_game(
+    _trigger: Trigger<ResetGame>,
+    mode: Res<GameMode>,
+    stats: Res<SessionStats>,
+    round: Option<Res<Round>>,
+    demo: Option<Res<Demo>>,
+    mut lifetime: ResMut<LifetimeStats>,
+) {
+    if demo.is_some() {
+        return;
+    }
+    let round = round.map_or(0, |round| round.number);
+    lifetime.add_game(*mode, &stats, round);
+}
+
+fn add_stats_button(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    menu_query: Query<Entity, With<MenuScreen>>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).with_children(|parent| {
+            spawn_menu_button(parent, locale.get("stats.title"), StatsButton);
+        });
+    }
+}
+
+fn open_stats_page(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    lifetime: Res<LifetimeStats>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<StatsButton>)>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    let games: u32 = lifetime.games_played.values().sum();
+    let favorite = locale.get(
+        lifetime
+            .favorite_mode()
+            .map_or("stats.none", GameMode::locale_key),
+    );
+    let lines = [
+        locale.format("stats.games_played", &[("games", &games)]),
+        locale.format("stats.ducks_hit", &[("ducks", &lifetime.ducks_hit)]),
+        locale.format("stats.ducks_escaped", &[("ducks", &lifetime.ducks_escaped)]),
+        locale.format("stats.shots_fired", &[("shots", &lifetime.shots_fired)]),
+        locale.format(
+            "stats.accuracy",
+            &[("accuracy", &format!("{:.0}", lifetime.accuracy() * 100.0))],
+        ),
+        locale.format("stats.best_round", &[("round", &lifetime.best_round)]),
+        locale.format("stats.favorite_mode", &[("mode", &favorite)]),
+    ];
+    spawn_menu_page(&mut commands, 4.0, StatsPage).with_children(|parent| {
+        parent.spawn((
+            Text::new(locale.get("stats.title")),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+            Node {
+                margin: UiRect::bottom(Val::Px(12.0)),
+                ..default()
+            },
+        ));
+        for line in lines {
+            parent.spawn((
+                Text::new(line),
+                TextFont {
+                    font_size: 10.0,
+                    ..default()
+                },
+            ));
+        }
+        parent
+            .spawn(Node {
+                margin: UiRect::top(Val::Px(12.0)),
+                ..default()
+            })
+            .with_children(|parent| {
+                spawn_menu_button(parent, locale.get("menu.back"), StatsBackButton)
+            });
+    });
+}
+
+fn close_stats_page(
+    mut commands: Commands,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<StatsBackButton>)>,
+    page_query: Query<Entity, With<StatsPage>>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    for entity in &page_query {
+        commands.entity(entity).despawn();
+    }
+}
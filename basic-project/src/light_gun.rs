@@ -0,0 +1,544 @@
+//! USB light guns for cabinets. Guns like the Sinden or GUN4IR show up as
+//! absolute pointing devices, they're read straight from evdev so that two
+//! of them can be told apart, which the window's single cursor can't do.
+//! Linux only, and the user running the game needs read access to
+//! `/dev/input`, usually by being in the `input` group.
+//!
+//! Guns are numbered in the order of the USB ports they're plugged into,
+//! the first one plays as player one and the second as player two, any more
+//! than that are left out. Each is
+//! grabbed so it stops moving the desktop cursor, otherwise every shot would
+//! also land as a mouse click. The trigger shoots and the side button
+//! reloads.
+//!
+//! No two guns line up the same with a screen. A CALIBRATE GUNS page off
+//! the title menu has each one shoot two marks, the result is kept per USB
+//! port in `light_guns.ron` in the config directory.
+
+use std::{fs, io, path::Path, thread};
+
+use bevy::{
+    input::InputSystem,
+    platform::{collections::HashMap, time::Instant},
+    prelude::*,
+};
+use crossbeam_channel::Receiver;
+use evdev::{AbsoluteAxisType, Device, InputEventKind, Key};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::PlayerId,
+    locale::Locale,
+    menu::{spawn_menu_button, spawn_menu_page, MenuScreen},
+    sinden::PlayArea,
+    storage::{write_file, Storage},
+    versus::{Reticle, PLAYER_COLORS},
+    FrameSet, GameInputEvent, GameState, PauseState, RoundPhase,
+};
+
+const CALIBRATION_FILE: &str = "light_guns.ron";
+// Where the calibration marks sit, in window pixels from the top left
+const MARKS: [Vec2; 2] = [Vec2::new(16.0, 16.0), Vec2::new(240.0, 224.0)];
+const MARK_SIZE: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GunAction {
+    /// Where it points, from 0 to 1 across the gun's own range
+    Aim(Vec2),
+    Trigger,
+    Reload,
+}
+
+/// Something one of the guns did, `gun` is its number
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct LightGunInput {
+    pub gun: usize,
+    pub action: GunAction,
+    /// When the gun's thread read it, unlike the mouse that's mid frame
+    pub at: Instant,
+}
+
+/// Maps a gun's own range onto the window, see `GunCalibration::window`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GunCalibration {
+    pub scale: [f32; 2],
+    pub offset: [f32; 2],
+}
+
+impl Default for GunCalibration {
+    fn default() -> Self {
+        Self {
+            scale: [1.0, 1.0],
+            offset: [0.0, 0.0],
+        }
+    }
+}
+
+impl GunCalibration {
+    /// From where the gun read when shooting at each of `MARKS`, none when
+    /// the two are too close together to tell anything from
+    fn from_marks(first: Vec2, second: Vec2) -> Option<Self> {
+        let size = Vec2::new(256.0, 240.0);
+        let (target_first, target_second) = (MARKS[0] / size, MARKS[1] / size);
+        let spread = second - first;
+        if spread.x.abs() < f32::EPSILON || spread.y.abs() < f32::EPSILON {
+            return None;
+        }
+        let scale = (target_second - target_first) / spread;
+        let offset = target_first - scale * first;
+        Some(Self {
+            scale: scale.to_array(),
+            offset: offset.to_array(),
+        })
+    }
+
+    /// Window pixels from the top left for a raw reading
+    fn window(&self, raw: Vec2) -> Vec2 {
+        (Vec2::from(self.offset) + Vec2::from(self.scale) * raw) * Vec2::new(256.0, 240.0)
+    }
+
+    /// World position for a raw reading, the same the mouse would give
+    fn world(&self, raw: Vec2, play_area: &PlayArea) -> Vec2 {
+        play_area.to_world(self.window(raw))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LightGun {
+    /// USB port it's on, what its calibration is kept under
+    port: String,
+    aim: Option<Vec2>,
+}
+
+#[derive(Resource)]
+pub struct LightGuns {
+    input: Receiver<LightGunInput>,
+    guns: Vec<LightGun>,
+    calibrations: HashMap<String, GunCalibration>,
+}
+
+impl LightGuns {
+    fn calibration(&self, gun: usize) -> GunCalibration {
+        self.guns
+            .get(gun)
+            .and_then(|gun| self.calibrations.get(&gun.port))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// The calibration page is up, `first` is what the gun read on the first
+/// mark once it's been shot
+#[derive(Resource, Debug, Clone, Copy)]
+struct Calibrating {
+    gun: usize,
+    first: Option<Vec2>,
+}
+
+#[derive(Component)]
+struct CalibrateButton;
+
+#[derive(Component)]
+struct CalibrationPage;
+
+#[derive(Component)]
+struct CalibrationPrompt;
+
+#[derive(Component)]
+struct CalibrationMark;
+
+pub struct LightGunPlugin;
+
+impl Plugin for LightGunPlugin {
+    fn build(&self, app: &mut App) {
+        let guns = match open_guns() {
+            Ok(guns) => guns,
+            Err(error) => {
+                println!("Could not look for light guns: {error}");
+                return;
+            }
+        };
+        if guns.guns.is_empty() {
+            return;
+        }
+        println!("Found {} light gun(s)", guns.guns.len());
+        let path = app
+            .world()
+            .resource::<Storage>()
+            .config_file(CALIBRATION_FILE);
+        app.insert_resource(LightGuns {
+            calibrations: load_calibrations(&path),
+            ..guns
+        })
+        .add_event::<LightGunInput>()
+        .add_systems(PreUpdate, read_light_guns.after(InputSystem))
+        .add_systems(
+            Update,
+            (aim_light_guns, shoot_light_guns)
+                .in_set(FrameSet::Input)
+                .run_if(
+                    in_state(PauseState::Running)
+                        .and(in_state(RoundPhase::Playing))
+                        .and(not(resource_exists::<Calibrating>)),
+                ),
+        )
+        .add_systems(
+            OnEnter(GameState::Menu),
+            add_calibrate_button.after(crate::menu::setup_menu),
+        )
+        .add_systems(
+            Update,
+            (open_calibration_page, calibrate_guns)
+                .chain()
+                .run_if(in_state(GameState::Menu)),
+        )
+        .add_systems(OnExit(GameState::Menu), close_calibration_page);
+    }
+}
+
+fn is_light_gun(device: &Device) -> bool {
+    let absolute = device.supported_absolute_axes().is_some_and(|axes| {
+        axes.contains(AbsoluteAxisType::ABS_X) && axes.contains(AbsoluteAxisType::ABS_Y)
+    });
+    let trigger = device
+        .supported_keys()
+        .is_some_and(|keys| keys.contains(Key::BTN_LEFT) || keys.contains(Key::BTN_TRIGGER));
+    absolute && trigger
+}
+
+/// Finds the guns and starts a thread reading each
+fn open_guns() -> io::Result<LightGuns> {
+    let mut devices: Vec<(String, Device)> = evdev::enumerate()
+        .filter(|(_, device)| is_light_gun(device))
+        .map(|(path, device)| {
+            let port = device
+                .physical_path()
+                .map(str::to_string)
+                .unwrap_or_else(|| path.display().to_string());
+            (port, device)
+        })
+        .collect();
+    devices.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let (sender, input) = crossbeam_channel::unbounded();
+    let mut guns = Vec::new();
+    for (gun, (port, mut device)) in devices.into_iter().enumerate() {
+        let abs = device.get_abs_state()?;
+        let x = abs[AbsoluteAxisType::ABS_X.0 as usize];
+        let y = abs[AbsoluteAxisType::ABS_Y.0 as usize];
+        let min = Vec2::new(x.minimum as f32, y.minimum as f32);
+        let range = (Vec2::new(x.maximum as f32, y.maximum as f32) - min).max(Vec2::ONE);
+        if let Err(error) = device.grab() {
+            println!("Could not grab light gun on {port}: {error}");
+        }
+        let sender = sender.clone();
+        thread::spawn(move || {
+            let mut aim = Vec2::ZERO;
+            loop {
+                let Ok(events) = device.fetch_events() else {
+                    break;
+                };
+                let at = Instant::now();
+                let mut actions = Vec::new();
+                let mut moved = false;
+                for event in events {
+                    match (event.kind(), event.value()) {
+                        (InputEventKind::AbsAxis(AbsoluteAxisType::ABS_X), value) => {
+                            aim.x = (value as f32 - min.x) / range.x;
+                            moved = true;
+                        }
+                        (InputEventKind::AbsAxis(AbsoluteAxisType::ABS_Y), value) => {
+                            aim.y = (value as f32 - min.y) / range.y;
+                            moved = true;
+                        }
+                        (InputEventKind::Key(Key::BTN_LEFT | Key::BTN_TRIGGER), 1) => {
+                            actions.push(GunAction::Trigger);
+                        }
+                        (InputEventKind::Key(Key::BTN_RIGHT | Key::BTN_THUMB), 1) => {
+                            actions.push(GunAction::Reload);
+                        }
+                        _ => {}
+                    }
+                }
+                // Aim goes first so a shot lands where the gun now points
+                if moved {
+                    actions.insert(0, GunAction::Aim(aim));
+                }
+                for action in actions {
+                    if sender.send(LightGunInput { gun, action, at }).is_err() {
+                        return;
+                    }
+                }
+            }
+            println!("Light gun {} disconnected", gun + 1);
+        });
+        guns.push(LightGun { port, aim: None });
+    }
+    Ok(LightGuns {
+        input,
+        guns,
+        calibrations: HashMap::default(),
+    })
+}
+
+fn load_calibrations(path: &Path) -> HashMap<String, GunCalibration> {
+    match fs::read_to_string(path) {
+        Ok(contents) => ron::from_str(&contents).unwrap_or_else(|error| {
+            println!("Could not read light gun calibration: {error}");
+            HashMap::default()
+        }),
+        Err(_) => HashMap::default(),
+    }
+}
+
+fn save_calibrations(path: &Path, calibrations: &HashMap<String, GunCalibration>) {
+    let result = ron::ser::to_string_pretty(calibrations, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+        .and_then(|contents| write_file(path, contents).map_err(|error| error.to_string()));
+    if let Err(error) = result {
+        println!("Could not save light gun calibration: {error}");
+    }
+}
+
+/// Who `gun` plays as, there are only two seats
+fn gun_player(gun: usize) -> Option<PlayerId> {
+    (gun < PLAYER_COLORS.len()).then_some(PlayerId(gun))
+}
+
+fn read_light_guns(mut guns: ResMut<LightGuns>, mut input_writer: EventWriter<LightGunInput>) {
+    while let Ok(input) = guns.input.try_recv() {
+        if let (GunAction::Aim(aim), Some(gun)) = (input.action, guns.guns.get_mut(input.gun)) {
+            gun.aim = Some(aim);
+        }
+        input_writer.write(input);
+    }
+}
+
+fn aim_light_guns(
+    guns: Res<LightGuns>,
+    play_area: Res<PlayArea>,
+    mut input_reader: EventReader<LightGunInput>,
+    mut reticle_query: Query<(&Reticle, &mut Transform)>,
+) {
+    for input in input_reader.read() {
+        let (GunAction::Aim(raw), Some(player)) = (input.action, gun_player(input.gun)) else {
+            continue;
+        };
+        let position = guns.calibration(input.gun).world(raw, &play_area);
+        for (reticle, mut transform) in &mut reticle_query {
+            if reticle.player == player {
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+            }
+        }
+    }
+}
+
+// One shot per pull, holding the trigger doesn't keep an automatic going
+fn shoot_light_guns(
+    guns: Res<LightGuns>,
+    play_area: Res<PlayArea>,
+    mut input_reader: EventReader<LightGunInput>,
+    mut game_input_event_writer: EventWriter<GameInputEvent>,
+) {
+    for input in input_reader.read() {
+        let Some(player) = gun_player(input.gun) else {
+            continue;
+        };
+        match input.action {
+            GunAction::Trigger => {
+                let Some(raw) = guns.guns.get(input.gun).and_then(|gun| gun.aim) else {
+                    continue;
+                };
+                let calibration = guns.calibration(input.gun);
+                let window = calibration.window(raw);
+                // Pointing away from the screen reloads, like the Zapper
+                if window.x < 0.0 || window.y < 0.0 || window.x > 256.0 || window.y > 240.0 {
+                    game_input_event_writer.write(GameInputEvent::Reload(player));
+                } else {
+                    game_input_event_writer.write(GameInputEvent::Shoot(
+                        player,
+                        calibration.world(raw, &play_area),
+                        Some(input.at),
+                    ));
+                }
+            }
+            GunAction::Reload => {
+                game_input_event_writer.write(GameInputEvent::Reload(player));
+            }
+            GunAction::Aim(_) => {}
+        }
+    }
+}
+
+fn add_calibrate_button(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    menu_query: Query<Entity, With<MenuScreen>>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).with_children(|parent| {
+            spawn_menu_button(parent, locale.get("light_gun.calibrate"), CalibrateButton);
+        });
+    }
+}
+
+fn calibration_prompt(locale: &Locale, calibrating: &Calibrating) -> String {
+    let key = match calibrating.first {
+        None => "light_gun.shoot_top_left",
+        Some(_) => "light_gun.shoot_bottom_right",
+    };
+    locale.format(key, &[("gun", &(calibrating.gun + 1))])
+}
+
+fn open_calibration_page(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<CalibrateButton>)>,
+) {
+    if !button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    let calibrating = Calibrating {
+        gun: 0,
+        first: None,
+    };
+    commands.insert_resource(calibrating);
+    spawn_menu_page(&mut commands, 0.0, CalibrationPage)
+        // Dark, so the white marks are all the guns see
+        .insert(BackgroundColor(Color::BLACK))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(calibration_prompt(&locale, &calibrating)),
+                TextFont {
+                    font_size: 10.0,
+                    ..default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+                CalibrationPrompt,
+            ));
+            parent.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(MARKS[0].x - MARK_SIZE / 2.0),
+                    top: Val::Px(MARKS[0].y - MARK_SIZE / 2.0),
+                    width: Val::Px(MARK_SIZE),
+                    height: Val::Px(MARK_SIZE),
+                    ..default()
+                },
+                BackgroundColor(Color::WHITE),
+                CalibrationMark,
+            ));
+        });
+}
+
+// Escape leaves the page without keeping anything from the gun in progress
+#[allow(clippy::too_many_arguments)]
+fn calibrate_guns(
+    mut commands: Commands,
+    storage: Res<Storage>,
+    locale: Res<Locale>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    calibrating: Option<ResMut<Calibrating>>,
+    mut guns: ResMut<LightGuns>,
+    mut input_reader: EventReader<LightGunInput>,
+    mut prompt_query: Query<&mut Text, With<CalibrationPrompt>>,
+    mut mark_query: Query<&mut Node, With<CalibrationMark>>,
+    page_query: Query<Entity, With<CalibrationPage>>,
+) {
+    let Some(mut calibrating) = calibrating else {
+        input_reader.clear();
+        return;
+    };
+    let mut done = keyboard_input.just_pressed(KeyCode::Escape);
+    for input in input_reader.read() {
+        if done || input.gun != calibrating.gun || input.action != GunAction::Trigger {
+            continue;
+        }
+        let Some(raw) = guns.guns.get(input.gun).and_then(|gun| gun.aim) else {
+            continue;
+        };
+        let Some(first) = calibrating.first else {
+            calibrating.first = Some(raw);
+            continue;
+        };
+        // Shooting the same spot twice starts this gun over
+        calibrating.first = None;
+        let Some(calibration) = GunCalibration::from_marks(first, raw) else {
+            continue;
+        };
+        let port = guns.guns[input.gun].port.clone();
+        guns.calibrations.insert(port, calibration);
+        save_calibrations(&storage.config_file(CALIBRATION_FILE), &guns.calibrations);
+        calibrating.gun += 1;
+        done = calibrating.gun >= guns.guns.len();
+    }
+    if done {
+        commands.remove_resource::<Calibrating>();
+        for entity in &page_query {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    if !calibrating.is_changed() {
+        return;
+    }
+    for mut text in &mut prompt_query {
+        text.0 = calibration_prompt(&locale, &calibrating);
+    }
+    let mark = MARKS[usize::from(calibrating.first.is_some())];
+    for mut node in &mut mark_query {
+        node.left = Val::Px(mark.x - MARK_SIZE / 2.0);
+        node.top = Val::Px(mark.y - MARK_SIZE / 2.0);
+    }
+}
+
+fn close_calibration_page(mut commands: Commands) {
+    commands.remove_resource::<Calibrating>();
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn a_third_gun_does_not_shoot() {
+        let mut world = World::new();
+        world.init_resource::<Events<LightGunInput>>();
+        world.init_resource::<Events<GameInputEvent>>();
+        world.init_resource::<PlayArea>();
+        world.insert_resource(LightGuns {
+            input: crossbeam_channel::never(),
+            guns: (0..3)
+                .map(|port| LightGun {
+                    port: port.to_string(),
+                    aim: Some(Vec2::splat(0.5)),
+                })
+                .collect(),
+            calibrations: HashMap::default(),
+        });
+        for gun in [1, 2] {
+            world.send_event(LightGunInput {
+                gun,
+                action: GunAction::Trigger,
+                at: Instant::now(),
+            });
+        }
+        world.run_system_once(shoot_light_guns).unwrap();
+
+        let events = world.resource::<Events<GameInputEvent>>();
+        let players: Vec<_> = events
+            .iter_current_update_events()
+            .map(|event| match event {
+                GameInputEvent::Shoot(player, ..) => *player,
+                _ => panic!("expected only shots"),
+            })
+            .collect();
+        assert_eq!(players, [PlayerId(1)]);
+    }
+}
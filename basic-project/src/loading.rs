@@ -0,0 +1,152 @@
+//! Loading screen, the game stays on it until every file in `GameAssets` is
+//! in memory so nothing gets drawn untextured on the first frames. The
+//! collection is loaded by bevy_asset_loader, which also reports the
+//! progress shown on the bar. If anything fails to load it goes to
+//! `GameState::Error` instead, which lists what's missing.
+
+use bevy::{
+    asset::{io::file::FileAssetReader, UntypedAssetLoadFailedEvent},
+    prelude::*,
+};
+use iyes_progress::ProgressTracker;
+
+use crate::{locale::Locale, menu::start_game, GameAssets, GameState, LaunchOptions};
+
+const BAR_WIDTH: f32 = 128.0;
+
+#[derive(Component)]
+pub struct LoadingBar;
+
+/// Paths of the files that couldn't be loaded, listed on the error screen
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FailedAssets(pub Vec<String>);
+
+pub fn setup_loading_screen(mut commands: Commands, locale: Res<Locale>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+            StateScoped(GameState::Loading),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(locale.get("loading.title")),
+                TextFont {
+                    font_size: 10.0,
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(6.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::WHITE),
+                    LoadingBar,
+                ));
+        });
+}
+
+pub fn track_loading(
+    mut commands: Commands,
+    progress: Res<ProgressTracker<GameState>>,
+    game_assets: Option<Res<GameAssets>>,
+    launch: Res<LaunchOptions>,
+    mut bar_query: Query<&mut Node, With<LoadingBar>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let progress = progress.get_global_progress();
+    if progress.total > 0 {
+        for mut node in &mut bar_query {
+            node.width = Val::Percent(100.0 * f32::from(progress));
+        }
+    }
+    // The collection is only inserted once every file is in
+    if game_assets.is_none() {
+        return;
+    }
+    println!("Finished loading");
+    match launch.mode {
+        Some(mode) => {
+            start_game(&mut commands, mode, &launch);
+            next_state.set(GameState::InGame);
+        }
+        None => next_state.set(GameState::Menu),
+    }
+}
+
+pub fn record_failed_assets(
+    mut failed_reader: EventReader<UntypedAssetLoadFailedEvent>,
+    mut failed: ResMut<FailedAssets>,
+) {
+    for event in failed_reader.read() {
+        // Where it was looked for, relative paths are no help when the game
+        // was started from somewhere else
+        let file = FileAssetReader::get_base_path()
+            .join(AssetPlugin::default().file_path)
+            .join(event.path.path());
+        println!(
+            "Could not load {}: {}\nExpected it at {}",
+            event.path,
+            event.error,
+            file.display()
+        );
+        failed.0.push(event.path.to_string());
+    }
+}
+
+pub fn setup_error_screen(mut commands: Commands, locale: Res<Locale>, failed: Res<FailedAssets>) {
+    let mut lines = failed.0.clone();
+    lines.push(String::new());
+    lines.push(locale.get("loading.error_hint").to_string());
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+            StateScoped(GameState::Error),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(locale.get("loading.error_title")),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(lines.join("\n")),
+                TextFont {
+                    font_size: 8.0,
+                    ..default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+        });
+}
@@ -0,0 +1,134 @@
+//! Translations of the text the player sees. Each language is a table of
+//! keys to strings in `assets/lang/<code>.lang.ron`, built into the game so
+//! the loading screen has them too. A key missing from a table falls back to
+//! English, and one missing from English shows the key itself.
+//!
+//! Strings can have `{name}` in them, filled in by [`Locale::format`]. Text
+//! already on screen keeps its language until it's spawned again.
+//!
+//! The NES font only has upper case ASCII, `BitmapText` folds accented
+//! letters down to the plain ones it can draw.
+
+use std::{fmt, str::FromStr};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+const ENGLISH: &str = include_str!("../assets/lang/en.lang.ron");
+const FRENCH: &str = include_str!("../assets/lang/fr.lang.ron");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    English,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+
+    /// The one after this in `ALL`, wrapping round
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|language| *language == self);
+        Self::ALL[i.map_or(0, |i| (i + 1) % Self::ALL.len())]
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Language::English => ENGLISH,
+            Language::French => FRENCH,
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "english" => Ok(Language::English),
+            "french" => Ok(Language::French),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::English => "english",
+            Language::French => "french",
+        })
+    }
+}
+
+fn parse_table(language: Language) -> HashMap<String, String> {
+    ron::from_str(language.table()).unwrap_or_else(|error| {
+        println!("Could not read the {language} strings: {error}");
+        HashMap::default()
+    })
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct Locale {
+    language: Language,
+    strings: HashMap<String, String>,
+    english: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            strings: parse_table(language),
+            english: parse_table(Language::English),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.english.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// The string for `key` with each `{name}` in it replaced by its value
+    pub fn format(&self, key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new(Language::default())
+    }
+}
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        let language = app.world().resource::<Settings>().ui.language;
+        app.insert_resource(Locale::new(language)).add_systems(
+            PreUpdate,
+            switch_language.run_if(resource_changed::<Settings>),
+        );
+    }
+}
+
+fn switch_language(settings: Res<Settings>, mut locale: ResMut<Locale>) {
+    if locale.language != settings.ui.language {
+        *locale = Locale::new(settings.ui.language);
+    }
+}
@@ -1,32 +1,125 @@
-use bevy::{prelude::*, window::WindowResolution};
-use lib::SimpleGamePlugin;
+use std::time::Duration;
 
-pub mod lib;
+use basic_project::{
+    asset_pack::AssetPackPlugin,
+    mode::GameMode,
+    settings::{Settings, MAX_WINDOW_SCALE, MIN_WINDOW_SCALE, SETTINGS_FILE},
+    storage::Storage,
+    LaunchOptions, SimpleGamePlugin,
+};
+use bevy::{
+    app::ScheduleRunnerPlugin,
+    prelude::*,
+    render::{
+        settings::{RenderCreation, WgpuSettings},
+        RenderPlugin,
+    },
+    window::{ExitCondition, WindowMode, WindowResolution},
+    winit::WinitPlugin,
+};
+use clap::Parser;
 
-// Recomend going with a multiple of 2
-const WINDOW_SCALE: f32 = 2.0;
+/// Duck Hunt, the flags override the defaults the game would start with
+#[derive(Parser, Debug)]
+struct Args {
+    /// Window size as a multiple of the NES resolution, instead of the
+    /// `window.scale` setting
+    #[arg(long, value_parser = parse_scale)]
+    scale: Option<f32>,
+    #[arg(long)]
+    fullscreen: bool,
+    /// Seed every game with this instead of a random one, for races and
+    /// reproducing bugs
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Skip the menu and start a game of this mode, e.g. `time-attack`
+    #[arg(long)]
+    mode: Option<GameMode>,
+    /// Round games start on
+    #[arg(long, default_value_t = 1)]
+    round: u32,
+    /// Step the game at the NES's 60.0988 frames a second, with the pauses
+    /// counted in frames like the original
+    #[arg(long)]
+    nes_timing: bool,
+    /// No window and no GPU, for tests and servers
+    #[arg(long)]
+    headless: bool,
+}
+
+/// Same range the `window.scale` setting takes
+fn parse_scale(value: &str) -> Result<f32, String> {
+    let scale: f32 = value.parse().map_err(|error| format!("{error}"))?;
+    if (MIN_WINDOW_SCALE as f32..=MAX_WINDOW_SCALE as f32).contains(&scale) {
+        Ok(scale)
+    } else {
+        Err(format!(
+            "scale must be from {MIN_WINDOW_SCALE} to {MAX_WINDOW_SCALE}"
+        ))
+    }
+}
 
 fn main() {
-    App::new()
-        .add_plugins(
+    let args = Args::parse();
+    let mut app = App::new();
+    // Swaps out where assets are read from, so it goes before `AssetPlugin`
+    app.add_plugins(AssetPackPlugin);
+    if args.headless {
+        app.add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..Default::default()
+                })
+                .set(RenderPlugin {
+                    render_creation: RenderCreation::Automatic(WgpuSettings {
+                        backends: None,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .disable::<WinitPlugin>(),
+        )
+        // Without winit nothing drives the frames
+        .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+            1.0 / 60.0,
+        )));
+    } else {
+        // Too early for the settings resource, same as the asset packs
+        let scale = args.scale.unwrap_or_else(|| {
+            let settings =
+                Settings::load_or_default(&Storage::default().config_file(SETTINGS_FILE));
+            settings.window.scale as f32
+        });
+        app.add_plugins(
             DefaultPlugins
                 // Set the window resolution to match the NES
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: "Duck Hunt".into(),
                         name: Some("meetballs.duckhunt.client".into()),
-                        resolution: WindowResolution::new(
-                            256.0 * WINDOW_SCALE,
-                            240.0 * WINDOW_SCALE,
-                        )
-                        .with_scale_factor_override(WINDOW_SCALE),
+                        resolution: WindowResolution::new(256.0 * scale, 240.0 * scale)
+                            .with_scale_factor_override(scale),
+                        mode: if args.fullscreen {
+                            WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+                        } else {
+                            WindowMode::Windowed
+                        },
                         ..Default::default()
                     }),
                     ..Default::default()
                 })
                 // Set to nearest scaling, this will make our pixels crispy
                 .set(ImagePlugin::default_nearest()),
-        )
-        .add_plugins(SimpleGamePlugin)
-        .run();
+        );
+    }
+    app.insert_resource(LaunchOptions {
+        seed: args.seed,
+        mode: args.mode,
+        round: args.round.max(1),
+        nes_timing: args.nes_timing,
+    })
+    .add_plugins(SimpleGamePlugin)
+    .run();
 }
@@ -0,0 +1,159 @@
+//! Title menu where the game mode is picked. Starting a game sets the mode
+//! and the run seed, then hands over to `InGame`.
+
+use bevy::{ecs::system::EntityCommands, prelude::*, ui::FocusPolicy};
+
+use crate::{
+    daily::DailyChallenge, locale::Locale, mode::GameMode, rng::RunSeed, GameState, LaunchOptions,
+    SimulationDriver,
+};
+
+const MENU_COLOR: Color = Color::srgb(0.251, 0.753, 1.0);
+const BUTTON_COLOR: Color = Color::srgb(0.0, 0.0, 0.0);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+#[derive(Component)]
+pub struct MenuScreen;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct MenuButton(GameMode);
+
+pub fn setup_menu(mut commands: Commands, locale: Res<Locale>) {
+    let entries = [
+        ("menu.classic", GameMode::Classic),
+        ("menu.endless", GameMode::Endless),
+        ("menu.time_attack", GameMode::TimeAttack),
+        ("menu.daily", GameMode::Daily),
+        ("menu.marsh", GameMode::Marsh),
+        ("menu.training", GameMode::Training),
+        ("menu.hotseat", GameMode::Hotseat),
+        ("menu.versus", GameMode::Versus),
+        ("menu.be_the_duck", GameMode::BeTheDuck),
+    ];
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                // Tight enough for the buttons other plugins add to still fit
+                row_gap: Val::Px(3.0),
+                ..default()
+            },
+            BackgroundColor(MENU_COLOR),
+            MenuScreen,
+            StateScoped(GameState::Menu),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(locale.get("menu.title")),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                Node {
+                    margin: UiRect::bottom(Val::Px(6.0)),
+                    ..default()
+                },
+            ));
+            for (key, mode) in entries {
+                spawn_menu_button(parent, locale.get(key), MenuButton(mode));
+            }
+        });
+}
+
+/// A page over the whole menu, with what goes on it in a column down the
+/// middle `row_gap` pixels apart. `marker` is what closes it again.
+pub fn spawn_menu_page<'a>(
+    commands: &'a mut Commands,
+    row_gap: f32,
+    marker: impl Bundle,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(row_gap),
+            ..default()
+        },
+        BackgroundColor(MENU_COLOR),
+        GlobalZIndex(1),
+        // Clicks shouldn't reach the menu underneath
+        FocusPolicy::Block,
+        marker,
+        StateScoped(GameState::Menu),
+    ))
+}
+
+/// Adds a button in the menu's style, `marker` is what its handler looks for
+pub fn spawn_menu_button(parent: &mut ChildSpawnerCommands, label: &str, marker: impl Bundle) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(128.0),
+                padding: UiRect::all(Val::Px(4.0)),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            marker,
+        ))
+        .with_child((
+            Text::new(label),
+            TextFont {
+                font_size: 10.0,
+                ..default()
+            },
+        ));
+}
+
+#[allow(clippy::type_complexity)]
+pub fn highlight_menu_buttons(
+    mut button_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (interaction, mut background) in &mut button_query {
+        background.0 = match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+pub fn handle_menu_buttons(
+    mut commands: Commands,
+    launch: Res<LaunchOptions>,
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        start_game(&mut commands, button.0, &launch);
+        next_state.set(GameState::InGame);
+    }
+}
+
+/// Sets up the resources a local game of `mode` needs, the caller moves on
+/// to `InGame`
+pub fn start_game(commands: &mut Commands, mode: GameMode, launch: &LaunchOptions) {
+    if mode == GameMode::Daily {
+        let daily = DailyChallenge::today();
+        commands.insert_resource(RunSeed(daily.seed()));
+        commands.insert_resource(daily);
+    } else {
+        commands.insert_resource(launch.seed.map_or_else(RunSeed::default, RunSeed));
+        commands.remove_resource::<DailyChallenge>();
+    }
+    commands.insert_resource(mode);
+    commands.insert_resource(SimulationDriver::Local);
+}
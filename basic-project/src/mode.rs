@@ -0,0 +1,88 @@
+//! Which ruleset the current game is played under. Mode controllers only
+//! run while their mode is selected and steer the shared systems through
+//! resources and events.
+
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameMode {
+    /// Rounds of ten ducks like the NES
+    #[default]
+    Classic,
+    /// No rounds, ducks keep coming faster until three get away
+    Endless,
+    /// Two minutes to score as much as possible
+    TimeAttack,
+    /// Classic rules with the same ducks for everyone on a given day
+    Daily,
+    /// Two players take turns, a round each
+    Hotseat,
+    /// Two players on screen at once, mouse against gamepad
+    Versus,
+    /// Player two flies the ducks, player one shoots them
+    BeTheDuck,
+    /// Practice on slow targets with unlimited ammo, never ends
+    Training,
+    /// Classic rules out on the marsh, which the view pans across
+    Marsh,
+}
+
+impl GameMode {
+    /// How the mode is called outside of the game, e.g. on a Discord profile
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Endless => "Endless",
+            Self::TimeAttack => "Time Attack",
+            Self::Daily => "Daily Challenge",
+            Self::Hotseat => "Hotseat",
+            Self::Versus => "Versus",
+            Self::BeTheDuck => "Be the Duck",
+            Self::Training => "Training",
+            Self::Marsh => "Marsh",
+        }
+    }
+
+    /// Key of the mode's name in the language tables
+    pub fn locale_key(self) -> &'static str {
+        match self {
+            Self::Classic => "mode.classic",
+            Self::Endless => "mode.endless",
+            Self::TimeAttack => "mode.time_attack",
+            Self::Daily => "mode.daily",
+            Self::Hotseat => "mode.hotseat",
+            Self::Versus => "mode.versus",
+            Self::BeTheDuck => "mode.be_the_duck",
+            Self::Training => "mode.training",
+            Self::Marsh => "mode.marsh",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("unknown game mode `{0}`")]
+pub struct UnknownGameMode(String);
+
+/// Parses the kebab case names used on the command line, e.g. `time-attack`
+impl FromStr for GameMode {
+    type Err = UnknownGameMode;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "classic" => Ok(Self::Classic),
+            "endless" => Ok(Self::Endless),
+            "time-attack" => Ok(Self::TimeAttack),
+            "daily" => Ok(Self::Daily),
+            "hotseat" => Ok(Self::Hotseat),
+            "versus" => Ok(Self::Versus),
+            "be-the-duck" => Ok(Self::BeTheDuck),
+            "training" => Ok(Self::Training),
+            "marsh" => Ok(Self::Marsh),
+            _ => Err(UnknownGameMode(name.to_string())),
+        }
+    }
+}
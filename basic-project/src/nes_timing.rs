@@ -0,0 +1,89 @@
+//! Runs the game like the NES did, for players who want the original's
+//! timings, speedruns against it among them. Turned on with `--nes-timing`.
+//!
+//! The simulation steps once every NES frame, 60.0988 a second, from
+//! `FixedUpdate` instead of once a display frame from `Update`, so a faster
+//! monitor draws the same step more than once and a slower one runs two
+//! steps in a frame. Every step moves things along by exactly one frame.
+//!
+//! The shot pause, the banners and how long a duck stays before flying away
+//! are counted in whole frames, rounded from their usual lengths, so each
+//! lasts the same number of frames every time. Games driven by rollback or
+//! an online host keep their own clock either way.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    duck_state::Dying, flight::FlightPattern, run_simulation, LaunchOptions, PauseState,
+    SimulationDriver,
+};
+
+/// Frames a second of the NES's NTSC picture
+pub const NES_FPS: f64 = 60.0988;
+/// One NES frame, to the nanosecond
+pub const FRAME: Duration = Duration::from_nanos(16_639_267);
+
+/// Present while the game runs on NES timing
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct NesTiming;
+
+/// Whether the simulation is stepping once every NES frame right now, for
+/// things outside of it that have to keep in step
+pub fn steps_nes_frames(nes_timing: Option<Res<NesTiming>>, driver: Res<SimulationDriver>) -> bool {
+    nes_timing.is_some() && *driver == SimulationDriver::Local
+}
+
+/// The whole number of NES frames nearest to `duration`
+pub fn frames(duration: Duration) -> u32 {
+    (duration.as_secs_f64() * NES_FPS).round() as u32
+}
+
+/// `duration` rounded to the nearest whole NES frame
+pub fn whole_frames(duration: Duration) -> Duration {
+    FRAME * frames(duration)
+}
+
+pub struct NesTimingPlugin;
+
+impl Plugin for NesTimingPlugin {
+    fn build(&self, app: &mut App) {
+        let enabled = app
+            .world()
+            .get_resource::<LaunchOptions>()
+            .is_some_and(|options| options.nes_timing);
+        if !enabled {
+            return;
+        }
+        app.insert_resource(NesTiming)
+            .insert_resource(Time::<Fixed>::from_duration(FRAME))
+            .add_observer(count_shot_pause)
+            .add_observer(count_stay)
+            .add_systems(
+                FixedUpdate,
+                run_simulation.run_if(
+                    in_state(PauseState::Running).and(resource_equals(SimulationDriver::Local)),
+                ),
+            );
+    }
+}
+
+fn count_shot_pause(trigger: Trigger<OnInsert, Dying>, mut dying_query: Query<&mut Dying>) {
+    let Ok(mut dying) = dying_query.get_mut(trigger.target()) else {
+        return;
+    };
+    let pause = whole_frames(dying.pause.duration());
+    dying.pause.set_duration(pause);
+}
+
+fn count_stay(
+    trigger: Trigger<OnInsert, FlightPattern>,
+    mut pattern_query: Query<&mut FlightPattern>,
+) {
+    let Ok(mut pattern) = pattern_query.get_mut(trigger.target()) else {
+        return;
+    };
+    let stay = whole_frames(pattern.stay_duration());
+    pattern.set_stay_duration(stay);
+}
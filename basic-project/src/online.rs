@@ -0,0 +1,390 @@
+//! Versus over the network. The host runs the whole simulation like a
+//! local versus game with the remote player in the second seat, the
+//! joining player runs with `SimulationDriver::Remote` and only draws the
+//! snapshots the host sends back.
+//!
+//! The address to host on or join comes from `DUCK_HUNT_ADDR` and defaults
+//! to localhost.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::SystemTime,
+};
+
+use bevy::{platform::collections::HashMap, prelude::*, window::PrimaryWindow};
+use bevy_renet::{
+    netcode::{
+        ClientAuthentication, NetcodeClientPlugin, NetcodeClientTransport, NetcodeServerPlugin,
+        NetcodeServerTransport, ServerAuthentication, ServerConfig,
+    },
+    renet::{ConnectionConfig, DefaultChannel, RenetClient, RenetServer, ServerEvent},
+    RenetClientPlugin, RenetServerPlugin,
+};
+use duck_hunt_shared::protocol::{
+    ClientMessage, DuckState, ServerMessage, Snapshot, DEFAULT_PORT, PROTOCOL_ID,
+};
+
+use crate::{
+    events::PlayerId,
+    locale::Locale,
+    menu::{spawn_menu_button, MenuScreen},
+    mode::GameMode,
+    rng::RunSeed,
+    sinden::PlayArea,
+    versus::{Reticle, VersusScores},
+    Dead, Duck, FrameSet, GameAssets, GameInputEvent, GameState, SimulationDriver, SpawnTimer,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetRole {
+    Host,
+    Join,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OnlineButton(NetRole);
+
+/// Local sprite for each duck in the host's snapshots
+#[derive(Resource, Default)]
+pub struct RemoteDucks(HashMap<u64, Entity>);
+
+pub struct OnlinePlugin;
+
+impl Plugin for OnlinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            RenetServerPlugin,
+            NetcodeServerPlugin,
+            RenetClientPlugin,
+            NetcodeClientPlugin,
+        ))
+        .init_resource::<RemoteDucks>()
+        .add_systems(
+            OnEnter(GameState::Menu),
+            add_online_buttons.after(crate::menu::setup_menu),
+        )
+        .add_systems(
+            Update,
+            handle_online_buttons.run_if(in_state(GameState::Menu)),
+        )
+        .add_systems(
+            Update,
+            (
+                hold_until_joined,
+                receive_client_messages,
+                send_snapshot.after(receive_client_messages),
+            )
+                .run_if(in_state(GameState::InGame).and(resource_exists::<RenetServer>)),
+        )
+        .add_systems(
+            OnEnter(GameState::GameOver),
+            send_game_over.run_if(resource_exists::<RenetServer>),
+        )
+        .add_systems(
+            Update,
+            (
+                send_client_input.after(FrameSet::Input),
+                receive_server_messages,
+            )
+                .run_if(in_state(GameState::InGame).and(resource_exists::<RenetClient>)),
+        );
+    }
+}
+
+fn server_addr() -> SocketAddr {
+    std::env::var("DUCK_HUNT_ADDR")
+        .ok()
+        .and_then(|addr| addr.parse().ok())
+        .unwrap_or_else(|| SocketAddr::new(Ipv4Addr::LOCALHOST.into(), DEFAULT_PORT))
+}
+
+fn add_online_buttons(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    menu_query: Query<Entity, With<MenuScreen>>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).with_children(|parent| {
+            spawn_menu_button(
+                parent,
+                locale.get("online.host"),
+                OnlineButton(NetRole::Host),
+            );
+            spawn_menu_button(
+                parent,
+                locale.get("online.join"),
+                OnlineButton(NetRole::Join),
+            );
+        });
+    }
+}
+
+fn handle_online_buttons(
+    mut commands: Commands,
+    button_query: Query<(&Interaction, &OnlineButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let addr = server_addr();
+        let current_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let started = match button.0 {
+            NetRole::Host => start_host(&mut commands, addr, current_time),
+            NetRole::Join => start_join(&mut commands, addr, current_time),
+        };
+        if let Err(error) = started {
+            println!("Could not go online at {addr}: {error}");
+            continue;
+        }
+        commands.insert_resource(RunSeed::default());
+        commands.insert_resource(GameMode::Versus);
+        next_state.set(GameState::InGame);
+    }
+}
+
+fn start_host(
+    commands: &mut Commands,
+    addr: SocketAddr,
+    current_time: std::time::Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let socket = UdpSocket::bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), addr.port()))?;
+    let transport = NetcodeServerTransport::new(
+        ServerConfig {
+            current_time,
+            max_clients: 1,
+            protocol_id: PROTOCOL_ID,
+            public_addresses: vec![addr],
+            authentication: ServerAuthentication::Unsecure,
+        },
+        socket,
+    )?;
+    println!("Hosting on {addr}");
+    commands.insert_resource(RenetServer::new(ConnectionConfig::default()));
+    commands.insert_resource(transport);
+    commands.insert_resource(SimulationDriver::Local);
+    Ok(())
+}
+
+fn start_join(
+    commands: &mut Commands,
+    addr: SocketAddr,
+    current_time: std::time::Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let socket = UdpSocket::bind(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0))?;
+    let transport = NetcodeClientTransport::new(
+        current_time,
+        ClientAuthentication::Unsecure {
+            protocol_id: PROTOCOL_ID,
+            client_id: current_time.as_millis() as u64,
+            server_addr: addr,
+            user_data: None,
+        },
+        socket,
+    )?;
+    println!("Joining {addr}");
+    commands.insert_resource(RenetClient::new(ConnectionConfig::default()));
+    commands.insert_resource(transport);
+    commands.insert_resource(SimulationDriver::Remote);
+    Ok(())
+}
+
+// No ducks until there's someone to compete with, and the game is over
+// once they leave
+fn hold_until_joined(
+    server: Res<RenetServer>,
+    mut spawn_timer: ResMut<SpawnTimer>,
+    mut server_events: EventReader<ServerEvent>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if server.connected_clients() == 0 {
+        spawn_timer.0.pause();
+    } else {
+        spawn_timer.0.unpause();
+    }
+    for event in server_events.read() {
+        if let ServerEvent::ClientDisconnected { .. } = event {
+            next_state.set(GameState::GameOver);
+        }
+    }
+}
+
+// The remote player sits in player two's seat
+fn receive_client_messages(
+    mut server: ResMut<RenetServer>,
+    mut reticle_query: Query<(&Reticle, &mut Transform)>,
+    mut game_input_event_writer: EventWriter<GameInputEvent>,
+) {
+    let channels: [u8; 2] = [
+        DefaultChannel::Unreliable.into(),
+        DefaultChannel::ReliableOrdered.into(),
+    ];
+    for client_id in server.clients_id() {
+        for channel in channels {
+            while let Some(bytes) = server.receive_message(client_id, channel) {
+                let Ok(message) = bincode::deserialize::<ClientMessage>(&bytes) else {
+                    continue;
+                };
+                match message {
+                    ClientMessage::Cursor(position) => {
+                        for (reticle, mut transform) in &mut reticle_query {
+                            if reticle.player == PlayerId(1) {
+                                transform.translation.x = position[0];
+                                transform.translation.y = position[1];
+                            }
+                        }
+                    }
+                    ClientMessage::Shoot(position) => {
+                        game_input_event_writer.write(GameInputEvent::Shoot(
+                            PlayerId(1),
+                            Vec2::from(position),
+                            None,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn send_snapshot(
+    mut server: ResMut<RenetServer>,
+    versus_scores: Res<VersusScores>,
+    duck_query: Query<(Entity, &Transform, &Sprite), (With<Duck>, Without<Dead>)>,
+    reticle_query: Query<(&Reticle, &Transform), Without<Duck>>,
+) {
+    let mut snapshot = Snapshot {
+        scores: versus_scores.scores,
+        ..default()
+    };
+    for (entity, transform, sprite) in &duck_query {
+        snapshot.ducks.push(DuckState {
+            id: entity.to_bits(),
+            position: transform.translation.xy().into(),
+            scale: transform.scale.x,
+            frame: sprite.texture_atlas.as_ref().map_or(0, |atlas| atlas.index),
+            flip_x: sprite.flip_x,
+        });
+    }
+    for (reticle, transform) in &reticle_query {
+        let PlayerId(player) = reticle.player;
+        snapshot.cursors[player] = transform.translation.xy().into();
+    }
+    if let Ok(bytes) = bincode::serialize(&ServerMessage::Snapshot(snapshot)) {
+        server.broadcast_message(DefaultChannel::Unreliable, bytes);
+    }
+}
+
+fn send_game_over(mut server: ResMut<RenetServer>) {
+    if let Ok(bytes) = bincode::serialize(&ServerMessage::GameOver) {
+        server.broadcast_message(DefaultChannel::ReliableOrdered, bytes);
+    }
+}
+
+fn send_client_input(
+    mut client: ResMut<RenetClient>,
+    play_area: Res<PlayArea>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut game_input_event_reader: EventReader<GameInputEvent>,
+) {
+    if !client.is_connected() {
+        game_input_event_reader.clear();
+        return;
+    }
+    if let Some(position) = window_query
+        .single()
+        .ok()
+        .and_then(|win| win.cursor_position())
+    {
+        let position = play_area.to_world(position).to_array();
+        if let Ok(bytes) = bincode::serialize(&ClientMessage::Cursor(position)) {
+            client.send_message(DefaultChannel::Unreliable, bytes);
+        }
+    }
+    // The local mouse shoots as player one, the host knows it's player two
+    for event in game_input_event_reader.read() {
+        let GameInputEvent::Shoot(_, position, _) = event else {
+            continue;
+        };
+        if let Ok(bytes) = bincode::serialize(&ClientMessage::Shoot((*position).into())) {
+            client.send_message(DefaultChannel::ReliableOrdered, bytes);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn receive_server_messages(
+    mut commands: Commands,
+    mut client: ResMut<RenetClient>,
+    game_assets: Res<GameAssets>,
+    mut remote_ducks: ResMut<RemoteDucks>,
+    mut versus_scores: ResMut<VersusScores>,
+    mut duck_query: Query<(&mut Transform, &mut Sprite), Without<Reticle>>,
+    mut reticle_query: Query<(&Reticle, &mut Transform), With<Reticle>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if client.is_disconnected() {
+        next_state.set(GameState::GameOver);
+        return;
+    }
+    let mut latest = None;
+    while let Some(bytes) = client.receive_message(DefaultChannel::ReliableOrdered) {
+        if let Ok(ServerMessage::GameOver) = bincode::deserialize(&bytes) {
+            next_state.set(GameState::GameOver);
+        }
+    }
+    // Only the newest snapshot matters, the older ones are already stale
+    while let Some(bytes) = client.receive_message(DefaultChannel::Unreliable) {
+        if let Ok(ServerMessage::Snapshot(snapshot)) = bincode::deserialize(&bytes) {
+            latest = Some(snapshot);
+        }
+    }
+    let Some(snapshot) = latest else {
+        return;
+    };
+
+    versus_scores.scores = snapshot.scores;
+    for (reticle, mut transform) in &mut reticle_query {
+        let PlayerId(player) = reticle.player;
+        transform.translation.x = snapshot.cursors[player][0];
+        transform.translation.y = snapshot.cursors[player][1];
+    }
+
+    remote_ducks.0.retain(|id, entity| {
+        let alive = snapshot.ducks.iter().any(|duck| duck.id == *id);
+        if !alive {
+            commands.entity(*entity).despawn();
+        }
+        alive
+    });
+    for duck in &snapshot.ducks {
+        let transform = Transform::from_translation(Vec2::from(duck.position).extend(0.0))
+            .with_scale(Vec3::splat(duck.scale));
+        let Some(&entity) = remote_ducks.0.get(&duck.id) else {
+            let mut sprite = Sprite::from_atlas_image(
+                game_assets.duck_image().clone(),
+                TextureAtlas {
+                    layout: game_assets.duck_layout().clone(),
+                    index: duck.frame,
+                },
+            );
+            sprite.flip_x = duck.flip_x;
+            let entity = commands
+                .spawn((sprite, transform, StateScoped(GameState::InGame)))
+                .id();
+            remote_ducks.0.insert(duck.id, entity);
+            continue;
+        };
+        if let Ok((mut local_transform, mut sprite)) = duck_query.get_mut(entity) {
+            *local_transform = transform;
+            sprite.flip_x = duck.flip_x;
+            if let Some(atlas) = &mut sprite.texture_atlas {
+                atlas.index = duck.frame;
+            }
+        }
+    }
+}
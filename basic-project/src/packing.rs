@@ -0,0 +1,332 @@
+//! Every sheet, the background, the ducks, the dog, the HUD's weapon icons,
+//! the power-ups, the font, the cloud and the crosshair, packed into one
+//! image once loading's done so everything cut from them goes out in one
+//! batch. The ducks and the dog are the skin's, and each duck look is
+//! packed next to them, so a duck changing look only changes its layout.
+//!
+//! `GameAssets` points at the packed image afterwards, with each grid's
+//! layout moved to where its sheet ended up, so atlas sprites don't need
+//! to know. Sprites cut from the background by hand go through
+//! `GameAssets::background_rect`, whole image ones through helpers like
+//! `GameAssets::cloud_sprite`, and the ducks through
+//! `GameAssets::show_look`.
+//!
+//! The sheets as they were loaded are kept, saving one with `hot_reload`
+//! on packs them all again, and so does picking another skin or a look
+//! being built. Sprites cut by hand keep their old rects until they're
+//! spawned again.
+
+use bevy::{
+    image::{TextureAtlasBuilder, TextureAtlasBuilderError},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{
+    duck_look::{DuckLook, DuckLooks},
+    skin::Skin,
+    GameAssets, GameState,
+};
+
+// Keeps the nearest neighbour lookups at the edge of a frame from picking up
+// the sheet next to it
+const PADDING: UVec2 = UVec2::ONE;
+// What the smallest GPUs still take, everything fits in far less
+const MAX_SIZE: UVec2 = UVec2::splat(2048);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackedSheet {
+    Background,
+    WeaponIcons,
+    PowerUps,
+    Font,
+    Cloud,
+    Crosshair,
+    Dog,
+    Duck(DuckLook),
+}
+
+impl PackedSheet {
+    /// The ones packed as they were loaded, the dog and the ducks change
+    /// with the skin
+    const FIXED: [PackedSheet; 6] = [
+        PackedSheet::Background,
+        PackedSheet::WeaponIcons,
+        PackedSheet::PowerUps,
+        PackedSheet::Font,
+        PackedSheet::Cloud,
+        PackedSheet::Crosshair,
+    ];
+
+    /// Where `GameAssets` keeps it, the skin's sheets are left as they are
+    /// and handed out through `GameAssets::packed_image`
+    fn image(self, game_assets: &mut GameAssets) -> Option<&mut Handle<Image>> {
+        match self {
+            PackedSheet::Background => Some(&mut game_assets.background_spritesheet),
+            PackedSheet::WeaponIcons => Some(&mut game_assets.weapon_icons_spritesheet),
+            PackedSheet::PowerUps => Some(&mut game_assets.power_ups_spritesheet),
+            PackedSheet::Font => Some(&mut game_assets.font),
+            PackedSheet::Cloud => Some(&mut game_assets.cloud),
+            PackedSheet::Crosshair => Some(&mut game_assets.crosshair),
+            PackedSheet::Dog | PackedSheet::Duck(_) => None,
+        }
+    }
+
+    /// The grid it's cut into, the cloud and the crosshair are drawn whole
+    /// and the ducks are cut by their JSON
+    fn layout(self, game_assets: &GameAssets) -> Option<&Handle<TextureAtlasLayout>> {
+        match self {
+            PackedSheet::Background => Some(&game_assets.background_layout),
+            PackedSheet::WeaponIcons => Some(&game_assets.weapon_icons_layout),
+            PackedSheet::PowerUps => Some(&game_assets.power_ups_layout),
+            PackedSheet::Font => Some(&game_assets.font_layout),
+            PackedSheet::Dog => Some(&game_assets.dog_layout),
+            PackedSheet::Cloud | PackedSheet::Crosshair | PackedSheet::Duck(_) => None,
+        }
+    }
+}
+
+/// One of the sheets as it was loaded
+#[derive(Clone)]
+struct Original {
+    sheet: PackedSheet,
+    image: Handle<Image>,
+    /// Size of a frame of its grid, if it has one
+    tile: Option<UVec2>,
+}
+
+#[derive(Resource)]
+struct PackedSheets {
+    image: Handle<Image>,
+    /// The fixed ones
+    originals: Vec<Original>,
+    /// Size of a frame of the dog, every skin's is cut the same
+    dog_tile: Option<UVec2>,
+    /// What the dog and the ducks were packed from last time
+    skin: Skin,
+}
+
+impl PackedSheets {
+    /// The fixed sheets with the skin's dog and ducks, and each duck look
+    /// built so far
+    fn sources(&self, game_assets: &GameAssets, looks: &DuckLooks) -> Vec<Original> {
+        let mut sources = self.originals.clone();
+        sources.push(Original {
+            sheet: PackedSheet::Dog,
+            image: game_assets.skin_dog_image().clone(),
+            tile: self.dog_tile,
+        });
+        sources.push(Original {
+            sheet: PackedSheet::Duck(DuckLook::Plain),
+            image: game_assets.skin_duck_image().clone(),
+            tile: None,
+        });
+        sources.extend(looks.iter().map(|(look, image)| Original {
+            sheet: PackedSheet::Duck(look),
+            image: image.clone(),
+            tile: None,
+        }));
+        sources
+    }
+}
+
+pub struct PackingPlugin;
+
+impl Plugin for PackingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::Loading), pack_sheets)
+            .add_systems(Update, repack_modified_sheets);
+    }
+}
+
+/// Every sheet in one image, and where each went
+fn pack(
+    originals: &[Original],
+    images: &Assets<Image>,
+) -> Result<(Image, HashMap<PackedSheet, URect>), TextureAtlasBuilderError> {
+    let mut builder = TextureAtlasBuilder::default();
+    builder.padding(PADDING).max_size(MAX_SIZE);
+    for original in originals {
+        if let Some(image) = images.get(&original.image) {
+            builder.add_texture(Some(original.image.id()), image);
+        }
+    }
+    let (layout, sources, image) = builder.build()?;
+    let rects = originals
+        .iter()
+        .filter_map(|original| {
+            let rect = sources.texture_rect(&layout, &original.image)?;
+            Some((original.sheet, rect))
+        })
+        .collect();
+    Ok((image, rects))
+}
+
+/// The grid a sheet was cut into, moved to where it is in the packed image.
+/// A sheet that's grown or shrunk gets as many frames as fit.
+fn packed_layout(rect: URect, tile: UVec2, size: UVec2) -> TextureAtlasLayout {
+    let mut layout = TextureAtlasLayout::new_empty(size);
+    let tile = tile.max(UVec2::ONE);
+    let frames = (rect.size() / tile).max(UVec2::ONE);
+    for row in 0..frames.y {
+        for column in 0..frames.x {
+            let min = rect.min + UVec2::new(column, row) * tile;
+            layout.add_texture(URect::from_corners(min, min + tile));
+        }
+    }
+    layout
+}
+
+/// The duck frames cut from the skin's JSON, moved by `offset`
+fn moved_layout(layout: &TextureAtlasLayout, offset: UVec2, size: UVec2) -> TextureAtlasLayout {
+    let mut moved = TextureAtlasLayout::new_empty(size);
+    for rect in &layout.textures {
+        moved.add_texture(URect::from_corners(rect.min + offset, rect.max + offset));
+    }
+    moved
+}
+
+/// Points `game_assets` at `image` with every grid moved to match
+fn apply_pack(
+    image: &Handle<Image>,
+    size: UVec2,
+    rects: HashMap<PackedSheet, URect>,
+    originals: &[Original],
+    game_assets: &mut GameAssets,
+    layouts: &mut Assets<TextureAtlasLayout>,
+) {
+    let duck_layout = layouts.get(game_assets.skin_duck_layout()).cloned();
+    for original in originals {
+        let Some(&rect) = rects.get(&original.sheet) else {
+            continue;
+        };
+        if let Some(sheet_image) = original.sheet.image(game_assets) {
+            *sheet_image = image.clone();
+        }
+        if let (Some(tile), Some(layout)) = (original.tile, original.sheet.layout(game_assets)) {
+            layouts.insert(layout, packed_layout(rect, tile, size));
+        }
+        let (PackedSheet::Duck(look), Some(duck_layout)) = (original.sheet, &duck_layout) else {
+            continue;
+        };
+        let moved = moved_layout(duck_layout, rect.min, size);
+        // Sprites hold on to the handle, so it's only ever made once
+        match game_assets.duck_layouts.get(&look) {
+            Some(layout) => layouts.insert(layout, moved),
+            None => {
+                let layout = layouts.add(moved);
+                game_assets.duck_layouts.insert(look, layout);
+            }
+        }
+    }
+    game_assets
+        .duck_layouts
+        .retain(|look, _| rects.contains_key(&PackedSheet::Duck(*look)));
+    game_assets.packed = rects;
+    game_assets.packed_image = Some(image.clone());
+}
+
+/// Size of a frame of `sheet`'s grid, if it has one
+fn tile(
+    sheet: PackedSheet,
+    game_assets: &GameAssets,
+    layouts: &Assets<TextureAtlasLayout>,
+) -> Option<UVec2> {
+    let layout = layouts.get(sheet.layout(game_assets)?)?;
+    layout.textures.first().map(URect::size)
+}
+
+fn pack_sheets(
+    mut commands: Commands,
+    game_assets: Option<ResMut<GameAssets>>,
+    looks: Res<DuckLooks>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    // Loading failed, there's nothing to draw anyway
+    let Some(mut game_assets) = game_assets else {
+        return;
+    };
+    let originals = PackedSheet::FIXED
+        .into_iter()
+        .filter_map(|sheet| {
+            Some(Original {
+                sheet,
+                image: sheet.image(&mut game_assets)?.clone(),
+                tile: tile(sheet, &game_assets, &layouts),
+            })
+        })
+        .collect();
+    let packed = PackedSheets {
+        image: Handle::default(),
+        originals,
+        dog_tile: tile(PackedSheet::Dog, &game_assets, &layouts),
+        skin: game_assets.skin(),
+    };
+    let sources = packed.sources(&game_assets, &looks);
+    // Left as separate sheets, which still draws, just in more batches
+    let (image, rects) = match pack(&sources, &images) {
+        Ok(packed) => packed,
+        Err(error) => {
+            println!("Could not pack the spritesheets: {error}");
+            return;
+        }
+    };
+    let size = image.size();
+    let image = images.add(image);
+    apply_pack(
+        &image,
+        size,
+        rects,
+        &sources,
+        &mut game_assets,
+        &mut layouts,
+    );
+    println!("Packed {} spritesheets into {size}", sources.len());
+    commands.insert_resource(PackedSheets { image, ..packed });
+}
+
+fn repack_modified_sheets(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    packed: Option<ResMut<PackedSheets>>,
+    game_assets: Option<ResMut<GameAssets>>,
+    looks: Res<DuckLooks>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let (Some(mut packed), Some(mut game_assets)) = (packed, game_assets) else {
+        image_events.clear();
+        return;
+    };
+    let sources = packed.sources(&game_assets, &looks);
+    // Read through all of them either way, so they don't pile up for the
+    // next frame
+    let modified = image_events.read().fold(false, |modified, event| {
+        let AssetEvent::Modified { id } = event else {
+            return modified;
+        };
+        modified || sources.iter().any(|source| source.image.id() == *id)
+    });
+    if !modified && !looks.is_changed() && packed.skin == game_assets.skin() {
+        return;
+    }
+    packed.skin = game_assets.skin();
+    let (image, rects) = match pack(&sources, &images) {
+        Ok(packed) => packed,
+        Err(error) => {
+            println!("Could not pack the spritesheets again: {error}");
+            return;
+        }
+    };
+    let size = image.size();
+    images.insert(&packed.image, image);
+    apply_pack(
+        &packed.image,
+        size,
+        rects,
+        &sources,
+        &mut game_assets,
+        &mut layouts,
+    );
+    println!("Packed the spritesheets again");
+}
@@ -0,0 +1,174 @@
+//! Escape pauses a local game. Everything that moves the game along runs in
+//! `PauseState::Running`, so pausing is just leaving it.
+//!
+//! Losing window focus pauses it too, unless turned off in the settings. When
+//! focus comes back it counts down for a moment before playing on, so the
+//! player has time to find the cursor again.
+
+use bevy::{prelude::*, window::WindowFocused};
+
+use crate::{
+    locale::Locale, menu::spawn_menu_button, session::SessionButton, settings::Settings, PauseState,
+};
+
+const RESUME_COUNTDOWN: f32 = 3.0;
+
+/// Root of the pause menu, photo mode hides it while it's up
+#[derive(Component)]
+pub struct PauseMenu;
+
+#[derive(Component)]
+pub struct ResumeButton;
+
+#[derive(Component)]
+pub struct ResumeCountdownText;
+
+/// Whether the current pause was the game's own doing, only those resume by
+/// themselves
+#[derive(Resource, Default)]
+pub struct AutoPause {
+    active: bool,
+    countdown: Option<Timer>,
+}
+
+pub fn toggle_pause(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    next_pause_state.set(match pause_state.get() {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+    });
+}
+
+pub fn setup_pause_menu(mut commands: Commands, locale: Res<Locale>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.5)),
+            PauseMenu,
+            StateScoped(PauseState::Paused),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(locale.get("pause.title")),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                Node {
+                    margin: UiRect::bottom(Val::Px(12.0)),
+                    ..default()
+                },
+            ));
+            spawn_menu_button(parent, locale.get("pause.resume"), ResumeButton);
+            spawn_menu_button(
+                parent,
+                locale.get("session.restart"),
+                SessionButton::Restart,
+            );
+            spawn_menu_button(parent, locale.get("session.menu"), SessionButton::Menu);
+        });
+}
+
+pub fn handle_resume_button(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<ResumeButton>)>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    if button_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_pause_state.set(PauseState::Running);
+    }
+}
+
+pub fn auto_pause_on_focus_loss(
+    settings: Res<Settings>,
+    pause_state: Res<State<PauseState>>,
+    mut auto_pause: ResMut<AutoPause>,
+    mut focus_reader: EventReader<WindowFocused>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    for event in focus_reader.read() {
+        match (event.focused, pause_state.get()) {
+            (false, PauseState::Running) if settings.window.auto_pause => {
+                auto_pause.active = true;
+                next_pause_state.set(PauseState::Paused);
+            }
+            // Tabbed away again before it got going
+            (false, PauseState::Paused) => auto_pause.countdown = None,
+            (true, PauseState::Paused) if auto_pause.active => {
+                auto_pause.countdown = Some(Timer::from_seconds(RESUME_COUNTDOWN, TimerMode::Once));
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn count_down_to_resume(
+    mut commands: Commands,
+    time: Res<Time>,
+    locale: Res<Locale>,
+    mut auto_pause: ResMut<AutoPause>,
+    mut text_query: Query<(Entity, &mut Text), With<ResumeCountdownText>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    let Some(countdown) = auto_pause.countdown.as_mut() else {
+        for (entity, _) in &text_query {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+    if countdown.tick(time.delta()).finished() {
+        next_pause_state.set(PauseState::Running);
+        return;
+    }
+    let line = locale.format(
+        "pause.resuming_in",
+        &[("seconds", &countdown.remaining_secs().ceil())],
+    );
+    match text_query.single_mut() {
+        Ok((_, mut text)) => {
+            if text.0 != line {
+                text.0 = line;
+            }
+        }
+        Err(_) => {
+            commands.spawn((
+                Text::new(line),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(24.0),
+                    width: Val::Percent(100.0),
+                    ..default()
+                },
+                GlobalZIndex(1),
+                ResumeCountdownText,
+                StateScoped(PauseState::Paused),
+            ));
+        }
+    }
+}
+
+// However the pause ended, the next one starts fresh
+pub fn reset_auto_pause(mut auto_pause: ResMut<AutoPause>) {
+    *auto_pause = AutoPause::default();
+}
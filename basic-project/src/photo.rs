@@ -0,0 +1,222 @@
+//! Photo mode, P on the pause menu. The frozen scene can be panned with the
+//! arrow keys or WASD and zoomed with the mouse wheel, H hides the HUD and
+//! Space saves it next to the screenshots. P goes back to the pause menu and
+//! Escape straight back into the game.
+//!
+//! Photos are taken like F12 screenshots, from the camera rendering the game
+//! at the NES resolution, so they're the game's own pixels whatever size the
+//! window is, and the UI isn't in them.
+
+use bevy::{
+    input::mouse::AccumulatedMouseScroll, prelude::*, render::view::screenshot::Screenshot,
+};
+
+use crate::{
+    hud::Hud,
+    locale::Locale,
+    pause::PauseMenu,
+    screenshot::{save_screenshot, NativeCamera, NativeTarget},
+    sinden::PlayArea,
+    storage::Storage,
+    view::View,
+    PauseState,
+};
+
+const SCREEN_SIZE: Vec2 = Vec2::new(256.0, 240.0);
+// Pixels per second at the default zoom, slower the closer in
+const PAN_SPEED: f32 = 96.0;
+// Zoom, 1 is the whole screen and there's nothing past it. The game camera's
+// projection is this times the border's scale, the native camera's is just
+// this
+const MIN_ZOOM: f32 = 0.25;
+const ZOOM_STEP: f32 = 0.1;
+
+/// Only exists while paused
+#[derive(SubStates, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[source(PauseState = PauseState::Paused)]
+#[states(scoped_entities)]
+pub enum PhotoMode {
+    #[default]
+    Off,
+    On,
+}
+
+/// Put on HUD entities photo mode hid, with the visibility to give back
+#[derive(Component)]
+pub struct PhotoHidden(Visibility);
+
+pub struct PhotoPlugin;
+
+impl Plugin for PhotoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<PhotoMode>()
+            .add_systems(OnEnter(PhotoMode::On), setup_photo_mode)
+            .add_systems(OnExit(PhotoMode::On), (leave_photo_mode, show_hud))
+            .add_systems(
+                Update,
+                toggle_photo_mode.run_if(in_state(PauseState::Paused)),
+            )
+            .add_systems(
+                Update,
+                (pan_and_zoom, toggle_hud, take_photo).run_if(in_state(PhotoMode::On)),
+            );
+    }
+}
+
+fn toggle_photo_mode(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    photo_mode: Res<State<PhotoMode>>,
+    mut next_photo_mode: ResMut<NextState<PhotoMode>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    next_photo_mode.set(match photo_mode.get() {
+        PhotoMode::Off => PhotoMode::On,
+        PhotoMode::On => PhotoMode::Off,
+    });
+}
+
+fn setup_photo_mode(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    mut menu_query: Query<&mut Visibility, With<PauseMenu>>,
+) {
+    for mut visibility in &mut menu_query {
+        *visibility = Visibility::Hidden;
+    }
+    commands.spawn((
+        Text::new(locale.get("photo.controls")),
+        TextFont {
+            font_size: 8.0,
+            ..default()
+        },
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(4.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        StateScoped(PhotoMode::On),
+    ));
+}
+
+fn pan_and_zoom(
+    time: Res<Time<Real>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_scroll: Res<AccumulatedMouseScroll>,
+    play_area: Res<PlayArea>,
+    view: Res<View>,
+    mut camera_query: Query<(&mut Transform, &mut Projection, Has<NativeCamera>), With<Camera2d>>,
+) {
+    let mut input = Vec2::ZERO;
+    for (keys, direction) in [
+        ([KeyCode::ArrowLeft, KeyCode::KeyA], Vec2::NEG_X),
+        ([KeyCode::ArrowRight, KeyCode::KeyD], Vec2::X),
+        ([KeyCode::ArrowDown, KeyCode::KeyS], Vec2::NEG_Y),
+        ([KeyCode::ArrowUp, KeyCode::KeyW], Vec2::Y),
+    ] {
+        if keyboard_input.any_pressed(keys) {
+            input += direction;
+        }
+    }
+    // One step a notch, however far a touchpad says it scrolled
+    let mut zoom = if mouse_scroll.delta.y == 0.0 {
+        0.0
+    } else {
+        -mouse_scroll.delta.y.signum() * ZOOM_STEP
+    };
+    if keyboard_input.just_pressed(KeyCode::Equal) {
+        zoom -= ZOOM_STEP;
+    }
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        zoom += ZOOM_STEP;
+    }
+    // The native camera gets the same view, it's what gets saved
+    for (mut transform, mut projection, native) in &mut camera_query {
+        let Projection::Orthographic(ortho) = projection.as_mut() else {
+            continue;
+        };
+        let base = if native { 1.0 } else { play_area.scale };
+        let scale = (ortho.scale / base + zoom).clamp(MIN_ZOOM, 1.0);
+        ortho.scale = scale * base;
+        // Stays over the scene, fully zoomed out there's nowhere to go
+        let bound = SCREEN_SIZE / 2.0 * (1.0 - scale);
+        let position = transform.translation.xy()
+            + input.normalize_or_zero() * PAN_SPEED * scale * time.delta_secs();
+        let center = Vec2::new(view.x, 0.0);
+        let position = position.clamp(center - bound, center + bound);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_hud(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    hud_query: Query<(Entity, &Visibility), (With<Hud>, Without<PhotoHidden>)>,
+    hidden_query: Query<(Entity, &PhotoHidden)>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+    if hidden_query.is_empty() {
+        for (entity, visibility) in &hud_query {
+            commands
+                .entity(entity)
+                .insert((PhotoHidden(*visibility), Visibility::Hidden));
+        }
+    } else {
+        for (entity, hidden) in &hidden_query {
+            commands
+                .entity(entity)
+                .insert(hidden.0)
+                .remove::<PhotoHidden>();
+        }
+    }
+}
+
+fn show_hud(mut commands: Commands, hidden_query: Query<(Entity, &PhotoHidden)>) {
+    for (entity, hidden) in &hidden_query {
+        // Might be on its way out with the rest of the game
+        commands
+            .entity(entity)
+            .try_insert(hidden.0)
+            .try_remove::<PhotoHidden>();
+    }
+}
+
+fn take_photo(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    storage: Res<Storage>,
+    target: Res<NativeTarget>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    save_screenshot(
+        &mut commands,
+        Screenshot::image(target.0.clone()),
+        storage.pictures_dir(),
+    );
+}
+
+fn leave_photo_mode(
+    play_area: Res<PlayArea>,
+    view: Res<View>,
+    mut camera_query: Query<(&mut Transform, &mut Projection, Has<NativeCamera>), With<Camera2d>>,
+    mut menu_query: Query<&mut Visibility, With<PauseMenu>>,
+) {
+    for (mut transform, mut projection, native) in &mut camera_query {
+        *transform = Transform::from_xyz(view.x, 0.0, 0.0);
+        if let Projection::Orthographic(ortho) = projection.as_mut() {
+            ortho.scale = if native { 1.0 } else { play_area.scale };
+        }
+    }
+    for mut visibility in &mut menu_query {
+        *visibility = Visibility::Inherited;
+    }
+}
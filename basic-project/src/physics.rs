@@ -0,0 +1,131 @@
+//! Shot ducks turn into rigid bodies once the shot pause is over and fall
+//! under gravity, arcing and spinning on the way down, until they hit the
+//! ground below the screen. Without the `physics` feature they drop straight
+//! down at a fixed speed.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    duck_state::Falling, events::DuckLanded, time_scale::GameTimeScale, wind::Wind, Dead, Duck,
+    GameState, PauseState,
+};
+
+// Pixels per second squared, the NES drop is a steady 80 px/s so this is
+// tuned to take about as long to clear the screen
+const GRAVITY: f32 = 400.0;
+// The little hop a duck gives as it starts to drop, on the way it was going
+const KICK: Vec2 = Vec2::new(20.0, 40.0);
+// Radians per second
+const SPIN: f32 = 6.0;
+// How quickly a falling duck picks up the wind speed, per second
+const WIND_DRAG: f32 = 0.5;
+// Just under the screen so they're out of sight when they land
+const GROUND_Y: f32 = -240.0 / 2.0 - 16.0;
+
+#[derive(PhysicsLayer, Default)]
+enum Layer {
+    #[default]
+    Default,
+    Duck,
+    Ground,
+}
+
+#[derive(Component)]
+struct Ground;
+
+pub struct DuckPhysicsPlugin;
+
+impl Plugin for DuckPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(PhysicsPlugins::default())
+            .insert_resource(Gravity(Vec2::NEG_Y * GRAVITY))
+            .add_observer(drop_duck)
+            .add_systems(OnEnter(GameState::InGame), spawn_ground)
+            .add_systems(OnEnter(PauseState::Paused), pause_physics)
+            .add_systems(OnExit(PauseState::Paused), resume_physics)
+            .add_systems(
+                Update,
+                (match_game_speed, carry_ducks, land_ducks).run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+fn spawn_ground(mut commands: Commands) {
+    commands.spawn((
+        Ground,
+        Transform::from_xyz(0.0, GROUND_Y - 16.0, 0.0),
+        RigidBody::Static,
+        Collider::rectangle(512.0, 32.0),
+        Sensor,
+        CollisionEventsEnabled,
+        CollisionLayers::new(Layer::Ground, Layer::Duck),
+        StateScoped(GameState::InGame),
+    ));
+}
+
+// Falling ducks are out of play, so it doesn't matter that physics steps on
+// its own clock instead of with the simulation
+fn drop_duck(
+    trigger: Trigger<OnAdd, Falling>,
+    mut commands: Commands,
+    duck_query: Query<&Sprite, With<Duck>>,
+) {
+    let entity = trigger.target();
+    let Ok(sprite) = duck_query.get(entity) else {
+        return;
+    };
+    // Flipped ducks were heading left
+    let direction = if sprite.flip_x { -1.0 } else { 1.0 };
+    commands.entity(entity).insert((
+        RigidBody::Dynamic,
+        Collider::rectangle(32.0, 32.0),
+        CollisionLayers::new(Layer::Duck, Layer::Ground),
+        LinearVelocity(Vec2::new(KICK.x * direction, KICK.y)),
+        AngularVelocity(-SPIN * direction),
+    ));
+}
+
+fn land_ducks(
+    mut commands: Commands,
+    mut collision_started_reader: EventReader<CollisionStarted>,
+    ground_query: Query<(), With<Ground>>,
+    duck_query: Query<&Transform, (With<Duck>, Without<Dead>)>,
+    mut duck_landed_writer: EventWriter<DuckLanded>,
+) {
+    for CollisionStarted(a, b) in collision_started_reader.read() {
+        let duck = if ground_query.contains(*a) { *b } else { *a };
+        if let Ok(transform) = duck_query.get(duck) {
+            commands.entity(duck).insert(Dead);
+            duck_landed_writer.write(DuckLanded {
+                entity: duck,
+                position: transform.translation.xy(),
+            });
+        }
+    }
+}
+
+fn carry_ducks(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    wind: Res<Wind>,
+    mut body_query: Query<&mut LinearVelocity, With<Duck>>,
+) {
+    let drag = (WIND_DRAG * time_scale.delta_secs(&time)).min(1.0);
+    for mut velocity in &mut body_query {
+        velocity.x += (wind.0 - velocity.x) * drag;
+    }
+}
+
+// Slow-mo and hit-stop slow the fall down too
+fn match_game_speed(time_scale: Res<GameTimeScale>, mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.set_relative_speed(time_scale.scale());
+}
+
+fn pause_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.pause();
+}
+
+fn resume_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.unpause();
+}
@@ -0,0 +1,173 @@
+//! Hit ducks sometimes drop a floating power-up. Shooting it grants a timed
+//! effect, `ActiveEffects` keeps track of those and pushes them into the
+//! resources they change.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::{DuckHit, PowerUpCollected, ShotFired},
+    rng::GameRng,
+    time_scale::GameTimeScale,
+    weapon::WeaponModifiers,
+    GameAssets, GameState,
+};
+
+const DROP_CHANCE: f64 = 0.1;
+// Seconds a power-up floats around before it's gone
+const POWER_UP_LIFETIME: f32 = 6.0;
+const EFFECT_DURATION: f32 = 8.0;
+const SLOW_MO_SPEED: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PowerUpKind {
+    SlowMo,
+    RapidFire,
+    WideShot,
+}
+
+impl PowerUpKind {
+    const ALL: [PowerUpKind; 3] = [
+        PowerUpKind::SlowMo,
+        PowerUpKind::RapidFire,
+        PowerUpKind::WideShot,
+    ];
+
+    // Frame in the power-up spritesheet
+    fn icon(&self) -> usize {
+        match self {
+            PowerUpKind::SlowMo => 0,
+            PowerUpKind::RapidFire => 1,
+            PowerUpKind::WideShot => 2,
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    lifetime: Timer,
+    origin: Vec2,
+}
+
+/// Effects currently running and how long they have left. Timed with the
+/// unscaled clock so slow-mo doesn't stretch itself out.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct ActiveEffects(HashMap<PowerUpKind, Timer>);
+
+impl ActiveEffects {
+    pub fn grant(&mut self, kind: PowerUpKind) {
+        // Picking up the same one again restarts it
+        self.0
+            .insert(kind, Timer::from_seconds(EFFECT_DURATION, TimerMode::Once));
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.0.contains_key(&kind)
+    }
+}
+
+pub fn drop_power_ups(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    game_assets: Res<GameAssets>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+) {
+    for hit in duck_hit_reader.read() {
+        if !rng.gen_bool(DROP_CHANCE) {
+            continue;
+        }
+        let kind = PowerUpKind::ALL[rng.gen_range(0..PowerUpKind::ALL.len())];
+        commands.spawn((
+            Sprite::from_atlas_image(
+                game_assets.power_ups_spritesheet.clone(),
+                TextureAtlas {
+                    layout: game_assets.power_ups_layout.clone(),
+                    index: kind.icon(),
+                },
+            ),
+            // In front of the background so it can't hide behind the grass
+            Transform::from_translation(hit.position.extend(2.0)),
+            PowerUp {
+                kind,
+                lifetime: Timer::from_seconds(POWER_UP_LIFETIME, TimerMode::Once),
+                origin: hit.position,
+            },
+            StateScoped(GameState::InGame),
+        ));
+    }
+}
+
+pub fn float_power_ups(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    mut power_up_query: Query<(Entity, &mut Transform, &mut Sprite, &mut PowerUp)>,
+) {
+    for (entity, mut transform, mut sprite, mut power_up) in &mut power_up_query {
+        power_up.lifetime.tick(time_scale.delta(&time));
+        if power_up.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Drift upwards while bobbing side to side
+        let elapsed = power_up.lifetime.elapsed_secs();
+        transform.translation.x = power_up.origin.x + (elapsed * 3.0).sin() * 6.0;
+        transform.translation.y = power_up.origin.y + elapsed * 8.0;
+        // Blink for the last couple of seconds
+        let visible =
+            power_up.lifetime.remaining_secs() > 2.0 || ((elapsed * 8.0) as u32).is_multiple_of(2);
+        sprite.color.set_alpha(if visible { 1.0 } else { 0.0 });
+    }
+}
+
+pub fn collect_power_ups(
+    mut commands: Commands,
+    mut effects: ResMut<ActiveEffects>,
+    power_up_query: Query<(Entity, &Transform, &PowerUp)>,
+    mut shot_fired_reader: EventReader<ShotFired>,
+    mut power_up_collected_writer: EventWriter<PowerUpCollected>,
+) {
+    for shot in shot_fired_reader.read() {
+        for (entity, transform, power_up) in &power_up_query {
+            let pos = transform.translation.xy();
+            let hitbox = Rect::from_center_half_size(pos, Vec2::splat(8.0));
+            if hitbox.contains(shot.position) {
+                effects.grant(power_up.kind);
+                commands.entity(entity).despawn();
+                power_up_collected_writer.write(PowerUpCollected {
+                    kind: power_up.kind,
+                    position: pos,
+                });
+            }
+        }
+    }
+}
+
+pub fn apply_effects(
+    time: Res<Time>,
+    mut effects: ResMut<ActiveEffects>,
+    mut time_scale: ResMut<GameTimeScale>,
+    mut modifiers: ResMut<WeaponModifiers>,
+) {
+    effects.0.retain(|_, timer| {
+        timer.tick(time.delta());
+        !timer.finished()
+    });
+
+    let speed = if effects.is_active(PowerUpKind::SlowMo) {
+        SLOW_MO_SPEED
+    } else {
+        1.0
+    };
+    if time_scale.effect != speed {
+        time_scale.effect = speed;
+    }
+    let rapid_fire = effects.is_active(PowerUpKind::RapidFire);
+    let wide_shot = effects.is_active(PowerUpKind::WideShot);
+    if modifiers.rapid_fire != rapid_fire || modifiers.wide_shot != wide_shot {
+        modifiers.rapid_fire = rapid_fire;
+        modifiers.wide_shot = wide_shot;
+    }
+}
@@ -0,0 +1,242 @@
+//! Everything about the player that outlives a session, in one versioned file.
+//!
+//! The first line is a RON comment with the format version and a checksum of
+//! the rest, so a damaged file is noticed instead of half loaded. Files from
+//! older versions are read in their own layout and converted, see
+//! `read_body`. Adding a field doesn't need a new version as long as it has a
+//! default, renaming or reshaping one does.
+
+use std::{fs, io, path::Path};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    achievements::UnlockedAchievements,
+    high_score::HighScores,
+    lifetime::LifetimeStats,
+    storage::{write_file, Storage},
+    tutorial::Tutorial,
+};
+
+const PROFILE_FILE: &str = "profile.ron";
+// Where achievements were kept before there was a profile, in the working
+// directory like the first profiles were
+const LEGACY_ACHIEVEMENTS_FILE: &str = "achievements.ron";
+
+pub const PROFILE_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("could not access profile: {0}")]
+    Io(#[from] io::Error),
+    #[error("profile is missing its header")]
+    MissingHeader,
+    #[error("profile checksum does not match, the file is damaged")]
+    Checksum,
+    #[error("profile is version {0}, newer than this game knows about")]
+    TooNew(u32),
+    #[error("could not read profile: {0}")]
+    Parse(#[from] ron::de::SpannedError),
+    #[error("could not write profile: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub high_scores: HighScores,
+    pub achievements: UnlockedAchievements,
+    pub tutorial: Tutorial,
+    pub stats: LifetimeStats,
+}
+
+impl Profile {
+    /// Reads the profile, upgrading it if it was written by an older version.
+    /// When there is none yet, whatever older versions left in the working
+    /// directory is picked up instead.
+    pub fn load(path: &Path) -> Result<Self, ProfileError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self::from_legacy_files(Path::new("")))
+            }
+            Err(error) => return Err(error.into()),
+        };
+        Self::parse(&contents)
+    }
+
+    fn parse(contents: &str) -> Result<Self, ProfileError> {
+        let (header, body) = contents
+            .split_once('\n')
+            .ok_or(ProfileError::MissingHeader)?;
+        let (version, checksum) = parse_header(header).ok_or(ProfileError::MissingHeader)?;
+        if checksum != fnv1a(body.as_bytes()) {
+            return Err(ProfileError::Checksum);
+        }
+        read_body(version, body)
+    }
+
+    /// Falls back to defaults when the profile can't be read. The file is
+    /// moved aside first so saving the new one doesn't lose it for good, it
+    /// may be damaged or just from a newer version of the game.
+    pub fn load_or_default(path: &Path) -> Self {
+        match Self::load(path) {
+            Ok(profile) => profile,
+            Err(error) => {
+                println!("{error}, starting a new profile");
+                let _ = fs::rename(path, path.with_extension("ron.bak"));
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProfileError> {
+        let body = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        let contents = format!(
+            "// duck hunt profile v{PROFILE_VERSION} {:016x}\n{body}",
+            fnv1a(body.as_bytes())
+        );
+        // Written next to it and swapped in, a crash mid write leaves the
+        // old profile intact
+        let temp_path = path.with_extension("ron.tmp");
+        write_file(&temp_path, contents)?;
+        fs::rename(temp_path, path)?;
+        Ok(())
+    }
+
+    /// What older versions left in `dir`, the working directory outside of
+    /// the tests
+    fn from_legacy_files(dir: &Path) -> Self {
+        let old_profile = fs::read_to_string(dir.join(PROFILE_FILE))
+            .ok()
+            .and_then(|contents| Self::parse(&contents).ok());
+        if let Some(profile) = old_profile {
+            return profile;
+        }
+        let achievements = fs::read_to_string(dir.join(LEGACY_ACHIEVEMENTS_FILE))
+            .ok()
+            .and_then(|contents| ron::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            achievements,
+            ..default()
+        }
+    }
+}
+
+/// Reads a profile body written by `version` of the format. When the layout
+/// changes, bump `PROFILE_VERSION`, keep the old layout around as its own
+/// struct and give it an arm here that converts it into the current one.
+fn read_body(version: u32, body: &str) -> Result<Profile, ProfileError> {
+    match version {
+        version if version > PROFILE_VERSION => Err(ProfileError::TooNew(version)),
+        // No layout has changed yet, anything added since an older version
+        // was written is filled in from its default
+        _ => Ok(ron::from_str(body)?),
+    }
+}
+
+fn parse_header(header: &str) -> Option<(u32, u64)> {
+    let rest = header.strip_prefix("// duck hunt profile v")?;
+    let (version, checksum) = rest.trim().split_once(' ')?;
+    Some((
+        version.parse().ok()?,
+        u64::from_str_radix(checksum, 16).ok()?,
+    ))
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        let path = app.world().resource::<Storage>().data_file(PROFILE_FILE);
+        let profile = Profile::load_or_default(&path);
+        app.insert_resource(profile.high_scores)
+            .insert_resource(profile.achievements)
+            .insert_resource(profile.tutorial)
+            .insert_resource(profile.stats)
+            .add_systems(
+                Last,
+                save_profile.run_if(
+                    resource_changed::<HighScores>
+                        .or(resource_changed::<UnlockedAchievements>)
+                        .or(resource_changed::<Tutorial>)
+                        .or(resource_changed::<LifetimeStats>),
+                ),
+            );
+    }
+}
+
+fn save_profile(
+    storage: Res<Storage>,
+    high_scores: Res<HighScores>,
+    achievements: Res<UnlockedAchievements>,
+    tutorial: Res<Tutorial>,
+    stats: Res<LifetimeStats>,
+) {
+    let profile = Profile {
+        high_scores: high_scores.clone(),
+        achievements: achievements.clone(),
+        tutorial: tutorial.clone(),
+        stats: stats.clone(),
+    };
+    if let Err(error) = profile.save(&storage.data_file(PROFILE_FILE)) {
+        println!("{error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_header(version: u32, body: &str) -> String {
+        format!(
+            "// duck hunt profile v{version} {:016x}\n{body}",
+            fnv1a(body.as_bytes())
+        )
+    }
+
+    #[test]
+    fn a_damaged_profile_fails_its_checksum() {
+        let contents = with_header(PROFILE_VERSION, "(tutorial: (seen: true))");
+        assert!(Profile::parse(&contents).unwrap().tutorial.seen);
+        let damaged = contents.replace("true", "fals");
+        assert!(matches!(
+            Profile::parse(&damaged),
+            Err(ProfileError::Checksum)
+        ));
+        assert!(matches!(
+            Profile::parse("(tutorial: (seen: true))"),
+            Err(ProfileError::MissingHeader)
+        ));
+    }
+
+    #[test]
+    fn only_newer_versions_are_too_new() {
+        let newer = with_header(PROFILE_VERSION + 1, "()");
+        assert!(matches!(
+            Profile::parse(&newer),
+            Err(ProfileError::TooNew(version)) if version == PROFILE_VERSION + 1
+        ));
+        let older = with_header(PROFILE_VERSION - 1, "(tutorial: (seen: true))");
+        assert!(Profile::parse(&older).unwrap().tutorial.seen);
+    }
+
+    #[test]
+    fn achievements_from_before_the_profile_are_kept() {
+        let dir = std::env::temp_dir().join(format!("duck-hunt-profile-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(LEGACY_ACHIEVEMENTS_FILE), "([\"first_blood\"])").unwrap();
+        let profile = Profile::from_legacy_files(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(profile.achievements.0.contains("first_blood"));
+    }
+}
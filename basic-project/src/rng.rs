@@ -0,0 +1,52 @@
+//! Every random decision the game makes should go through `GameRng` so a run
+//! can be reproduced from its seed. The duck sequence has its own stream in
+//! `SpawnRng`, that way it stays the same no matter what the player does.
+
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// Seed both generators start from when entering `InGame`. Insert a
+/// specific one beforehand to replay a run.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunSeed(pub u64);
+
+impl Default for RunSeed {
+    fn default() -> Self {
+        Self(rand::random())
+    }
+}
+
+#[derive(Resource, Clone, Deref, DerefMut, Serialize, Deserialize)]
+pub struct GameRng(ChaCha8Rng);
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(ChaCha8Rng::from_entropy())
+    }
+}
+
+#[derive(Resource, Clone, Deref, DerefMut, Serialize, Deserialize)]
+pub struct SpawnRng(ChaCha8Rng);
+
+impl SpawnRng {
+    pub fn from_seed(seed: u64) -> Self {
+        // Separate stream from GameRng even though they share the seed
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        rng.set_stream(1);
+        Self(rng)
+    }
+}
+
+pub fn seed_rngs(mut commands: Commands, seed: Res<RunSeed>) {
+    println!("Run seed {}", seed.0);
+    commands.insert_resource(GameRng::from_seed(seed.0));
+    commands.insert_resource(SpawnRng::from_seed(seed.0));
+}
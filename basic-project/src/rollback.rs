@@ -0,0 +1,300 @@
+//! Versus with GGRS rollback netcode. Both peers run the whole simulation
+//! from the same seed and only trade inputs. When a remote input arrives
+//! late the world is rolled back to that frame and `Simulation` is run
+//! again up to the present, so everything it touches has to be registered
+//! for snapshots below.
+//!
+//! Each side picks half of the match's seed and sends it along with every
+//! input. The random streams are seeded from both halves on the first frame
+//! both have arrived, until then they run from a placeholder both sides
+//! share.
+//!
+//! The peer to play against comes from `DUCK_HUNT_PEER`, the local port
+//! from `DUCK_HUNT_PORT` and which seat this side takes from
+//! `DUCK_HUNT_PLAYER` (0 or 1).
+
+use std::net::SocketAddr;
+
+use bevy::{platform::collections::HashMap, prelude::*, window::PrimaryWindow};
+use bevy_ggrs::{
+    ggrs::{GgrsError, UdpNonBlockingSocket},
+    prelude::*,
+    LocalInputs, LocalPlayers,
+};
+use duck_hunt_shared::protocol::DEFAULT_PORT;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    animation::SpriteAnimation,
+    boss::Boss,
+    duck_state::{Dying, Escaping, Falling, Flying, Launching, Perched},
+    events::PlayerId,
+    flight::FlightPattern,
+    locale::Locale,
+    menu::{spawn_menu_button, MenuScreen},
+    mode::GameMode,
+    power_up::{ActiveEffects, PowerUp},
+    rng::{GameRng, RunSeed, SpawnRng},
+    score::{Score, SessionStats},
+    settings::{MouseAction, Settings},
+    sinden::PlayArea,
+    spatial::SpatialIndex,
+    time_scale::GameTimeScale,
+    versus::{Reticle, VersusScores},
+    weapon::{EquippedWeapon, WeaponModifiers},
+    Dead, Difficulty, Duck, GameInputEvent, GameState, Health, HitFlash, Round, SimulationDriver,
+    SpawnTimer,
+};
+
+const ROLLBACK_FPS: usize = 60;
+const INPUT_DELAY: usize = 2;
+
+/// One player's input for a frame, this is all that goes over the wire
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RollbackInput {
+    /// Crosshair in whole world pixels
+    pub x: i16,
+    pub y: i16,
+    pub trigger: bool,
+    pub reload: bool,
+    /// This side's half of the match seed, 0 until it's been sent
+    pub seed: u32,
+}
+
+pub type RollbackConfig = GgrsConfig<RollbackInput>;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct RollbackButton;
+
+/// Whether each player's trigger was down last frame. Rolled back with the
+/// rest, a `Local` would go out of step when frames are simulated again.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct HeldTriggers([bool; 2]);
+
+/// This side's half of the match seed, picked when the match starts
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct LocalSeed(u32);
+
+/// Whether the random streams have been seeded from both halves yet. Rolled
+/// back too, a prediction without the other half mustn't count.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct MatchSeeded(bool);
+
+pub struct RollbackPlugin;
+
+impl Plugin for RollbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(GgrsPlugin::<RollbackConfig>::default())
+            .set_rollback_schedule_fps(ROLLBACK_FPS)
+            .rollback_component_with_clone::<Transform>()
+            .rollback_component_with_clone::<Sprite>()
+            .rollback_component_with_clone::<Duck>()
+            .rollback_component_with_clone::<Health>()
+            .rollback_component_with_clone::<HitFlash>()
+            .rollback_component_with_clone::<SpriteAnimation>()
+            .rollback_component_with_clone::<Launching>()
+            .rollback_component_with_clone::<Flying>()
+            .rollback_component_with_clone::<Escaping>()
+            .rollback_component_with_clone::<Perched>()
+            .rollback_component_with_clone::<Dying>()
+            .rollback_component_with_clone::<Falling>()
+            .rollback_component_with_clone::<FlightPattern>()
+            .rollback_component_with_clone::<Dead>()
+            .rollback_component_with_clone::<Boss>()
+            .rollback_component_with_clone::<PowerUp>()
+            .rollback_component_with_clone::<EquippedWeapon>()
+            .rollback_resource_with_clone::<Round>()
+            .rollback_resource_with_clone::<Score>()
+            .rollback_resource_with_clone::<SessionStats>()
+            .rollback_resource_with_clone::<VersusScores>()
+            .rollback_resource_with_clone::<SpawnTimer>()
+            .rollback_resource_with_clone::<Difficulty>()
+            .rollback_resource_with_clone::<GameRng>()
+            .rollback_resource_with_clone::<SpawnRng>()
+            .rollback_resource_with_clone::<GameTimeScale>()
+            .rollback_resource_with_clone::<EquippedWeapon>()
+            .rollback_resource_with_clone::<WeaponModifiers>()
+            .rollback_resource_with_clone::<ActiveEffects>()
+            .rollback_resource_with_clone::<HeldTriggers>()
+            .rollback_resource_with_clone::<MatchSeeded>()
+            // Shots test against the index from the step before, see
+            // `PreviousPosition`
+            .rollback_resource_with_clone::<SpatialIndex>()
+            .update_resource_with_map_entities::<SpatialIndex>()
+            .init_resource::<HeldTriggers>()
+            .init_resource::<MatchSeeded>()
+            // Whatever the simulation spawns has to be rolled back with it
+            .add_observer(add_rollback::<Duck>)
+            .add_observer(add_rollback::<PowerUp>)
+            .add_observer(add_rollback::<Reticle>)
+            .add_systems(
+                OnEnter(GameState::Menu),
+                add_rollback_button.after(crate::menu::setup_menu),
+            )
+            .add_systems(
+                Update,
+                handle_rollback_button.run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(
+                ReadInputs,
+                read_local_inputs.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                GgrsSchedule,
+                (apply_rollback_inputs, crate::run_simulation)
+                    .chain()
+                    .run_if(
+                        in_state(GameState::InGame)
+                            .and(resource_equals(SimulationDriver::Rollback)),
+                    ),
+            );
+    }
+}
+
+fn add_rollback<T: Component>(trigger: Trigger<OnAdd, T>, mut commands: Commands) {
+    commands.entity(trigger.target()).add_rollback();
+}
+
+fn add_rollback_button(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    menu_query: Query<Entity, With<MenuScreen>>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).with_children(|parent| {
+            spawn_menu_button(parent, locale.get("rollback.versus"), RollbackButton);
+        });
+    }
+}
+
+fn handle_rollback_button(
+    mut commands: Commands,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<RollbackButton>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let session = match start_session() {
+            Ok(session) => session,
+            Err(error) => {
+                println!("Could not start rollback session: {error}");
+                continue;
+            }
+        };
+        commands.insert_resource(Session::P2P(session));
+        // Never 0, that's what an input without it yet carries
+        commands.insert_resource(LocalSeed(rand::random::<u32>().max(1)));
+        commands.insert_resource(MatchSeeded(false));
+        // The placeholder both sides start from, see `apply_rollback_inputs`
+        commands.insert_resource(RunSeed(0));
+        commands.insert_resource(GameMode::Versus);
+        commands.insert_resource(SimulationDriver::Rollback);
+        next_state.set(GameState::InGame);
+    }
+}
+
+fn start_session() -> Result<bevy_ggrs::ggrs::P2PSession<RollbackConfig>, Box<dyn std::error::Error>>
+{
+    let peer: SocketAddr = std::env::var("DUCK_HUNT_PEER")
+        .map_err(|_| "DUCK_HUNT_PEER is not set")?
+        .parse()?;
+    let port = match std::env::var("DUCK_HUNT_PORT") {
+        Ok(port) => port.parse()?,
+        Err(_) => DEFAULT_PORT,
+    };
+    let local: usize = match std::env::var("DUCK_HUNT_PLAYER") {
+        Ok(player) => player.parse()?,
+        Err(_) => 0,
+    };
+    if local > 1 {
+        return Err("DUCK_HUNT_PLAYER has to be 0 or 1".into());
+    }
+    let socket = UdpNonBlockingSocket::bind_to_port(port)?;
+    let session = SessionBuilder::<RollbackConfig>::new()
+        .with_num_players(2)
+        .with_input_delay(INPUT_DELAY)
+        .add_player(PlayerType::Local, local)?
+        .add_player(PlayerType::Remote(peer), 1 - local)?
+        .start_p2p_session(socket)
+        .map_err(|error: GgrsError| error.to_string())?;
+    Ok(session)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn read_local_inputs(
+    mut commands: Commands,
+    local_players: Res<LocalPlayers>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    seed: Res<LocalSeed>,
+    play_area: Res<PlayArea>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+) {
+    let cursor = window_query
+        .single()
+        .ok()
+        .and_then(|win| win.cursor_position())
+        .map_or(Vec2::ZERO, |position| play_area.to_world(position));
+    // Held rather than just pressed, inputs can get dropped or repeated so
+    // the press is picked out of consecutive frames in the simulation
+    let input = RollbackInput {
+        x: cursor.x.round() as i16,
+        y: cursor.y.round() as i16,
+        trigger: mouse_input.pressed(settings.mouse.button(MouseAction::Shoot)),
+        // Starting a reload again while it's going does nothing, so this
+        // can be held too
+        reload: keyboard_input.pressed(KeyCode::KeyR),
+        seed: seed.0,
+    };
+    let inputs = local_players
+        .0
+        .iter()
+        .map(|handle| (*handle, input))
+        .collect::<HashMap<_, _>>();
+    commands.insert_resource(LocalInputs::<RollbackConfig>(inputs));
+}
+
+// Turns the confirmed or predicted inputs into the same events and reticle
+// moves a local versus game gets
+#[allow(clippy::too_many_arguments)]
+fn apply_rollback_inputs(
+    inputs: Res<PlayerInputs<RollbackConfig>>,
+    modifiers: Res<WeaponModifiers>,
+    equipped: Res<EquippedWeapon>,
+    mut reticle_query: Query<(&Reticle, &mut Transform, Option<&EquippedWeapon>)>,
+    mut held: ResMut<HeldTriggers>,
+    mut seeded: ResMut<MatchSeeded>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_rng: ResMut<SpawnRng>,
+    mut game_input_event_writer: EventWriter<GameInputEvent>,
+) {
+    // The input delay and the trip over the network hold the halves back a
+    // few frames, both sides see them arrive on the same confirmed frame
+    let (first, second) = (inputs[0].0.seed, inputs[1].0.seed);
+    if !seeded.0 && first != 0 && second != 0 {
+        let seed = (u64::from(first) << 32) | u64::from(second);
+        *game_rng = GameRng::from_seed(seed);
+        *spawn_rng = SpawnRng::from_seed(seed);
+        seeded.0 = true;
+    }
+    for (reticle, mut transform, weapon) in &mut reticle_query {
+        let PlayerId(player) = reticle.player;
+        let Some((input, _)) = inputs.get(player) else {
+            continue;
+        };
+        let position = Vec2::new(input.x as f32, input.y as f32);
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        let automatic = weapon.unwrap_or(&equipped).automatic(&modifiers);
+        if input.trigger && (automatic || !held.0[player]) {
+            game_input_event_writer.write(GameInputEvent::Shoot(reticle.player, position, None));
+        }
+        if input.reload {
+            game_input_event_writer.write(GameInputEvent::Reload(reticle.player));
+        }
+        held.0[player] = input.trigger;
+    }
+}
@@ -0,0 +1,47 @@
+//! "ROUND 3" before every round. Ducks only spawn and the trigger only
+//! works in `RoundPhase::Playing`, the intro lasts until its banner and any
+//! left over from the last round have gone.
+
+use bevy::prelude::*;
+
+use crate::{
+    banner::{Banners, BANNER_SECONDS},
+    events::RoundEnded,
+    locale::Locale,
+    Round, RoundPhase, SimulationDriver,
+};
+
+pub fn start_round_intro(
+    locale: Res<Locale>,
+    round: Res<Round>,
+    driver: Res<SimulationDriver>,
+    mut banners: ResMut<Banners>,
+    mut next_round_phase: ResMut<NextState<RoundPhase>>,
+) {
+    // Rollback peers can't agree on when a state changes, they play on
+    // without one
+    if *driver == SimulationDriver::Rollback {
+        next_round_phase.set(RoundPhase::Playing);
+        return;
+    }
+    banners.show(
+        locale.format("round_intro.round", &[("round", &round.number)]),
+        BANNER_SECONDS,
+    );
+}
+
+pub fn end_round_intro(banners: Res<Banners>, mut next_round_phase: ResMut<NextState<RoundPhase>>) {
+    if banners.is_idle() {
+        next_round_phase.set(RoundPhase::Playing);
+    }
+}
+
+pub fn next_round_intro(
+    driver: Res<SimulationDriver>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+    mut next_round_phase: ResMut<NextState<RoundPhase>>,
+) {
+    if round_ended_reader.read().count() > 0 && *driver != SimulationDriver::Rollback {
+        next_round_phase.set(RoundPhase::Intro);
+    }
+}
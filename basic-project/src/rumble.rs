@@ -0,0 +1,106 @@
+//! Rumble for whoever is shooting with the gamepad, player two in versus. A
+//! short buzz on every shot, a harder knock when a duck goes down or the boss
+//! takes damage, and the hardest for taking the boss down. How strong it is,
+//! or whether it's on at all, is in the settings.
+
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+
+use crate::{
+    boss::Boss,
+    events::{DuckDamaged, DuckHit, PlayerId, ShotFired},
+    settings::Settings,
+    versus::versus_gamepad,
+    FrameSet, GameState, SimulationDriver,
+};
+
+// The one aiming with the stick, see `handle_gamepad_reticle`
+const GAMEPAD_PLAYER: PlayerId = PlayerId(1);
+
+#[derive(Debug, Clone, Copy)]
+struct Pulse {
+    strong: f32,
+    weak: f32,
+    seconds: f32,
+}
+
+const SHOT: Pulse = Pulse {
+    strong: 0.0,
+    weak: 0.4,
+    seconds: 0.08,
+};
+const HIT: Pulse = Pulse {
+    strong: 0.6,
+    weak: 0.6,
+    seconds: 0.15,
+};
+const BOSS_KILL: Pulse = Pulse {
+    strong: 1.0,
+    weak: 1.0,
+    seconds: 0.4,
+};
+
+pub struct RumblePlugin;
+
+impl Plugin for RumblePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            rumble_on_shots.in_set(FrameSet::Presentation).run_if(
+                in_state(GameState::InGame)
+                    .and(resource_equals(SimulationDriver::Local))
+                    .and(|settings: Res<Settings>| settings.rumble.enabled),
+            ),
+        );
+    }
+}
+
+fn rumble_on_shots(
+    settings: Res<Settings>,
+    gamepad_query: Query<(Entity, &Gamepad)>,
+    boss_query: Query<(), With<Boss>>,
+    mut shot_fired_reader: EventReader<ShotFired>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+    mut duck_damaged_reader: EventReader<DuckDamaged>,
+    mut rumble_writer: EventWriter<GamepadRumbleRequest>,
+) {
+    let mut pulse = None;
+    if shot_fired_reader
+        .read()
+        .any(|shot| shot.player == GAMEPAD_PLAYER)
+    {
+        pulse = Some(SHOT);
+    }
+    if duck_damaged_reader
+        .read()
+        .any(|damaged| damaged.player == GAMEPAD_PLAYER)
+    {
+        pulse = Some(HIT);
+    }
+    for hit in duck_hit_reader.read() {
+        if hit.player != GAMEPAD_PLAYER {
+            continue;
+        }
+        if boss_query.contains(hit.entity) {
+            pulse = Some(BOSS_KILL);
+            break;
+        }
+        pulse = Some(HIT);
+    }
+    let (Some(pulse), Some((gamepad, _))) = (pulse, versus_gamepad(&gamepad_query)) else {
+        return;
+    };
+    let scale = settings.rumble.intensity.clamp(0.0, 1.0);
+    rumble_writer.write(GamepadRumbleRequest::Add {
+        gamepad,
+        duration: Duration::from_secs_f32(pulse.seconds),
+        intensity: GamepadRumbleIntensity {
+            strong_motor: pulse.strong * scale,
+            weak_motor: pulse.weak * scale,
+        },
+    });
+}
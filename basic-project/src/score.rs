@@ -0,0 +1,44 @@
+//! Points for every duck taken down, and the running tally of the session.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::events::{DuckEscaped, DuckHit, ShotFired};
+
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score(pub u32);
+
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionStats {
+    pub shots_fired: u32,
+    pub ducks_hit: u32,
+    pub ducks_escaped: u32,
+}
+
+impl SessionStats {
+    /// Fraction of shots that took a duck down, 0 before the first shot
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.ducks_hit as f32 / self.shots_fired as f32
+        }
+    }
+}
+
+pub fn award_points(mut score: ResMut<Score>, mut duck_hit_reader: EventReader<DuckHit>) {
+    for hit in duck_hit_reader.read() {
+        score.0 += hit.points;
+    }
+}
+
+pub fn track_session_stats(
+    mut stats: ResMut<SessionStats>,
+    mut shot_fired_reader: EventReader<ShotFired>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+    mut duck_escaped_reader: EventReader<DuckEscaped>,
+) {
+    stats.shots_fired += shot_fired_reader.read().count() as u32;
+    stats.ducks_hit += duck_hit_reader.read().count() as u32;
+    stats.ducks_escaped += duck_escaped_reader.read().count() as u32;
+}
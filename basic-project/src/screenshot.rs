@@ -0,0 +1,106 @@
+//! F12 saves a screenshot at the NES resolution to the player's pictures,
+//! Shift+F12 the window as it's shown, scaled up and with the UI on top.
+//!
+//! The game camera draws straight to the window, so a second camera renders
+//! the same view into a 256x240 image the whole time for the native shots to
+//! be read back from. Photo mode saves its photos from it too.
+
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::screenshot::{save_to_disk, Screenshot},
+    },
+};
+
+use crate::{setup_camera, storage::Storage};
+
+const NATIVE_SIZE: Extent3d = Extent3d {
+    width: 256,
+    height: 240,
+    depth_or_array_layers: 1,
+};
+
+/// The image `NativeCamera` renders into
+#[derive(Resource)]
+pub struct NativeTarget(pub Handle<Image>);
+
+/// Follows the game camera, anything that moves one moves both
+#[derive(Component)]
+pub struct NativeCamera;
+
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_native_camera.after(setup_camera))
+            .add_systems(Update, take_screenshot);
+    }
+}
+
+fn setup_native_camera(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let mut image = Image::new_fill(
+        NATIVE_SIZE,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::bevy_default(),
+        RenderAssetUsages::default(),
+    );
+    // Screenshots copy out of it
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_DST
+        | TextureUsages::COPY_SRC
+        | TextureUsages::RENDER_ATTACHMENT;
+    let handle = images.add(image);
+    commands.spawn((
+        Camera2d,
+        Camera {
+            target: RenderTarget::Image(handle.clone().into()),
+            // Before the game camera, so it doesn't end up as the UI's
+            order: -1,
+            ..default()
+        },
+        NativeCamera,
+    ));
+    commands.insert_resource(NativeTarget(handle));
+}
+
+fn take_screenshot(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    storage: Res<Storage>,
+    target: Res<NativeTarget>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F12) {
+        return;
+    }
+    let screenshot = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        Screenshot::primary_window()
+    } else {
+        Screenshot::image(target.0.clone())
+    };
+    save_screenshot(&mut commands, screenshot, storage.pictures_dir());
+}
+
+/// Saves what `screenshot` captures to a PNG in `dir` named after the time
+/// it was taken
+pub fn save_screenshot(commands: &mut Commands, screenshot: Screenshot, dir: &Path) {
+    if let Err(error) = fs::create_dir_all(dir) {
+        println!("Could not create {}: {error}", dir.display());
+        return;
+    }
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let path = dir.join(format!("duck-hunt-{}.png", since_epoch.as_millis()));
+    println!("Saving screenshot to {}", path.display());
+    commands.spawn(screenshot).observe(save_to_disk(path));
+}
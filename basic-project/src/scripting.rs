@@ -0,0 +1,230 @@
+//! Lua mods, for game variants that don't need a fork. Every `.lua` file in
+//! `assets/mods` is loaded at startup and has these called when it defines
+//! them:
+//!
+//! - `on_round_start(round)` once the round's intro is over
+//! - `on_spawn(species, x, y)` for every duck, species is `"regular"`,
+//!   `"fast"`, `"golden"` or `"boss"`
+//! - `on_duck_hit(points, x, y)` when a shot takes a duck down
+//!
+//! They can call back into the game with:
+//!
+//! - `spawn_duck(species, x)` for an extra duck taking off from the grass
+//! - `add_score(points)`, negative takes points away
+//! - `show_banner(text, seconds)`
+//!
+//! ```lua
+//! function on_round_start(round)
+//!     if round % 5 == 0 then
+//!         show_banner("GOLD RUSH", 1.5)
+//!         spawn_duck("golden", 0)
+//!     end
+//! end
+//! ```
+//!
+//! Scripts only ask, their requests cross over through a channel and are
+//! carried out between frames. Nothing runs in online or rollback games, the
+//! other side wouldn't see it.
+
+use bevy::{asset::LoadedFolder, prelude::*};
+use bevy_mod_scripting::{
+    asset::ScriptAsset,
+    bindings::{
+        function::namespace::{GlobalNamespace, NamespaceBuilder},
+        script_value::ScriptValue,
+    },
+    core::{
+        callback_labels, commands::AttachScript, event::ScriptCallbackEvent,
+        handler::event_handler, script::ScriptAttachment,
+    },
+    lua::LuaScriptingPlugin,
+    BMSPlugin,
+};
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::{
+    banner::Banners,
+    duck_state::Heading,
+    events::{DuckHit, DuckSpawned},
+    score::Score,
+    spawn::{Behaviour, SpawnDuckEvent},
+    species::Species,
+    Round, RoundPhase, SimulationDriver, GRASS_Y,
+};
+
+const MODS_FOLDER: &str = "mods";
+
+callback_labels!(
+    OnRoundStart => "on_round_start",
+    OnSpawn => "on_spawn",
+    OnDuckHit => "on_duck_hit",
+);
+
+/// Something a script asked the game to do
+#[derive(Debug, Clone, PartialEq)]
+enum ScriptRequest {
+    SpawnDuck { species: String, x: f32 },
+    AddScore(i64),
+    ShowBanner { text: String, seconds: f32 },
+}
+
+#[derive(Resource)]
+struct ScriptRequests(Receiver<ScriptRequest>);
+
+/// Kept so the scripts stay loaded
+#[derive(Resource)]
+struct Mods(Handle<LoadedFolder>);
+
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        app.add_plugins(BMSPlugin)
+            .insert_resource(ScriptRequests(receiver))
+            .add_systems(Startup, load_mods)
+            .add_systems(Update, start_mods)
+            .add_systems(
+                OnEnter(RoundPhase::Playing),
+                send_round_start.run_if(resource_equals(SimulationDriver::Local)),
+            )
+            .add_systems(
+                Update,
+                (
+                    (send_spawns, send_duck_hits),
+                    event_handler::<OnRoundStart, LuaScriptingPlugin>,
+                    event_handler::<OnSpawn, LuaScriptingPlugin>,
+                    event_handler::<OnDuckHit, LuaScriptingPlugin>,
+                    apply_script_requests,
+                )
+                    .chain()
+                    .run_if(resource_equals(SimulationDriver::Local)),
+            );
+        register_functions(app.world_mut(), sender);
+    }
+}
+
+fn register_functions(world: &mut World, sender: Sender<ScriptRequest>) {
+    let spawn_sender = sender.clone();
+    let score_sender = sender.clone();
+    NamespaceBuilder::<GlobalNamespace>::new_unregistered(world)
+        .register("spawn_duck", move |species: String, x: f32| {
+            let _ = spawn_sender.send(ScriptRequest::SpawnDuck { species, x });
+        })
+        .register("add_score", move |points: i64| {
+            let _ = score_sender.send(ScriptRequest::AddScore(points));
+        })
+        .register("show_banner", move |text: String, seconds: f32| {
+            let _ = sender.send(ScriptRequest::ShowBanner { text, seconds });
+        });
+}
+
+fn load_mods(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Mods(asset_server.load_folder(MODS_FOLDER)));
+}
+
+fn start_mods(
+    mut commands: Commands,
+    mods: Res<Mods>,
+    folders: Res<Assets<LoadedFolder>>,
+    mut folder_events: EventReader<AssetEvent<LoadedFolder>>,
+) {
+    for event in folder_events.read() {
+        if !event.is_loaded_with_dependencies(&mods.0) {
+            continue;
+        }
+        let Some(folder) = folders.get(&mods.0) else {
+            continue;
+        };
+        for handle in &folder.handles {
+            let Some(path) = handle.path() else {
+                continue;
+            };
+            if path.path().extension().is_none_or(|ext| ext != "lua") {
+                continue;
+            }
+            let Ok(script) = handle.clone().try_typed::<ScriptAsset>() else {
+                continue;
+            };
+            println!("Loading mod {path}");
+            commands.queue(AttachScript::<LuaScriptingPlugin>::new(
+                ScriptAttachment::StaticScript(script),
+            ));
+        }
+    }
+}
+
+fn send_round_start(round: Res<Round>, mut callback_writer: EventWriter<ScriptCallbackEvent>) {
+    callback_writer.write(ScriptCallbackEvent::new_for_all_scripts(
+        OnRoundStart,
+        vec![ScriptValue::Integer(round.number.into())],
+    ));
+}
+
+fn send_spawns(
+    species_query: Query<&Species>,
+    mut duck_spawned_reader: EventReader<DuckSpawned>,
+    mut callback_writer: EventWriter<ScriptCallbackEvent>,
+) {
+    for spawned in duck_spawned_reader.read() {
+        let Ok(species) = species_query.get(spawned.entity) else {
+            continue;
+        };
+        callback_writer.write(ScriptCallbackEvent::new_for_all_scripts(
+            OnSpawn,
+            vec![
+                ScriptValue::String(species.name().into()),
+                ScriptValue::Float(spawned.position.x.into()),
+                ScriptValue::Float(spawned.position.y.into()),
+            ],
+        ));
+    }
+}
+
+fn send_duck_hits(
+    mut duck_hit_reader: EventReader<DuckHit>,
+    mut callback_writer: EventWriter<ScriptCallbackEvent>,
+) {
+    for hit in duck_hit_reader.read() {
+        callback_writer.write(ScriptCallbackEvent::new_for_all_scripts(
+            OnDuckHit,
+            vec![
+                ScriptValue::Integer(hit.points.into()),
+                ScriptValue::Float(hit.position.x.into()),
+                ScriptValue::Float(hit.position.y.into()),
+            ],
+        ));
+    }
+}
+
+fn apply_script_requests(
+    requests: Res<ScriptRequests>,
+    mut score: ResMut<Score>,
+    mut banners: ResMut<Banners>,
+    mut spawn_writer: EventWriter<SpawnDuckEvent>,
+) {
+    for request in requests.0.try_iter() {
+        match request {
+            ScriptRequest::SpawnDuck { species, x } => {
+                let Ok(species) = species.parse::<Species>() else {
+                    println!("Mod asked for an unknown duck: {species}");
+                    continue;
+                };
+                spawn_writer.write(SpawnDuckEvent {
+                    species,
+                    position: Vec2::new(x, GRASS_Y),
+                    behaviour: Behaviour::TakeOff(Heading::towards_center(x)),
+                });
+            }
+            ScriptRequest::AddScore(points) => {
+                let amount = u32::try_from(points.unsigned_abs()).unwrap_or(u32::MAX);
+                score.0 = if points < 0 {
+                    score.0.saturating_sub(amount)
+                } else {
+                    score.0.saturating_add(amount)
+                };
+            }
+            ScriptRequest::ShowBanner { text, seconds } => banners.show(text, seconds),
+        }
+    }
+}
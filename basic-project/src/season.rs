@@ -0,0 +1,112 @@
+//! Seasonal themes, snow and Santa hats in December and pumpkins in
+//! October. Each is an asset pack in `seasons/` laid over the game's own
+//! files before any from `mods/`, so a mod can still replace what it
+//! changes. The field's sky comes from the season's level file, the hats
+//! from its classic duck sheet.
+//!
+//! `cosmetic.season` picks one by hand or turns them off, by default it
+//! goes by the date. The asset source is set up once, so a change shows
+//! the next time the game starts.
+
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+pub const SEASONS_FOLDER: &str = "seasons";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    /// Whichever goes with today's date, if any
+    #[default]
+    Auto,
+    Off,
+    Winter,
+    Halloween,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [Season::Auto, Season::Off, Season::Winter, Season::Halloween];
+
+    /// The one after this in `ALL`, wrapping round
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|season| *season == self);
+        Self::ALL[i.map_or(0, |i| (i + 1) % Self::ALL.len())]
+    }
+
+    /// What `Auto` is in `month`, 1 for January
+    fn for_month(month: u32) -> Self {
+        match month {
+            10 => Season::Halloween,
+            12 => Season::Winter,
+            _ => Season::Off,
+        }
+    }
+
+    /// `Auto` worked out for today
+    pub fn resolve(self) -> Self {
+        match self {
+            Season::Auto => Self::for_month(current_month()),
+            season => season,
+        }
+    }
+
+    /// Its pack in `seasons/`
+    pub fn folder(self) -> Option<&'static str> {
+        match self.resolve() {
+            Season::Winter => Some("winter"),
+            Season::Halloween => Some("halloween"),
+            Season::Auto | Season::Off => None,
+        }
+    }
+}
+
+impl FromStr for Season {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Season::Auto),
+            "off" => Ok(Season::Off),
+            "winter" => Ok(Season::Winter),
+            "halloween" => Ok(Season::Halloween),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Season::Auto => "auto",
+            Season::Off => "off",
+            Season::Winter => "winter",
+            Season::Halloween => "halloween",
+        })
+    }
+}
+
+// In UTC, a few hours either side of midnight on the 1st doesn't matter
+fn current_month() -> u32 {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() / 86_400) as i64;
+    month_of(days)
+}
+
+/// Month of the day `days` after 1970-01-01, from Howard Hinnant's
+/// `civil_from_days`
+fn month_of(days: i64) -> u32 {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    (if month < 10 { month + 3 } else { month - 9 }) as u32
+}
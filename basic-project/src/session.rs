@@ -0,0 +1,42 @@
+//! Ways out of a game other than finishing it: playing the same mode again
+//! or going back to the menu, from the pause menu or the game over screen.
+//! Both trigger `ResetGame` first so every plugin can put its resources back.
+
+use bevy::prelude::*;
+
+use crate::{events::ResetGame, menu::start_game, mode::GameMode, GameState, LaunchOptions};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionButton {
+    Restart,
+    Menu,
+}
+
+pub fn handle_session_buttons(
+    mut commands: Commands,
+    launch: Res<LaunchOptions>,
+    mode: Res<GameMode>,
+    button_query: Query<(&Interaction, &SessionButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        commands.trigger(ResetGame);
+        match button {
+            SessionButton::Restart => {
+                // Same mode, new seed unless one was asked for
+                start_game(&mut commands, *mode, &launch);
+                next_state.set(GameState::Restarting);
+            }
+            SessionButton::Menu => next_state.set(GameState::Menu),
+        }
+    }
+}
+
+// InGame can't be entered from itself, a restart from the pause menu goes
+// through here so the world is torn down and set up again
+pub fn finish_restart(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::InGame);
+}
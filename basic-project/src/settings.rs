@@ -0,0 +1,582 @@
+//! Player settings, kept in `settings.ron` in the config directory and saved
+//! whenever they change. Every field has a default, so files from older
+//! versions load fine and a missing file just means the defaults.
+//!
+//! The console's `setting` command reads and changes them by name, e.g.
+//! `setting aim.sensitivity 1.5`, or lists a group of them with `setting aim`.
+
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    alpha_mask::HitDetection,
+    console::ConsoleAppExt,
+    display::DisplayMode,
+    locale::Language,
+    season::Season,
+    skin::Skin,
+    storage::{write_file, Storage},
+};
+
+pub const SETTINGS_FILE: &str = "settings.ron";
+pub const MIN_WINDOW_SCALE: u32 = 1;
+pub const MAX_WINDOW_SCALE: u32 = 6;
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("could not access settings: {0}")]
+    Io(#[from] io::Error),
+    #[error("could not read settings: {0}")]
+    Parse(#[from] ron::de::SpannedError),
+    #[error("could not write settings: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("there is no setting called {0}")]
+    Unknown(String),
+    #[error("{value} is not a valid value for {name}")]
+    InvalidValue { name: String, value: String },
+}
+
+/// Reticle movement for sticks and keys
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AimSettings {
+    /// Multiplier on the reticle speed at full deflection
+    pub sensitivity: f32,
+    /// Deflection is raised to this power, above 1 slows the reticle down
+    /// near the center for finer aim without losing top speed
+    pub curve: f32,
+    /// How much faster it goes after a second held all the way over, 0 is
+    /// a flat speed
+    pub acceleration: f32,
+    /// Seconds the reticle takes to catch up with the input, 0 is instant
+    pub smoothing: f32,
+}
+
+impl Default for AimSettings {
+    fn default() -> Self {
+        Self {
+            sensitivity: 1.0,
+            curve: 1.0,
+            acceleration: 0.0,
+            smoothing: 0.0,
+        }
+    }
+}
+
+/// A mouse button as it's written in the settings, Bevy's own type can't be
+/// saved without its serialize feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MouseBinding {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
+}
+
+impl From<MouseBinding> for MouseButton {
+    fn from(binding: MouseBinding) -> Self {
+        match binding {
+            MouseBinding::Left => MouseButton::Left,
+            MouseBinding::Right => MouseButton::Right,
+            MouseBinding::Middle => MouseButton::Middle,
+            MouseBinding::Back => MouseButton::Back,
+            MouseBinding::Forward => MouseButton::Forward,
+        }
+    }
+}
+
+impl FromStr for MouseBinding {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(MouseBinding::Left),
+            "right" => Ok(MouseBinding::Right),
+            "middle" => Ok(MouseBinding::Middle),
+            "back" => Ok(MouseBinding::Back),
+            "forward" => Ok(MouseBinding::Forward),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for MouseBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MouseBinding::Left => "left",
+            MouseBinding::Right => "right",
+            MouseBinding::Middle => "middle",
+            MouseBinding::Back => "back",
+            MouseBinding::Forward => "forward",
+        })
+    }
+}
+
+/// What a mouse button can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    Shoot,
+    Call,
+    Decoy,
+}
+
+impl MouseAction {
+    const ALL: [MouseAction; 3] = [MouseAction::Shoot, MouseAction::Call, MouseAction::Decoy];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MouseSettings {
+    /// Moves the cursor from the mouse's own motion instead of where the OS
+    /// puts the pointer, which skips pointer acceleration
+    pub raw_input: bool,
+    /// Multiplier on the mouse motion, only used with `raw_input`
+    pub sensitivity: f32,
+    pub shoot: MouseBinding,
+    pub call: MouseBinding,
+    pub decoy: MouseBinding,
+}
+
+impl Default for MouseSettings {
+    fn default() -> Self {
+        Self {
+            raw_input: false,
+            sensitivity: 1.0,
+            shoot: MouseBinding::Left,
+            call: MouseBinding::Right,
+            decoy: MouseBinding::Middle,
+        }
+    }
+}
+
+impl MouseSettings {
+    fn binding_mut(&mut self, action: MouseAction) -> &mut MouseBinding {
+        match action {
+            MouseAction::Shoot => &mut self.shoot,
+            MouseAction::Call => &mut self.call,
+            MouseAction::Decoy => &mut self.decoy,
+        }
+    }
+
+    /// The button `action` is on
+    pub fn button(&self, action: MouseAction) -> MouseButton {
+        match action {
+            MouseAction::Shoot => self.shoot,
+            MouseAction::Call => self.call,
+            MouseAction::Decoy => self.decoy,
+        }
+        .into()
+    }
+
+    /// Puts `action` on `binding`. Whatever was on that button before takes
+    /// over the action's old one, so no two actions end up on the same button.
+    pub fn bind(&mut self, action: MouseAction, binding: MouseBinding) {
+        let old = *self.binding_mut(action);
+        for other in MouseAction::ALL {
+            if *self.binding_mut(other) == binding {
+                *self.binding_mut(other) = old;
+            }
+        }
+        *self.binding_mut(action) = binding;
+    }
+}
+
+/// Options on the menu's accessibility page
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Pulls shots onto the closest duck near them
+    pub aim_assist: bool,
+    /// How far from a duck, in pixels, a shot still gets pulled onto it
+    pub assist_radius: f32,
+    /// Recolors the ducks and outlines them so they stand out from the sky
+    pub high_visibility: bool,
+    /// Rain and fog in the later rounds, fog hides the top of the screen
+    pub weather: bool,
+    /// Whether a shot has to land on the duck's art or just its hitbox
+    pub hit_detection: HitDetection,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            aim_assist: false,
+            assist_radius: 16.0,
+            high_visibility: false,
+            weather: true,
+            hit_detection: HitDetection::Hitbox,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    /// Pauses a local game when the window loses focus, and counts back in
+    /// once it has it again
+    pub auto_pause: bool,
+    /// Keeps the cursor inside the window while playing, so a wild shot
+    /// can't click onto another monitor
+    pub confine_cursor: bool,
+    /// Draws the white frame Sinden light guns track the screen by, with
+    /// the game shrunk to fit inside it
+    pub sinden_border: bool,
+    /// Windowed or one of the fullscreens, see `display`
+    pub display: DisplayMode,
+    /// Which monitor to go fullscreen on, from 0 in the order the OS lists
+    /// them
+    pub monitor: usize,
+    /// Windowed size as a multiple of the NES resolution, from 1 to 6
+    pub scale: u32,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            auto_pause: true,
+            confine_cursor: false,
+            sinden_border: false,
+            display: DisplayMode::Windowed,
+            monitor: 0,
+            scale: 2,
+        }
+    }
+}
+
+/// Fine aim from the controller's gyro, only in builds with the gyro feature
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GyroSettings {
+    pub enabled: bool,
+    /// Multiplier on how far the reticle moves for a turn
+    pub sensitivity: f32,
+}
+
+impl Default for GyroSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sensitivity: 1.0,
+        }
+    }
+}
+
+/// Gamepad rumble for the player aiming with one
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RumbleSettings {
+    pub enabled: bool,
+    /// Multiplier on every pulse, from 0 to 1
+    pub intensity: f32,
+}
+
+impl Default for RumbleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            intensity: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    pub language: Language,
+    /// Size of the menus and overlays, the play field doesn't change
+    pub scale: f32,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            scale: 1.0,
+        }
+    }
+}
+
+/// Only what things look like
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CosmeticSettings {
+    /// Used once its achievement is earned, until then the ducks are classic
+    pub skin: Skin,
+    /// Only read when the game starts
+    pub season: Season,
+}
+
+/// Off until the player turns it on, and only sent anywhere in builds with
+/// the telemetry feature
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+}
+
+/// Shows a run timer with splits, read when a game starts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeedrunSettings {
+    pub enabled: bool,
+}
+
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub aim: AimSettings,
+    pub mouse: MouseSettings,
+    pub accessibility: AccessibilitySettings,
+    pub window: WindowSettings,
+    pub gyro: GyroSettings,
+    pub rumble: RumbleSettings,
+    pub ui: UiSettings,
+    pub cosmetic: CosmeticSettings,
+    pub telemetry: TelemetrySettings,
+    pub speedrun: SpeedrunSettings,
+}
+
+impl Settings {
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                let mut settings: Self = ron::from_str(&contents)?;
+                // Hand edits skip the console's range check, and a 0 is a window with no size
+                settings.window.scale = settings
+                    .window
+                    .scale
+                    .clamp(MIN_WINDOW_SCALE, MAX_WINDOW_SCALE);
+                Ok(settings)
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Settings are easy to set again, a broken file isn't kept around like
+    /// the profile is
+    pub fn load_or_default(path: &Path) -> Self {
+        Self::load(path).unwrap_or_else(|error| {
+            println!("{error}, using the default settings");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        write_file(path, contents)?;
+        Ok(())
+    }
+
+    /// Names the console knows, in the order `setting` lists them
+    pub const NAMES: &[&str] = &[
+        "aim.sensitivity",
+        "aim.curve",
+        "aim.acceleration",
+        "aim.smoothing",
+        "mouse.raw_input",
+        "mouse.sensitivity",
+        "mouse.shoot",
+        "mouse.call",
+        "mouse.decoy",
+        "accessibility.aim_assist",
+        "accessibility.assist_radius",
+        "accessibility.high_visibility",
+        "accessibility.weather",
+        "accessibility.hit_detection",
+        "window.auto_pause",
+        "window.confine_cursor",
+        "window.sinden_border",
+        "window.display",
+        "window.monitor",
+        "window.scale",
+        "gyro.enabled",
+        "gyro.sensitivity",
+        "rumble.enabled",
+        "rumble.intensity",
+        "ui.language",
+        "ui.scale",
+        "cosmetic.skin",
+        "cosmetic.season",
+        "telemetry.enabled",
+        "speedrun.enabled",
+    ];
+
+    pub fn get(&self, name: &str) -> Result<String, SettingsError> {
+        Ok(match name {
+            "aim.sensitivity" => self.aim.sensitivity.to_string(),
+            "aim.curve" => self.aim.curve.to_string(),
+            "aim.acceleration" => self.aim.acceleration.to_string(),
+            "aim.smoothing" => self.aim.smoothing.to_string(),
+            "mouse.raw_input" => self.mouse.raw_input.to_string(),
+            "mouse.sensitivity" => self.mouse.sensitivity.to_string(),
+            "mouse.shoot" => self.mouse.shoot.to_string(),
+            "mouse.call" => self.mouse.call.to_string(),
+            "mouse.decoy" => self.mouse.decoy.to_string(),
+            "accessibility.aim_assist" => self.accessibility.aim_assist.to_string(),
+            "accessibility.assist_radius" => self.accessibility.assist_radius.to_string(),
+            "accessibility.high_visibility" => self.accessibility.high_visibility.to_string(),
+            "accessibility.weather" => self.accessibility.weather.to_string(),
+            "accessibility.hit_detection" => self.accessibility.hit_detection.to_string(),
+            "window.auto_pause" => self.window.auto_pause.to_string(),
+            "window.confine_cursor" => self.window.confine_cursor.to_string(),
+            "window.sinden_border" => self.window.sinden_border.to_string(),
+            "window.display" => self.window.display.to_string(),
+            "window.monitor" => self.window.monitor.to_string(),
+            "window.scale" => self.window.scale.to_string(),
+            "gyro.enabled" => self.gyro.enabled.to_string(),
+            "gyro.sensitivity" => self.gyro.sensitivity.to_string(),
+            "rumble.enabled" => self.rumble.enabled.to_string(),
+            "rumble.intensity" => self.rumble.intensity.to_string(),
+            "ui.language" => self.ui.language.to_string(),
+            "ui.scale" => self.ui.scale.to_string(),
+            "cosmetic.skin" => self.cosmetic.skin.to_string(),
+            "cosmetic.season" => self.cosmetic.season.to_string(),
+            "telemetry.enabled" => self.telemetry.enabled.to_string(),
+            "speedrun.enabled" => self.speedrun.enabled.to_string(),
+            _ => return Err(SettingsError::Unknown(name.to_string())),
+        })
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), SettingsError> {
+        let invalid = || SettingsError::InvalidValue {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        // None of the numbers make sense negative
+        let number = || {
+            value
+                .parse::<f32>()
+                .ok()
+                .filter(|number| *number >= 0.0)
+                .ok_or_else(invalid)
+        };
+        match name {
+            "aim.sensitivity" => self.aim.sensitivity = number()?,
+            "aim.curve" => self.aim.curve = number()?,
+            "aim.acceleration" => self.aim.acceleration = number()?,
+            "aim.smoothing" => self.aim.smoothing = number()?,
+            "mouse.raw_input" => self.mouse.raw_input = value.parse().map_err(|_| invalid())?,
+            "mouse.sensitivity" => self.mouse.sensitivity = number()?,
+            "mouse.shoot" | "mouse.call" | "mouse.decoy" => {
+                let action = match name {
+                    "mouse.shoot" => MouseAction::Shoot,
+                    "mouse.call" => MouseAction::Call,
+                    _ => MouseAction::Decoy,
+                };
+                self.mouse
+                    .bind(action, value.parse().map_err(|_| invalid())?);
+            }
+            "accessibility.aim_assist" => {
+                self.accessibility.aim_assist = value.parse().map_err(|_| invalid())?
+            }
+            "accessibility.assist_radius" => self.accessibility.assist_radius = number()?,
+            "accessibility.high_visibility" => {
+                self.accessibility.high_visibility = value.parse().map_err(|_| invalid())?
+            }
+            "accessibility.weather" => {
+                self.accessibility.weather = value.parse().map_err(|_| invalid())?
+            }
+            "accessibility.hit_detection" => {
+                self.accessibility.hit_detection = value.parse().map_err(|_| invalid())?
+            }
+            "window.auto_pause" => self.window.auto_pause = value.parse().map_err(|_| invalid())?,
+            "window.confine_cursor" => {
+                self.window.confine_cursor = value.parse().map_err(|_| invalid())?
+            }
+            "window.sinden_border" => {
+                self.window.sinden_border = value.parse().map_err(|_| invalid())?
+            }
+            "window.display" => self.window.display = value.parse().map_err(|_| invalid())?,
+            "window.monitor" => self.window.monitor = value.parse().map_err(|_| invalid())?,
+            "window.scale" => {
+                self.window.scale = value
+                    .parse()
+                    .ok()
+                    .filter(|scale| (MIN_WINDOW_SCALE..=MAX_WINDOW_SCALE).contains(scale))
+                    .ok_or_else(invalid)?
+            }
+            "gyro.enabled" => self.gyro.enabled = value.parse().map_err(|_| invalid())?,
+            "gyro.sensitivity" => self.gyro.sensitivity = number()?,
+            "rumble.enabled" => self.rumble.enabled = value.parse().map_err(|_| invalid())?,
+            "rumble.intensity" => self.rumble.intensity = number()?.min(1.0),
+            "ui.language" => self.ui.language = value.parse().map_err(|_| invalid())?,
+            // Nothing would fit on the screen past these
+            "ui.scale" => self.ui.scale = number()?.clamp(0.5, 2.0),
+            "cosmetic.skin" => self.cosmetic.skin = value.parse().map_err(|_| invalid())?,
+            "cosmetic.season" => self.cosmetic.season = value.parse().map_err(|_| invalid())?,
+            "telemetry.enabled" => self.telemetry.enabled = value.parse().map_err(|_| invalid())?,
+            "speedrun.enabled" => self.speedrun.enabled = value.parse().map_err(|_| invalid())?,
+            _ => return Err(SettingsError::Unknown(name.to_string())),
+        }
+        Ok(())
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let path = app.world().resource::<Storage>().config_file(SETTINGS_FILE);
+        app.insert_resource(Settings::load_or_default(&path))
+            .add_console_command("setting", "setting [group|name] [value]", setting)
+            .add_systems(
+                Last,
+                // Nothing to save until something is changed
+                save_settings
+                    .run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+            );
+    }
+}
+
+fn save_settings(storage: Res<Storage>, settings: Res<Settings>) {
+    if let Err(error) = settings.save(&storage.config_file(SETTINGS_FILE)) {
+        println!("{error}");
+    }
+}
+
+fn setting(world: &mut World, args: &[&str]) -> Result<String, String> {
+    // Only changing one goes through `resource_mut`, looking shouldn't save
+    let settings = world.resource::<Settings>();
+    // All of them don't fit in the console at once, they're listed a group
+    // at a time
+    let list = |prefix: &str| {
+        Settings::NAMES
+            .iter()
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| format!("{name} {}", settings.get(name).unwrap_or_default()))
+            .collect::<Vec<_>>()
+    };
+    match *args {
+        [] => {
+            let mut groups: Vec<_> = Settings::NAMES
+                .iter()
+                .filter_map(|name| name.split_once('.').map(|(group, _)| group))
+                .collect();
+            groups.dedup();
+            Ok(format!("Groups: {}", groups.join(" ")))
+        }
+        [name] => match settings.get(name) {
+            Ok(value) => Ok(format!("{name} {value}")),
+            Err(error) => {
+                let lines = list(&format!("{name}."));
+                if lines.is_empty() {
+                    Err(error.to_string())
+                } else {
+                    Ok(lines.join("\n"))
+                }
+            }
+        },
+        [name, value] => world
+            .resource_mut::<Settings>()
+            .set(name, value)
+            .map(|()| format!("{name} {value}"))
+            .map_err(|error| error.to_string()),
+        _ => Err("Usage: setting [group|name] [value]".to_string()),
+    }
+}
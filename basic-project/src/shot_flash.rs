@@ -0,0 +1,106 @@
+//! Shot ducks go solid white for a few frames before the splat, so it reads
+//! which one got hit even with a few of them flying close together. Sprites
+//! are tinted by multiplying, which can't brighten them, so the flash
+//! switches to a white look of the duck sheet.
+
+use bevy::prelude::*;
+
+use crate::{
+    alpha_mask::duck_image_to_build,
+    duck_look::{DuckLook, DuckLooks},
+    duck_state::Dying,
+    Duck, GameAssets, PauseState,
+};
+
+// Four frames at 60 fps
+const FLASH_SECONDS: f32 = 4.0 / 60.0;
+
+#[derive(Component)]
+struct ShotFlash {
+    timer: Timer,
+    // What the sprite goes back to afterwards
+    look: DuckLook,
+}
+
+pub struct ShotFlashPlugin;
+
+impl Plugin for ShotFlashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(flash_shot_duck)
+            .add_systems(Update, build_duck_flash_sheet)
+            .add_systems(Update, end_shot_flash.run_if(in_state(PauseState::Running)));
+    }
+}
+
+/// The duck spritesheet with every opaque pixel turned white, built on the
+/// CPU once the sheet has loaded
+fn build_duck_flash_sheet(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut built_from: Local<Option<AssetId<Image>>>,
+    mut images: ResMut<Assets<Image>>,
+    mut looks: ResMut<DuckLooks>,
+    game_assets: Option<Res<GameAssets>>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    let Some(image) =
+        duck_image_to_build(&game_assets, &images, &mut built_from, &mut image_events)
+    else {
+        return;
+    };
+    let mut white = image.clone();
+    let size = image.size();
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let alpha = image.get_color_at(x, y).map_or(0.0, |c| c.alpha());
+            let _ = white.set_color_at(x, y, Color::WHITE.with_alpha(alpha));
+        }
+    }
+    looks.set(DuckLook::Flash, images.add(white));
+}
+
+fn flash_shot_duck(
+    trigger: Trigger<OnAdd, Dying>,
+    mut commands: Commands,
+    game_assets: Option<Res<GameAssets>>,
+    looks: Res<DuckLooks>,
+    mut duck_query: Query<&mut Sprite, With<Duck>>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    if looks.get(DuckLook::Flash).is_none() {
+        return;
+    }
+    let entity = trigger.target();
+    let Ok(mut sprite) = duck_query.get_mut(entity) else {
+        return;
+    };
+    let look = game_assets.look_of(&looks, &sprite).unwrap_or_default();
+    game_assets.show_look(&looks, &mut sprite, DuckLook::Flash);
+    commands.entity(entity).insert(ShotFlash {
+        timer: Timer::from_seconds(FLASH_SECONDS, TimerMode::Once),
+        look,
+    });
+}
+
+// On the real clock, a hit-stop shouldn't stretch the flash out
+fn end_shot_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_assets: Option<Res<GameAssets>>,
+    looks: Res<DuckLooks>,
+    mut flash_query: Query<(Entity, &mut Sprite, &mut ShotFlash)>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    for (entity, mut sprite, mut flash) in &mut flash_query {
+        if !flash.timer.tick(time.delta()).finished() {
+            continue;
+        }
+        game_assets.show_look(&looks, &mut sprite, flash.look);
+        commands.entity(entity).remove::<ShotFlash>();
+    }
+}
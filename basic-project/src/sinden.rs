@@ -0,0 +1,123 @@
+//! Border mode for Sinden style light guns, which find the screen with a
+//! camera by looking for a white frame round it. With `window.sinden_border`
+//! on, a thick white border is drawn over the edge of the window and the game
+//! camera zooms out so the whole play area fits inside it.
+//!
+//! Anything turning window pixels into world positions goes through
+//! [`PlayArea::to_world`] so shots still land where the cursor is. The
+//! native resolution camera, and so screenshots and photos, never has the
+//! border.
+
+use bevy::prelude::*;
+
+use crate::{screenshot::NativeCamera, settings::Settings};
+
+// Window pixels, thick enough for the gun's camera to pick out from across
+// the room
+const BORDER: f32 = 8.0;
+const SCREEN_SIZE: Vec2 = Vec2::new(256.0, 240.0);
+
+/// How far the game camera is zoomed out to fit inside the border, 1 with it
+/// off, and how far it's panned
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct PlayArea {
+    pub scale: f32,
+    /// Kept up with `view::View` by `view`
+    pub view_x: f32,
+}
+
+impl Default for PlayArea {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            view_x: 0.0,
+        }
+    }
+}
+
+impl PlayArea {
+    fn new(border: bool) -> Self {
+        if !border {
+            return Self::default();
+        }
+        // Whichever side is tighter, the other gets a sliver of black
+        let inside = SCREEN_SIZE - Vec2::splat(BORDER * 2.0);
+        Self {
+            scale: (SCREEN_SIZE / inside).max_element(),
+            ..default()
+        }
+    }
+
+    /// World position under window pixels from the top left
+    pub fn to_world(&self, window: Vec2) -> Vec2 {
+        Vec2::new(window.x - 256.0 / 2.0, 240.0 / 2.0 - window.y) * self.scale
+            + Vec2::X * self.view_x
+    }
+}
+
+#[derive(Component)]
+struct SindenBorder;
+
+pub struct SindenPlugin;
+
+impl Plugin for SindenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayArea>()
+            .add_systems(Startup, setup_border)
+            .add_systems(PreUpdate, apply_border.run_if(resource_changed::<Settings>));
+    }
+}
+
+fn setup_border(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            border: UiRect::all(Val::Px(BORDER)),
+            ..default()
+        },
+        BorderColor(Color::WHITE),
+        // Over menus and overlays, the gun has to see it on every screen
+        GlobalZIndex(40),
+        Visibility::Hidden,
+        SindenBorder,
+    ));
+}
+
+fn apply_border(
+    settings: Res<Settings>,
+    mut play_area: ResMut<PlayArea>,
+    mut border_query: Query<(&mut Node, &mut Visibility), With<SindenBorder>>,
+    mut camera_query: Query<&mut Projection, (With<Camera2d>, Without<NativeCamera>)>,
+) {
+    // `UiScale` would make it thicker, the play area is worked out for
+    // window pixels
+    for (mut node, _) in &mut border_query {
+        let border = UiRect::all(Val::Px(BORDER / settings.ui.scale));
+        if node.border != border {
+            node.border = border;
+        }
+    }
+    let area = PlayArea {
+        view_x: play_area.view_x,
+        ..PlayArea::new(settings.window.sinden_border)
+    };
+    if *play_area == area {
+        return;
+    }
+    // Photo mode zooms relative to this, so it's fine to set mid photo
+    for mut projection in &mut camera_query {
+        if let Projection::Orthographic(ortho) = projection.as_mut() {
+            ortho.scale *= area.scale / play_area.scale;
+        }
+    }
+    for (_, mut visibility) in &mut border_query {
+        *visibility = if settings.window.sinden_border {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    *play_area = area;
+}
@@ -0,0 +1,123 @@
+//! Cosmetic skins for the ducks and the dog, picked on the accessibility
+//! page once the achievement for them is earned. A skin is its own duck
+//! sheet with its own named frames and a dog sheet cut like the classic one,
+//! `GameAssets` hands out whichever is in use.
+//!
+//! Skins only change how things look. The alpha mask is rebuilt from the
+//! skin's sheet, which has the classic shapes, so a goose is exactly as
+//! easy to hit as a duck.
+
+use std::{fmt, str::FromStr};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{achievements::UnlockedAchievements, settings::Settings, GameAssets};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Skin {
+    #[default]
+    Classic,
+    RubberDuck,
+    Goose,
+}
+
+impl Skin {
+    pub const ALL: [Skin; 3] = [Skin::Classic, Skin::RubberDuck, Skin::Goose];
+
+    /// Id of the achievement that has to be earned first
+    pub fn unlocked_by(self) -> Option<&'static str> {
+        match self {
+            Skin::Classic => None,
+            Skin::RubberDuck => Some("perfect_round"),
+            Skin::Goose => Some("sharpshooter"),
+        }
+    }
+
+    pub fn is_unlocked(self, unlocked: &UnlockedAchievements) -> bool {
+        self.unlocked_by().is_none_or(|id| unlocked.0.contains(id))
+    }
+
+    /// The next unlocked one after this in `ALL`, wrapping round
+    pub fn next_unlocked(self, unlocked: &UnlockedAchievements) -> Self {
+        let i = Self::ALL.iter().position(|skin| *skin == self).unwrap_or(0);
+        (1..=Self::ALL.len())
+            .map(|step| Self::ALL[(i + step) % Self::ALL.len()])
+            .find(|skin| skin.is_unlocked(unlocked))
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for Skin {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" => Ok(Skin::Classic),
+            "rubber_duck" => Ok(Skin::RubberDuck),
+            "goose" => Ok(Skin::Goose),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Skin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Skin::Classic => "classic",
+            Skin::RubberDuck => "rubber_duck",
+            Skin::Goose => "goose",
+        })
+    }
+}
+
+pub struct SkinPlugin;
+
+impl Plugin for SkinPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_skin);
+    }
+}
+
+/// Switches `GameAssets` over to the picked skin, or the classic one while
+/// it's still locked, e.g. when set by hand in the settings file. Sprites
+/// already showing the old sheets change over with it, or with the repack
+/// once they're packed.
+pub fn apply_skin(
+    settings: Res<Settings>,
+    unlocked: Res<UnlockedAchievements>,
+    game_assets: Option<ResMut<GameAssets>>,
+    mut sprite_query: Query<&mut Sprite>,
+) {
+    let Some(mut game_assets) = game_assets else {
+        return;
+    };
+    let picked = settings.cosmetic.skin;
+    let skin = if picked.is_unlocked(&unlocked) {
+        picked
+    } else {
+        Skin::Classic
+    };
+    if game_assets.skin() == skin {
+        return;
+    }
+    let old_duck = game_assets.duck_image().clone();
+    let old_dog = game_assets.dog_image().clone();
+    game_assets.set_skin(skin);
+    // Once packed they're all drawn from the packed image, which `packing`
+    // packs again with the new skin's sheets in it
+    if *game_assets.duck_image() == old_duck {
+        return;
+    }
+    for mut sprite in &mut sprite_query {
+        if sprite.image == old_duck {
+            sprite.image = game_assets.duck_image().clone();
+            if let Some(atlas) = &mut sprite.texture_atlas {
+                atlas.layout = game_assets.duck_layout().clone();
+            }
+        } else if sprite.image == old_dog {
+            sprite.image = game_assets.dog_image().clone();
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! The whole match as plain data, the ducks, the score, the ammo, the
+//! round and how far into it, the spawn timer, the power-ups running, the
+//! game clock and where both random streams are, so it can be written out
+//! and put back later. Suspending a game, seeking through a
+//! replay and rollback all come down to taking one of these and restoring
+//! it.
+//!
+//! What's kept is what changes how the game plays from here on, anything
+//! the next frame draws from it, the sprites and the HUD text, is made
+//! again on the way back in.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    boss::{boss_bundle, Boss},
+    duck_call::Lured,
+    duck_state::{Dying, Escaping, Falling, Flying, Launching, Perched},
+    events::PlayerId,
+    flight::FlightPattern,
+    power_up::ActiveEffects,
+    rng::{GameRng, SpawnRng},
+    score::Score,
+    spawn::species_sprite,
+    species::Species,
+    time_scale::GameTimeScale,
+    versus::Reticle,
+    weapon::EquippedWeapon,
+    Duck, ExtraDuck, GameAssets, Health, PreviousPosition, Round, RoundPhase, SpawnTimer,
+};
+
+/// Which of the duck states it was in, a duck is always in exactly one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DuckStateSnapshot {
+    Launching(Launching),
+    Flying(Flying),
+    Escaping(Escaping),
+    Perched(Perched),
+    Dying(Dying),
+    Falling(Falling),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DuckSnapshot {
+    pub species: Species,
+    pub duck: Duck,
+    pub state: DuckStateSnapshot,
+    pub transform: Transform,
+    pub previous: Vec2,
+    pub flight: FlightPattern,
+    pub health: Option<Health>,
+    pub boss: Option<Boss>,
+    /// Flying at a duck call
+    pub lured: Option<Lured>,
+    /// Not part of the round, see `ExtraDuck`
+    pub extra: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub round: Round,
+    pub phase: RoundPhase,
+    pub spawn_timer: SpawnTimer,
+    pub score: Score,
+    pub equipped: EquippedWeapon,
+    /// The weapons of versus players past the first, who carry their own
+    pub player_weapons: Vec<(PlayerId, EquippedWeapon)>,
+    pub effects: ActiveEffects,
+    pub game_rng: GameRng,
+    pub spawn_rng: SpawnRng,
+    /// The wind and the view's pan are worked out from its clock
+    pub time_scale: GameTimeScale,
+    pub ducks: Vec<DuckSnapshot>,
+}
+
+impl GameSnapshot {
+    /// The match as it is now, `None` outside of one
+    pub fn take(world: &mut World) -> Option<Self> {
+        let mut duck_query = world.query::<(
+            Entity,
+            &Species,
+            &Duck,
+            &Transform,
+            &PreviousPosition,
+            &FlightPattern,
+            Option<&Health>,
+            Option<&Boss>,
+            Option<&Lured>,
+            Has<ExtraDuck>,
+        )>();
+        let mut ducks = Vec::new();
+        for (entity, species, duck, transform, previous, flight, health, boss, lured, extra) in
+            duck_query.iter(world)
+        {
+            // Caught between two states by a command that hasn't landed yet
+            let Some(state) = duck_state(world, entity) else {
+                continue;
+            };
+            ducks.push(DuckSnapshot {
+                species: *species,
+                duck: duck.clone(),
+                state,
+                transform: *transform,
+                previous: previous.0,
+                flight: flight.clone(),
+                health: health.cloned(),
+                boss: boss.cloned(),
+                lured: lured.cloned(),
+                extra,
+            });
+        }
+        let mut reticle_query = world.query::<(&Reticle, &EquippedWeapon)>();
+        let mut player_weapons: Vec<_> = reticle_query
+            .iter(world)
+            .map(|(reticle, equipped)| (reticle.player, equipped.clone()))
+            .collect();
+        player_weapons.sort_by_key(|(player, _)| player.0);
+        Some(Self {
+            round: world.get_resource::<Round>()?.clone(),
+            phase: *world.get_resource::<State<RoundPhase>>()?.get(),
+            spawn_timer: world.get_resource::<SpawnTimer>()?.clone(),
+            score: *world.get_resource::<Score>()?,
+            equipped: world.get_resource::<EquippedWeapon>()?.clone(),
+            player_weapons,
+            effects: world.get_resource::<ActiveEffects>()?.clone(),
+            game_rng: world.get_resource::<GameRng>()?.clone(),
+            spawn_rng: world.get_resource::<SpawnRng>()?.clone(),
+            time_scale: world.get_resource::<GameTimeScale>()?.clone(),
+            ducks,
+        })
+    }
+
+    /// Puts the match back the way it was, the ducks on the field now are
+    /// swapped for the ones that were
+    pub fn restore(&self, world: &mut World) {
+        world.insert_resource(self.round.clone());
+        // Takes effect on the next state transition, and only if it's
+        // different, so the intro doesn't play again for nothing
+        let phase = world
+            .get_resource::<State<RoundPhase>>()
+            .map(|phase| *phase.get());
+        if phase.is_some_and(|phase| phase != self.phase) {
+            world
+                .resource_mut::<NextState<RoundPhase>>()
+                .set(self.phase);
+        }
+        world.insert_resource(self.spawn_timer.clone());
+        world.insert_resource(self.score);
+        world.insert_resource(self.equipped.clone());
+        let mut reticle_query = world.query::<(&Reticle, &mut EquippedWeapon)>();
+        for (reticle, mut equipped) in reticle_query.iter_mut(world) {
+            if let Some((_, saved)) = self
+                .player_weapons
+                .iter()
+                .find(|(player, _)| *player == reticle.player)
+            {
+                *equipped = saved.clone();
+            }
+        }
+        world.insert_resource(self.effects.clone());
+        world.insert_resource(self.game_rng.clone());
+        world.insert_resource(self.spawn_rng.clone());
+        world.insert_resource(self.time_scale.clone());
+        let mut duck_query = world.query_filtered::<Entity, With<Duck>>();
+        let current: Vec<_> = duck_query.iter(world).collect();
+        for entity in current {
+            world.despawn(entity);
+        }
+        for saved in &self.ducks {
+            restore_duck(world, saved);
+        }
+    }
+}
+
+fn duck_state(world: &World, entity: Entity) -> Option<DuckStateSnapshot> {
+    let entity = world.get_entity(entity).ok()?;
+    if let Some(launching) = entity.get::<Launching>() {
+        return Some(DuckStateSnapshot::Launching(*launching));
+    }
+    if let Some(flying) = entity.get::<Flying>() {
+        return Some(DuckStateSnapshot::Flying(*flying));
+    }
+    if let Some(escaping) = entity.get::<Escaping>() {
+        return Some(DuckStateSnapshot::Escaping(*escaping));
+    }
+    if let Some(perched) = entity.get::<Perched>() {
+        return Some(DuckStateSnapshot::Perched(perched.clone()));
+    }
+    if let Some(dying) = entity.get::<Dying>() {
+        return Some(DuckStateSnapshot::Dying(dying.clone()));
+    }
+    if let Some(falling) = entity.get::<Falling>() {
+        return Some(DuckStateSnapshot::Falling(*falling));
+    }
+    None
+}
+
+fn restore_duck(world: &mut World, saved: &DuckSnapshot) {
+    let sprite = world
+        .get_resource::<GameAssets>()
+        .map(|game_assets| species_sprite(game_assets, saved.species))
+        .unwrap_or_default();
+    // The sprite and transform go in before `Duck`, the same as a duck
+    // that's just spawned, so its `OnAdd` observers see them
+    let mut entity = world.spawn((saved.species, saved.transform, sprite));
+    if saved.species == Species::Boss {
+        entity.insert(boss_bundle());
+    }
+    entity.insert((
+        saved.duck.clone(),
+        PreviousPosition(saved.previous),
+        saved.flight.clone(),
+    ));
+    if let Some(health) = &saved.health {
+        entity.insert(health.clone());
+    }
+    if let Some(boss) = &saved.boss {
+        entity.insert(boss.clone());
+    }
+    if let Some(lured) = &saved.lured {
+        entity.insert(lured.clone());
+    }
+    if saved.extra {
+        entity.insert(ExtraDuck);
+    }
+    match &saved.state {
+        DuckStateSnapshot::Launching(launching) => entity.insert(*launching),
+        DuckStateSnapshot::Flying(flying) => entity.insert(*flying),
+        DuckStateSnapshot::Escaping(escaping) => entity.insert(*escaping),
+        DuckStateSnapshot::Perched(perched) => entity.insert(perched.clone()),
+        DuckStateSnapshot::Dying(dying) => entity.insert(dying.clone()),
+        DuckStateSnapshot::Falling(falling) => entity.insert(*falling),
+    };
+}
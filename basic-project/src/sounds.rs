@@ -0,0 +1,91 @@
+//! The ducks' sound effects. Each one plays once from its own entity and
+//! goes away when it's done. The rain loop is with the rest of the weather,
+//! see `weather`.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    duck_state::{Falling, Launching},
+    weapon::{EquippedWeapon, WeaponKind},
+    FrameSet, GameAssets, GameState,
+};
+
+pub struct SoundsPlugin;
+
+impl Plugin for SoundsPlugin {
+    fn build(&self, app: &mut App) {
+        // From the presentation, a rollback that steps the same launch again
+        // shouldn't play it twice
+        app.add_systems(
+            Update,
+            (play_wing_flaps, play_falling_whistles, play_reload_pumps)
+                .in_set(FrameSet::Presentation)
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+// Flaps its way up out of the grass
+fn play_wing_flaps(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    duck_query: Query<(), Added<Launching>>,
+) {
+    for () in &duck_query {
+        commands.spawn((
+            AudioPlayer::new(game_assets.wing_flap_sound.clone()),
+            PlaybackSettings::DESPAWN,
+            StateScoped(GameState::InGame),
+        ));
+    }
+}
+
+// Whistles on the way down, from the duck itself so it stops short when the
+// duck lands
+fn play_falling_whistles(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    duck_query: Query<Entity, Added<Falling>>,
+) {
+    for entity in &duck_query {
+        commands.spawn((
+            AudioPlayer::new(game_assets.falling_whistle_sound.clone()),
+            PlaybackSettings::DESPAWN,
+            ChildOf(entity),
+        ));
+    }
+}
+
+// Racks the pump once the fresh shells are in. Going by what was reloading
+// last frame, `None` for the weapon everyone but the versus players share,
+// and only if it's still the same weapon so switching away isn't mistaken
+// for a finished reload.
+fn play_reload_pumps(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    equipped: Res<EquippedWeapon>,
+    player_weapon_query: Query<(Entity, &EquippedWeapon)>,
+    mut reloading: Local<HashMap<Option<Entity>, WeaponKind>>,
+) {
+    let weapons = std::iter::once((None, &*equipped)).chain(
+        player_weapon_query
+            .iter()
+            .map(|(entity, weapon)| (Some(entity), weapon)),
+    );
+    for (owner, weapon) in weapons {
+        if weapon.reloading() {
+            reloading.insert(owner, weapon.weapon.kind);
+            continue;
+        }
+        let Some(kind) = reloading.remove(&owner) else {
+            continue;
+        };
+        if kind == weapon.weapon.kind && weapon.ammo >= weapon.weapon.max_ammo {
+            commands.spawn((
+                AudioPlayer::new(game_assets.pump_sound.clone()),
+                PlaybackSettings::DESPAWN,
+                StateScoped(GameState::InGame),
+            ));
+        }
+    }
+}
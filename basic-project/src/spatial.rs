@@ -0,0 +1,57 @@
+//! Uniform grid of duck positions so hit tests only look at ducks near the
+//! shot instead of scanning every duck on screen.
+
+use bevy::{
+    ecs::entity::{EntityMapper, MapEntities},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+// One cell per duck tile, a point can then only touch a 2x2 block of cells
+const CELL_SIZE: f32 = 32.0;
+
+#[derive(Resource, Default, Clone)]
+pub struct SpatialIndex {
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+// Rollback can bring the ducks back under new ids
+impl MapEntities for SpatialIndex {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        for entity in self.cells.values_mut().flatten() {
+            *entity = entity_mapper.get_mapped(*entity);
+        }
+    }
+}
+
+impl SpatialIndex {
+    pub fn clear(&mut self) {
+        // Keep the allocations around, the same cells get reused every frame
+        for entities in self.cells.values_mut() {
+            entities.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        self.cells
+            .entry(Self::cell(position))
+            .or_default()
+            .push(entity);
+    }
+
+    /// Entities whose position is inside any cell overlapping `area`.
+    /// Callers still have to do the precise test themselves.
+    pub fn query(&self, area: Rect) -> impl Iterator<Item = Entity> + '_ {
+        let min = Self::cell(area.min);
+        let max = Self::cell(area.max);
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+
+    fn cell(position: Vec2) -> IVec2 {
+        (position / CELL_SIZE).floor().as_ivec2()
+    }
+}
@@ -0,0 +1,149 @@
+//! One way in for anything that puts a duck on the field, the round itself,
+//! the console, Twitch chat, the debug keys or another plugin. With
+//! `Commands` at hand that's `commands.spawn_duck(..)`, which hands back the
+//! entity to add to, otherwise a `SpawnDuckEvent`.
+//!
+//! Either way the duck gets everything its species needs, is kept inside
+//! the field and is announced with `DuckSpawned`. Ducks from events are
+//! extras and don't count towards the round.
+//!
+//! Positions are from the middle of the screen, so on a level that pans
+//! they come in where the player is looking. `DuckSpawned` has where that
+//! is in the world.
+
+use bevy::prelude::*;
+
+use crate::{
+    boss::{boss_bundle, BOSS_SCALE, BOSS_TINT},
+    duck_state::{Flying, Heading, Launching},
+    events::DuckSpawned,
+    flight::CEILING,
+    species::Species,
+    view::View,
+    Duck, ExtraDuck, GameAssets, GameState, GRASS_Y,
+};
+
+const FAST_SPEED: f32 = 100.0;
+const GOLDEN_SPEED: f32 = 80.0;
+const GOLDEN_POINTS: u32 = 3000;
+const GOLDEN_TINT: Color = Color::srgb(1.0, 0.85, 0.2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behaviour {
+    /// Climbs out of the grass, then flies off this way
+    TakeOff(Heading),
+    /// Already in the air and flying this way
+    Fly(Heading),
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct SpawnDuckEvent {
+    pub species: Species,
+    /// From the middle of the screen
+    pub position: Vec2,
+    /// The boss always starts out flying, it ignores taking off
+    pub behaviour: Behaviour,
+}
+
+impl SpawnDuckEvent {
+    /// Where it actually goes, pulled inside the field
+    fn position(&self) -> Vec2 {
+        if !self.position.is_finite() {
+            return Vec2::ZERO;
+        }
+        self.position.clamp(
+            Vec2::new(-View::HALF_WIDTH, GRASS_Y),
+            Vec2::new(View::HALF_WIDTH, CEILING),
+        )
+    }
+}
+
+/// A duck's sprite, tinted for its species
+pub fn species_sprite(game_assets: &GameAssets, species: Species) -> Sprite {
+    let mut sprite = game_assets.duck_sprite();
+    match species {
+        Species::Golden => sprite.color = GOLDEN_TINT,
+        Species::Boss => sprite.color = BOSS_TINT,
+        Species::Regular | Species::Fast => {}
+    }
+    sprite
+}
+
+pub trait SpawnDuckCommands {
+    /// Spawns a duck, its sprite is filled in from `GameAssets` when the
+    /// commands are applied
+    fn spawn_duck(&mut self, duck: SpawnDuckEvent) -> EntityCommands<'_>;
+}
+
+impl SpawnDuckCommands for Commands<'_, '_> {
+    fn spawn_duck(&mut self, duck: SpawnDuckEvent) -> EntityCommands<'_> {
+        let mut entity = self.spawn(duck.species);
+        // In before `Duck` so its `OnAdd` observers see the real sprite and
+        // where it is
+        let species = duck.species;
+        let position = duck.position();
+        entity.queue(move |mut entity: EntityWorldMut| {
+            let view = entity
+                .world()
+                .get_resource::<View>()
+                .copied()
+                .unwrap_or_default();
+            let position = position + Vec2::X * view.x;
+            let mut transform = Transform::from_translation(position.extend(0.0));
+            if species == Species::Boss {
+                transform.scale = Vec3::splat(BOSS_SCALE);
+            }
+            entity.insert(transform);
+            if let Some(game_assets) = entity.world().get_resource::<GameAssets>() {
+                let sprite = species_sprite(game_assets, species);
+                entity.insert(sprite);
+            }
+            let id = entity.id();
+            entity.world_scope(|world| {
+                world.send_event(DuckSpawned {
+                    entity: id,
+                    position,
+                });
+            });
+        });
+        match duck.species {
+            Species::Regular => entity.insert(Duck::default()),
+            Species::Fast => entity.insert(Duck {
+                speed: FAST_SPEED,
+                ..default()
+            }),
+            Species::Golden => entity.insert(Duck {
+                speed: GOLDEN_SPEED,
+                points: GOLDEN_POINTS,
+            }),
+            Species::Boss => entity.insert(boss_bundle()),
+        };
+        match (duck.behaviour, duck.species) {
+            (Behaviour::TakeOff(heading), species) if species != Species::Boss => {
+                entity.insert(Launching { heading })
+            }
+            (Behaviour::TakeOff(heading) | Behaviour::Fly(heading), _) => {
+                entity.insert(Flying { heading })
+            }
+        };
+        let id = entity.id();
+        self.entity(id)
+    }
+}
+
+pub struct SpawnPlugin;
+
+impl Plugin for SpawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnDuckEvent>().add_systems(
+            Update,
+            spawn_requested_ducks.run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn spawn_requested_ducks(mut commands: Commands, mut spawn_reader: EventReader<SpawnDuckEvent>) {
+    for duck in spawn_reader.read() {
+        commands.spawn_duck(*duck).insert(ExtraDuck);
+    }
+}
@@ -0,0 +1,197 @@
+//! The kinds of duck and what sets them apart, read from
+//! `assets/data/default.species.ron`. For now that's how big a target each
+//! one is: fast ducks are harder to hit and the boss is easier. Every duck
+//! gets a `Hitbox` for its kind as it spawns.
+
+use std::str::FromStr;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{Duck, GameAssets};
+
+/// What a duck was spawned as, see `spawn::SpawnDuckCommands`
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Species {
+    /// Regular ducks at least as fast as the table's `fast_speed` count as
+    /// `Fast` too
+    #[default]
+    Regular,
+    Fast,
+    /// Rare and worth a lot more
+    Golden,
+    Boss,
+}
+
+impl FromStr for Species {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "regular" => Ok(Species::Regular),
+            "fast" => Ok(Species::Fast),
+            "golden" => Ok(Species::Golden),
+            "boss" => Ok(Species::Boss),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Species {
+    /// The name `from_str` takes back
+    pub fn name(&self) -> &'static str {
+        match self {
+            Species::Regular => "regular",
+            Species::Fast => "fast",
+            Species::Golden => "golden",
+            Species::Boss => "boss",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SpeciesStats {
+    pub species: Species,
+    /// Width and height in sprite pixels, before the duck's scale
+    pub hitbox: [f32; 2],
+}
+
+/// What a shot has to land in, centered on the duck and scaled with it.
+/// Defaults to the whole 32x32 sprite tile.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Hitbox {
+    pub size: Vec2,
+}
+
+impl Default for Hitbox {
+    fn default() -> Self {
+        Self {
+            size: Vec2::splat(32.0),
+        }
+    }
+}
+
+impl Hitbox {
+    /// Where it is for a duck at `position` with this `transform`'s scale
+    pub fn rect(&self, position: Vec2, transform: &Transform) -> Rect {
+        Rect::from_center_size(position, self.size * transform.scale.xy())
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct SpeciesTable {
+    /// Speed from which a regular duck counts as a fast one
+    pub fast_speed: f32,
+    pub species: Vec<SpeciesStats>,
+}
+
+impl SpeciesTable {
+    /// What the table treats a duck spawned as `species` as
+    pub fn species(&self, species: Species, duck: &Duck) -> Species {
+        match species {
+            Species::Regular if duck.speed >= self.fast_speed => Species::Fast,
+            species => species,
+        }
+    }
+
+    /// The loaded hitbox for `species`, or the whole tile if the table
+    /// doesn't have it
+    pub fn hitbox(&self, species: Species) -> Hitbox {
+        self.species
+            .iter()
+            .find(|stats| stats.species == species)
+            .map(|stats| Hitbox {
+                size: Vec2::from(stats.hitbox),
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+pub struct SpeciesTableLoader;
+
+#[derive(Debug, Error)]
+pub enum SpeciesTableLoaderError {
+    #[error("could not read species table: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse species table: {0}")]
+    Ron(#[from] ron::de::SpannedError),
+}
+
+impl AssetLoader for SpeciesTableLoader {
+    type Asset = SpeciesTable;
+    type Settings = ();
+    type Error = SpeciesTableLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["species.ron"]
+    }
+}
+
+pub struct SpeciesPlugin;
+
+impl Plugin for SpeciesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SpeciesTable>()
+            .init_asset_loader::<SpeciesTableLoader>()
+            .add_observer(set_hitbox)
+            .add_systems(Update, apply_species_table);
+    }
+}
+
+// Again whenever the duck's stats are replaced, its speed can change what
+// it counts as
+fn set_hitbox(
+    trigger: Trigger<OnInsert, Duck>,
+    game_assets: Option<Res<GameAssets>>,
+    species_tables: Res<Assets<SpeciesTable>>,
+    mut duck_query: Query<(&Duck, &Species, &mut Hitbox)>,
+) {
+    let Some(table) = game_assets.and_then(|assets| species_tables.get(&assets.species_table))
+    else {
+        return;
+    };
+    if let Ok((duck, species, mut hitbox)) = duck_query.get_mut(trigger.target()) {
+        *hitbox = table.hitbox(table.species(*species, duck));
+    }
+}
+
+// Edits to the table show up on the ducks already flying
+fn apply_species_table(
+    mut species_table_events: EventReader<AssetEvent<SpeciesTable>>,
+    game_assets: Option<Res<GameAssets>>,
+    species_tables: Res<Assets<SpeciesTable>>,
+    mut duck_query: Query<(&Duck, &Species, &mut Hitbox)>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    for event in species_table_events.read() {
+        if !event.is_loaded_with_dependencies(&game_assets.species_table)
+            && !event.is_modified(&game_assets.species_table)
+        {
+            continue;
+        }
+        let Some(table) = species_tables.get(&game_assets.species_table) else {
+            continue;
+        };
+        for (duck, species, mut hitbox) in &mut duck_query {
+            *hitbox = table.hitbox(table.species(*species, duck));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Optional speedrun timer, turned on with `speedrun.enabled` or from the
+//! accessibility page. Starts on the first shot, takes a split every time a
+//! round ends and stops when the game does.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    bitmap_text::{BitmapText, TextAlign},
+    events::{RoundEnded, ShotFired},
+    hud::Hud,
+    locale::Locale,
+    settings::Settings,
+    GameState,
+};
+
+#[derive(Resource, Default, Debug, Clone)]
+pub struct SpeedrunTimer {
+    /// Shows the timer and records the final time with the score, taken
+    /// from the settings when a game starts
+    pub enabled: bool,
+    running: bool,
+    elapsed: Duration,
+    splits: Vec<Duration>,
+}
+
+impl SpeedrunTimer {
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Time at the end of every round so far
+    pub fn splits(&self) -> &[Duration] {
+        &self.splits
+    }
+
+    /// The time to record with the score, none if it never got going
+    pub fn final_time(&self) -> Option<Duration> {
+        (self.enabled && self.running).then_some(self.elapsed)
+    }
+}
+
+#[derive(Component)]
+pub struct SpeedrunText;
+
+pub fn format_run_time(time: Duration) -> String {
+    let centis = time.as_millis() / 10;
+    format!(
+        "{}:{:02}.{:02}",
+        centis / 6_000,
+        (centis / 100) % 60,
+        centis % 100
+    )
+}
+
+pub fn setup_speedrun_timer(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut timer: ResMut<SpeedrunTimer>,
+) {
+    *timer = SpeedrunTimer {
+        enabled: settings.speedrun.enabled,
+        ..default()
+    };
+    if !timer.enabled {
+        return;
+    }
+    // Top left, over the sky
+    commands.spawn((
+        BitmapText::new(format_run_time(Duration::ZERO)).with_align(TextAlign::Left),
+        Transform::from_xyz(-124.0, 116.0, 2.0),
+        SpeedrunText,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+// Measured on the unscaled clock, slow-mo is still real time to a runner
+pub fn tick_speedrun_timer(
+    time: Res<Time>,
+    locale: Res<Locale>,
+    mut timer: ResMut<SpeedrunTimer>,
+    mut shot_fired_reader: EventReader<ShotFired>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+    mut text_query: Query<&mut BitmapText, With<SpeedrunText>>,
+) {
+    if !timer.enabled {
+        return;
+    }
+    if !timer.running && shot_fired_reader.read().count() > 0 {
+        timer.running = true;
+    }
+    if !timer.running {
+        return;
+    }
+    timer.elapsed += time.delta();
+    for ended in round_ended_reader.read() {
+        let split = timer.elapsed;
+        timer.splits.push(split);
+        println!("Round {} split {}", ended.round, format_run_time(split));
+    }
+
+    let mut label = format_run_time(timer.elapsed);
+    if let Some(last) = timer.splits.last() {
+        label.push('\n');
+        label.push_str(&locale.format(
+            "speedrun.split",
+            &[
+                ("round", &timer.splits.len()),
+                ("time", &format_run_time(*last)),
+            ],
+        ));
+    }
+    for mut text in &mut text_query {
+        BitmapText::set(&mut text, &label);
+    }
+}
@@ -0,0 +1,142 @@
+//! Spritesheets described by a TexturePacker JSON export instead of a grid,
+//! so frames are looked up by name. The image named in `meta.image` is
+//! loaded next to the JSON and the layout is available as the `layout`
+//! label, e.g. `textures/duck.atlas.json#layout`.
+//!
+//! Both the "JSON (Array)" and "JSON (Hash)" exports load. The array keeps
+//! the frame order of the file, hash frames are indexed in name order.
+
+use std::collections::{BTreeMap, HashMap};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext, ParseAssetPathError},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Asset, TypePath, Debug)]
+pub struct SpriteSheet {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    frames: HashMap<String, usize>,
+}
+
+impl SpriteSheet {
+    /// Atlas index of the frame, named like the file it was packed from
+    /// without the extension
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.frames.get(name).copied()
+    }
+}
+
+#[derive(Deserialize)]
+struct TexturePackerAtlas {
+    frames: TexturePackerFrames,
+    meta: TexturePackerMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TexturePackerFrames {
+    Array(Vec<NamedFrame>),
+    Hash(BTreeMap<String, Frame>),
+}
+
+#[derive(Deserialize)]
+struct NamedFrame {
+    filename: String,
+    #[serde(flatten)]
+    frame: Frame,
+}
+
+#[derive(Deserialize)]
+struct Frame {
+    frame: FrameRect,
+}
+
+#[derive(Deserialize)]
+struct FrameRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct TexturePackerMeta {
+    image: String,
+    size: FrameSize,
+}
+
+#[derive(Deserialize)]
+struct FrameSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Default)]
+pub struct SpriteSheetLoader;
+
+#[derive(Debug, Error)]
+pub enum SpriteSheetLoaderError {
+    #[error("could not read sprite sheet: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse sprite sheet: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("sprite sheet image path is invalid: {0}")]
+    ImagePath(#[from] ParseAssetPathError),
+}
+
+impl AssetLoader for SpriteSheetLoader {
+    type Asset = SpriteSheet;
+    type Settings = ();
+    type Error = SpriteSheetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let atlas: TexturePackerAtlas = serde_json::from_slice(&bytes)?;
+        let image_path = load_context.asset_path().resolve_embed(&atlas.meta.image)?;
+        let image = load_context.load(image_path);
+
+        let frames: Vec<(String, FrameRect)> = match atlas.frames {
+            TexturePackerFrames::Array(frames) => frames
+                .into_iter()
+                .map(|frame| (frame.filename, frame.frame.frame))
+                .collect(),
+            TexturePackerFrames::Hash(frames) => frames
+                .into_iter()
+                .map(|(name, frame)| (name, frame.frame))
+                .collect(),
+        };
+        let mut layout =
+            TextureAtlasLayout::new_empty(UVec2::new(atlas.meta.size.w, atlas.meta.size.h));
+        let mut names = HashMap::new();
+        for (name, rect) in frames {
+            let index =
+                layout.add_texture(URect::new(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h));
+            let name = match name.rsplit_once('.') {
+                Some((stem, _extension)) => stem.to_string(),
+                None => name,
+            };
+            names.insert(name, index);
+        }
+        let layout = load_context.add_labeled_asset("layout".to_string(), layout);
+
+        Ok(SpriteSheet {
+            image,
+            layout,
+            frames: names,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.json"]
+    }
+}
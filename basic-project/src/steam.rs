@@ -0,0 +1,99 @@
+//! Steamworks achievements and stats. Achievements unlock from
+//! `AchievementUnlocked` and the lifetime stats are pushed from the gameplay
+//! events. Without a running Steam client none of this is added.
+
+use bevy::prelude::*;
+use steamworks::{Client, SingleClient};
+
+use crate::{
+    demo::Demo,
+    events::{AchievementUnlocked, DuckHit, RoundEnded, ShotFired},
+};
+
+// Stat API names, these have to match the ones set up on Steamworks
+const STAT_SHOTS_FIRED: &str = "shots_fired";
+const STAT_DUCKS_HIT: &str = "ducks_hit";
+const STAT_ROUNDS_PLAYED: &str = "rounds_played";
+
+#[derive(Resource)]
+pub struct Steam(Client);
+
+/// Counted since the last upload
+#[derive(Default)]
+struct PendingStats {
+    shots_fired: i32,
+    ducks_hit: i32,
+    rounds_played: i32,
+}
+
+pub struct SteamPlugin;
+
+impl Plugin for SteamPlugin {
+    fn build(&self, app: &mut App) {
+        let (client, single) = match Client::init() {
+            Ok(clients) => clients,
+            Err(error) => {
+                println!("Steam is not available: {error}");
+                return;
+            }
+        };
+        client.user_stats().request_current_stats();
+        app.insert_resource(Steam(client))
+            // The callback pump isn't Send, it has to stay on the main thread
+            .insert_non_send_resource(single)
+            .add_systems(PreUpdate, run_steam_callbacks)
+            .add_systems(Update, (unlock_achievements, push_stats));
+    }
+}
+
+fn run_steam_callbacks(single: NonSend<SingleClient>) {
+    single.run_callbacks();
+}
+
+fn unlock_achievements(steam: Res<Steam>, mut unlocked_reader: EventReader<AchievementUnlocked>) {
+    let mut changed = false;
+    for unlocked in unlocked_reader.read() {
+        let stats = steam.0.user_stats();
+        changed |= stats.achievement(unlocked.id).set().is_ok();
+    }
+    if changed && steam.0.user_stats().store_stats().is_err() {
+        println!("Could not store Steam achievements");
+    }
+}
+
+fn push_stats(
+    steam: Res<Steam>,
+    demo: Option<Res<Demo>>,
+    mut pending: Local<PendingStats>,
+    mut shot_reader: EventReader<ShotFired>,
+    mut hit_reader: EventReader<DuckHit>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+) {
+    // The bot's demo games aren't the player's stats
+    if demo.is_some() {
+        shot_reader.clear();
+        hit_reader.clear();
+        round_ended_reader.clear();
+        return;
+    }
+    pending.shots_fired += shot_reader.read().count() as i32;
+    pending.ducks_hit += hit_reader.read().count() as i32;
+    pending.rounds_played += round_ended_reader.read().count() as i32;
+    // Only upload at the end of a round, Steam rate limits storing
+    if pending.rounds_played == 0 {
+        return;
+    }
+    let stats = steam.0.user_stats();
+    for (name, amount) in [
+        (STAT_SHOTS_FIRED, pending.shots_fired),
+        (STAT_DUCKS_HIT, pending.ducks_hit),
+        (STAT_ROUNDS_PLAYED, pending.rounds_played),
+    ] {
+        let current = stats.get_stat_i32(name).unwrap_or(0);
+        let _ = stats.set_stat_i32(name, current + amount);
+    }
+    if stats.store_stats().is_err() {
+        println!("Could not store Steam stats");
+    }
+    *pending = PendingStats::default();
+}
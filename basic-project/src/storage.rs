@@ -0,0 +1,94 @@
+//! Where files that outlive a session go. Settings land in the platform's
+//! config directory and everything else (profile, replays) in its data
+//! directory, e.g. `~/.local/share/duck-hunt` on Linux, `%APPDATA%` on
+//! Windows and `Application Support` on macOS. Screenshots are for the
+//! player to find, they go in a `Duck Hunt` folder in their pictures.
+//!
+//! Portable installs keep everything in one folder instead, either the one in
+//! `DUCK_HUNT_DATA_DIR` or the game's own when a file called `portable` sits
+//! next to the executable.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use directories::{ProjectDirs, UserDirs};
+
+const DATA_DIR_VAR: &str = "DUCK_HUNT_DATA_DIR";
+const PORTABLE_MARKER: &str = "portable";
+const PICTURES_FOLDER: &str = "Duck Hunt";
+const SCREENSHOTS_FOLDER: &str = "screenshots";
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct Storage {
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+    pictures_dir: PathBuf,
+}
+
+impl Storage {
+    /// Keeps config and data together in `dir`
+    pub fn portable(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self {
+            config_dir: dir.clone(),
+            pictures_dir: dir.join(SCREENSHOTS_FOLDER),
+            data_dir: dir,
+        }
+    }
+
+    /// Picks the directories for this install, the override wins over the
+    /// portable marker which wins over the platform directories
+    pub fn locate() -> Self {
+        if let Some(dir) = env::var_os(DATA_DIR_VAR) {
+            return Self::portable(dir);
+        }
+        let exe_dir = env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(Path::to_path_buf));
+        if let Some(exe_dir) = exe_dir.filter(|dir| dir.join(PORTABLE_MARKER).exists()) {
+            return Self::portable(exe_dir);
+        }
+        match ProjectDirs::from("", "meetballs", "duck-hunt") {
+            Some(dirs) => Self {
+                config_dir: dirs.config_dir().to_path_buf(),
+                data_dir: dirs.data_dir().to_path_buf(),
+                // Not every platform has a pictures folder
+                pictures_dir: UserDirs::new()
+                    .and_then(|user| user.picture_dir().map(|dir| dir.join(PICTURES_FOLDER)))
+                    .unwrap_or_else(|| dirs.data_dir().join(SCREENSHOTS_FOLDER)),
+            },
+            // No home directory to speak of, fall back to the working one
+            None => Self::portable("."),
+        }
+    }
+
+    pub fn config_file(&self, name: &str) -> PathBuf {
+        self.config_dir.join(name)
+    }
+
+    pub fn data_file(&self, name: &str) -> PathBuf {
+        self.data_dir.join(name)
+    }
+
+    pub fn pictures_dir(&self) -> &Path {
+        &self.pictures_dir
+    }
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Self::locate()
+    }
+}
+
+/// Like `fs::write` but creates the directory first, neither of the storage
+/// directories exists until something is saved for the first time
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
@@ -0,0 +1,177 @@
+//! The row of ten ducks in the HIT box. The duck that's out blinks, hit
+//! ducks turn red, and the blue bar under them marks how many have to go
+//! down, blinking until that many have.
+
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::{
+    events::{DuckEscaped, DuckHit, DuckSpawned, RoundEnded},
+    view::ScreenFixed,
+    ExtraDuck, GameAssets, GameState, PauseState, Round, DUCKS_PER_ROUND,
+};
+
+// First duck icon in the background's HIT box, in background pixels
+const FIRST_ICON: Vec2 = Vec2::new(96.0, 208.0);
+const ICON_SIZE: f32 = 8.0;
+// The bar under the icons, it's printed at six ducks so ours covers it
+const BAR_Y: f32 = 218.0;
+const BAR_HEIGHT: f32 = 5.0;
+const BAR_COLOR: Color = Color::srgb(0.25, 0.5, 1.0);
+const HIT_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+// Blinks a second
+const BLINK_RATE: f32 = 4.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    #[default]
+    Waiting,
+    Out(Entity),
+    Hit,
+    Escaped,
+}
+
+#[derive(Resource, Default)]
+struct Tally {
+    slots: [Slot; DUCKS_PER_ROUND as usize],
+}
+
+impl Tally {
+    fn slot_mut(&mut self, entity: Entity) -> Option<&mut Slot> {
+        self.slots
+            .iter_mut()
+            .find(|slot| **slot == Slot::Out(entity))
+    }
+}
+
+#[derive(Component)]
+struct TallyIcon(usize);
+
+#[derive(Component)]
+struct RequiredBar;
+
+pub struct TallyPlugin;
+
+impl Plugin for TallyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tally>()
+            .add_systems(OnEnter(GameState::InGame), setup_tally)
+            .add_systems(
+                Update,
+                (track_tally, draw_tally.after(track_tally)).run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+// Background pixels to world space, the background is centered on the
+// origin
+fn to_world(pixel: Vec2) -> Vec2 {
+    Vec2::new(pixel.x - 256.0 / 2.0, 240.0 / 2.0 - pixel.y)
+}
+
+fn setup_tally(mut commands: Commands, mut tally: ResMut<Tally>, game_assets: Res<GameAssets>) {
+    *tally = Tally::default();
+    for i in 0..DUCKS_PER_ROUND as usize {
+        // The printed icon itself, tinted to show what happened to the duck
+        let corner = FIRST_ICON + Vec2::X * ICON_SIZE * i as f32;
+        let center = to_world(corner + Vec2::splat(ICON_SIZE / 2.0));
+        commands.spawn((
+            Sprite {
+                image: game_assets.background_spritesheet.clone(),
+                rect: Some(
+                    game_assets.background_rect(Rect::from_corners(corner, corner + ICON_SIZE)),
+                ),
+                ..default()
+            },
+            Transform::from_translation(center.extend(2.0)),
+            TallyIcon(i),
+            ScreenFixed,
+            StateScoped(GameState::InGame),
+        ));
+    }
+    let width = ICON_SIZE * DUCKS_PER_ROUND as f32;
+    let bar = Vec2::new(FIRST_ICON.x, BAR_Y);
+    // Blacks out the printed bar so only the one for this round shows
+    commands.spawn((
+        Sprite::from_color(Color::BLACK, Vec2::new(width, BAR_HEIGHT)),
+        Transform::from_translation(to_world(bar + Vec2::new(width, BAR_HEIGHT) / 2.0).extend(2.0)),
+        ScreenFixed,
+        StateScoped(GameState::InGame),
+    ));
+    commands.spawn((
+        Sprite {
+            // Grows from its left edge
+            anchor: Anchor::CenterLeft,
+            ..Sprite::from_color(BAR_COLOR, Vec2::new(width, BAR_HEIGHT))
+        },
+        Transform::from_translation(to_world(bar + Vec2::Y * BAR_HEIGHT / 2.0).extend(2.1)),
+        RequiredBar,
+        ScreenFixed,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+fn track_tally(
+    mut tally: ResMut<Tally>,
+    extra_query: Query<(), With<ExtraDuck>>,
+    mut duck_spawned_reader: EventReader<DuckSpawned>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+    mut duck_escaped_reader: EventReader<DuckEscaped>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+) {
+    for spawned in duck_spawned_reader.read() {
+        if extra_query.contains(spawned.entity) {
+            continue;
+        }
+        if let Some(slot) = tally.slots.iter_mut().find(|slot| **slot == Slot::Waiting) {
+            *slot = Slot::Out(spawned.entity);
+        }
+    }
+    for hit in duck_hit_reader.read() {
+        if let Some(slot) = tally.slot_mut(hit.entity) {
+            *slot = Slot::Hit;
+        }
+    }
+    for escaped in duck_escaped_reader.read() {
+        if let Some(slot) = tally.slot_mut(escaped.entity) {
+            *slot = Slot::Escaped;
+        }
+    }
+    // A fresh row for the next round
+    if round_ended_reader.read().count() > 0 {
+        *tally = Tally::default();
+    }
+}
+
+fn draw_tally(
+    time: Res<Time>,
+    tally: Res<Tally>,
+    round: Res<Round>,
+    mut icon_query: Query<(&mut Sprite, &TallyIcon), Without<RequiredBar>>,
+    mut bar_query: Query<(&mut Sprite, &mut Visibility), With<RequiredBar>>,
+) {
+    let blink_on = ((time.elapsed_secs() * BLINK_RATE * 2.0) as u32).is_multiple_of(2);
+    for (mut sprite, TallyIcon(i)) in &mut icon_query {
+        // Slots past the end of a short round stay dark
+        let (color, visible) = match tally.slots[*i] {
+            _ if *i as u32 >= round.ducks_total => (Color::BLACK, true),
+            Slot::Out(_) => (Color::WHITE, blink_on),
+            Slot::Hit => (HIT_COLOR, true),
+            Slot::Waiting | Slot::Escaped => (Color::WHITE, true),
+        };
+        sprite.color = if visible { color } else { Color::BLACK };
+    }
+    let required = round.ducks_required();
+    let hits = tally
+        .slots
+        .iter()
+        .filter(|slot| **slot == Slot::Hit)
+        .count() as u32;
+    for (mut sprite, mut visibility) in &mut bar_query {
+        sprite.custom_size = Some(Vec2::new(ICON_SIZE * required as f32, BAR_HEIGHT));
+        *visibility = if hits >= required || blink_on {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
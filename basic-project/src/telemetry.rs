@@ -0,0 +1,124 @@
+//! Anonymous gameplay events, sent only when `telemetry.enabled` is turned
+//! on in the settings and `DUCK_HUNT_TELEMETRY_URL` says where to. Each one
+//! is posted as JSON to `{url}/events` with a random id picked at launch, so
+//! events from one session can be told apart but never tied to a player.
+//!
+//! A game is reported once it's left, with its mode and the round it got
+//! to, and a session that quits normally says so on the way out. A session
+//! that never sends its end is one that crashed.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, tasks::IoTaskPool};
+use serde::Serialize;
+
+use crate::{demo::Demo, events::ResetGame, mode::GameMode, settings::Settings, Round};
+
+// Quitting waits on the last event, it shouldn't wait for long
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    GamePlayed { mode: String, round: u32 },
+    SessionEnded { seconds: u64 },
+}
+
+/// An event as it's sent
+#[derive(Serialize, Debug, Clone, PartialEq)]
+struct Envelope<'a> {
+    session: u64,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a TelemetryEvent,
+}
+
+#[derive(Resource, Clone)]
+pub struct Telemetry {
+    agent: ureq::Agent,
+    url: String,
+    session: u64,
+}
+
+impl Telemetry {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+            url: url.into(),
+            session: rand::random(),
+        }
+    }
+
+    /// Blocks until the server answers or the timeout runs out
+    fn send(&self, event: &TelemetryEvent) {
+        let envelope = Envelope {
+            session: self.session,
+            version: env!("CARGO_PKG_VERSION"),
+            event,
+        };
+        if let Err(error) = self
+            .agent
+            .post(&format!("{}/events", self.url))
+            .send_json(&envelope)
+        {
+            println!("Could not send telemetry: {error}");
+        }
+    }
+
+    fn send_in_background(&self, event: TelemetryEvent) {
+        let telemetry = self.clone();
+        IoTaskPool::get()
+            .spawn(async move { telemetry.send(&event) })
+            .detach();
+    }
+}
+
+pub struct TelemetryPlugin;
+
+impl Plugin for TelemetryPlugin {
+    fn build(&self, app: &mut App) {
+        let Ok(url) = std::env::var("DUCK_HUNT_TELEMETRY_URL") else {
+            return;
+        };
+        app.insert_resource(Telemetry::new(url))
+            .add_observer(report_game)
+            .add_systems(Last, report_session_end.run_if(telemetry_enabled));
+    }
+}
+
+fn telemetry_enabled(settings: Res<Settings>) -> bool {
+    settings.telemetry.enabled
+}
+
+fn report_game(
+    _trigger: Trigger<ResetGame>,
+    settings: Res<Settings>,
+    telemetry: Res<Telemetry>,
+    mode: Res<GameMode>,
+    round: Option<Res<Round>>,
+    demo: Option<Res<Demo>>,
+) {
+    if !settings.telemetry.enabled || demo.is_some() {
+        return;
+    }
+    telemetry.send_in_background(TelemetryEvent::GamePlayed {
+        mode: format!("{:?}", *mode).to_lowercase(),
+        round: round.map_or(0, |round| round.number),
+    });
+}
+
+// Sent on the main thread, the task pools are going away with the app
+fn report_session_end(
+    time: Res<Time<Real>>,
+    telemetry: Res<Telemetry>,
+    mut app_exit_reader: EventReader<AppExit>,
+) {
+    if app_exit_reader
+        .read()
+        .any(|exit| matches!(exit, AppExit::Success))
+    {
+        telemetry.send(&TelemetryEvent::SessionEnded {
+            seconds: time.elapsed().as_secs(),
+        });
+    }
+}
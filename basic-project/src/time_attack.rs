@@ -0,0 +1,56 @@
+//! Time attack mode controller. Ducks keep coming for two minutes and the
+//! only goal is the biggest score before the clock runs out.
+
+use bevy::prelude::*;
+
+use crate::{bitmap_text::BitmapText, events::ResetGame, hud::Hud, GameState, Round};
+
+const TIME_LIMIT: f32 = 120.0;
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct TimeAttackClock(Timer);
+
+#[derive(Component)]
+pub struct CountdownText;
+
+pub fn setup_time_attack(mut commands: Commands, mut round: ResMut<Round>) {
+    // One long stretch of ducks instead of rounds
+    round.ducks_total = u32::MAX;
+    commands.insert_resource(TimeAttackClock(Timer::from_seconds(
+        TIME_LIMIT,
+        TimerMode::Once,
+    )));
+    // Top centre, over the sky
+    commands.spawn((
+        BitmapText::new(format_clock(TIME_LIMIT)),
+        Transform::from_xyz(0.0, 108.0, 2.0),
+        CountdownText,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+pub fn reset_time_attack(_trigger: Trigger<ResetGame>, mut commands: Commands) {
+    commands.remove_resource::<TimeAttackClock>();
+}
+
+// Runs on the unscaled clock, slow-mo doesn't buy extra time
+pub fn tick_time_attack(
+    time: Res<Time>,
+    mut clock: ResMut<TimeAttackClock>,
+    mut countdown_query: Query<&mut BitmapText, With<CountdownText>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    clock.tick(time.delta());
+    for mut text in &mut countdown_query {
+        BitmapText::set(&mut text, format_clock(clock.remaining_secs()));
+    }
+    if clock.finished() {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn format_clock(seconds: f32) -> String {
+    let seconds = seconds.ceil() as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,79 @@
+//! Game speed separate from Bevy's clock. Movement and animation multiply
+//! their delta by this, so slow-mo and hit-stop don't affect input, UI or
+//! anything else reading `Time`.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct GameTimeScale {
+    /// Overall speed, meant for debugging and tuning
+    pub base: f32,
+    /// Speed set by gameplay effects like the slow-mo power-up
+    pub effect: f32,
+    hit_stop: Timer,
+    elapsed: Duration,
+}
+
+impl Default for GameTimeScale {
+    fn default() -> Self {
+        let mut hit_stop = Timer::default();
+        // An empty timer starts out finished, no hit-stop until asked for
+        hit_stop.tick(Duration::ZERO);
+        Self {
+            base: 1.0,
+            effect: 1.0,
+            hit_stop,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl GameTimeScale {
+    pub fn scale(&self) -> f32 {
+        if self.hit_stop.finished() {
+            self.base * self.effect
+        } else {
+            0.0
+        }
+    }
+
+    /// Back to normal speed for a new game, the debug speed in `base` stays
+    pub fn reset(&mut self) {
+        *self = Self {
+            base: self.base,
+            ..default()
+        };
+    }
+
+    /// Freeze the game for a moment, measured in real time
+    pub fn hit_stop(&mut self, seconds: f32) {
+        self.hit_stop = Timer::from_seconds(seconds, TimerMode::Once);
+    }
+
+    pub fn delta(&self, time: &Time) -> Duration {
+        time.delta().mul_f32(self.scale())
+    }
+
+    pub fn delta_secs(&self, time: &Time) -> f32 {
+        time.delta_secs() * self.scale()
+    }
+
+    /// Scaled time since the app started, use instead of
+    /// `Time::elapsed_secs` for anything that animates
+    pub fn elapsed_secs(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+}
+
+pub fn tick_game_time_scale(
+    time: Res<Time>,
+    real_time: Res<Time<Real>>,
+    mut time_scale: ResMut<GameTimeScale>,
+) {
+    let delta = time_scale.delta(&time);
+    time_scale.elapsed += delta;
+    time_scale.hit_stop.tick(real_time.delta());
+}
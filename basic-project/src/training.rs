@@ -0,0 +1,130 @@
+//! Training range mode controller, for getting a feel for the aim before
+//! playing for real. Instead of rounds of ducks a few targets hang still over
+//! the field and a couple more drift across it slowly, each one taken down or
+//! flown off is replaced. The weapon never runs dry, every duck's hitbox is
+//! drawn and the accuracy so far is shown at the top. There's no end to it,
+//! the pause menu leads back out.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    bitmap_text::BitmapText,
+    duck_state::{Heading, Shootable},
+    flight::{CEILING, FLOOR},
+    hud::Hud,
+    locale::Locale,
+    rng::GameRng,
+    score::SessionStats,
+    spawn::{Behaviour, SpawnDuckCommands, SpawnDuckEvent},
+    species::{Hitbox, Species},
+    weapon::EquippedWeapon,
+    Duck, GameState, Round, SpawnTimer,
+};
+
+const STATIONARY_TARGETS: usize = 3;
+const MOVING_TARGETS: usize = 2;
+// Pixels per second, the slowest regular duck does 20
+const MOVING_SPEED: f32 = 12.0;
+const HITBOX_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrainingTarget {
+    Stationary,
+    Moving,
+}
+
+#[derive(Component)]
+pub struct AccuracyText;
+
+pub fn setup_training(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    mut round: ResMut<Round>,
+    mut timer: ResMut<SpawnTimer>,
+) {
+    // Always the first round so there's no wind, and one that never ends
+    *round = Round::new(1);
+    round.ducks_total = u32::MAX;
+    // The range puts up its own targets
+    timer.0.pause();
+    commands.spawn((
+        BitmapText::new(format_accuracy(&SessionStats::default(), &locale)),
+        Transform::from_xyz(0.0, 108.0, 2.0),
+        AccuracyText,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+pub fn keep_training_targets(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    target_query: Query<&TrainingTarget, Shootable>,
+) {
+    for (target, wanted) in [
+        (TrainingTarget::Stationary, STATIONARY_TARGETS),
+        (TrainingTarget::Moving, MOVING_TARGETS),
+    ] {
+        let up = target_query.iter().filter(|up| **up == target).count();
+        for _ in up..wanted {
+            // Clear of the accuracy readout and the grass
+            let position = Vec2::new(
+                rng.gen_range(-100.0..100.0),
+                rng.gen_range(FLOOR + 10.0..CEILING - 20.0),
+            );
+            let speed = match target {
+                TrainingTarget::Stationary => 0.0,
+                TrainingTarget::Moving => MOVING_SPEED,
+            };
+            commands
+                .spawn_duck(SpawnDuckEvent {
+                    species: Species::Regular,
+                    position,
+                    behaviour: Behaviour::Fly(Heading::towards_center(position.x)),
+                })
+                .insert((Duck { speed, points: 0 }, target));
+        }
+    }
+}
+
+pub fn refill_training_ammo(mut equipped: ResMut<EquippedWeapon>) {
+    if equipped.ammo < equipped.weapon.max_ammo {
+        equipped.refill();
+    }
+}
+
+// The same rect `handle_shoot_duck` tests against, scaled like the duck
+pub fn draw_hitboxes(
+    mut gizmos: Gizmos,
+    duck_query: Query<(&Transform, &Hitbox), (With<Duck>, Shootable)>,
+) {
+    for (transform, hitbox) in &duck_query {
+        let rect = hitbox.rect(transform.translation.xy(), transform);
+        gizmos.rect_2d(rect.center(), rect.size(), HITBOX_COLOR);
+    }
+}
+
+pub fn update_accuracy_text(
+    locale: Res<Locale>,
+    stats: Res<SessionStats>,
+    mut text_query: Query<&mut BitmapText, With<AccuracyText>>,
+) {
+    if !stats.is_changed() {
+        return;
+    }
+    for mut text in &mut text_query {
+        BitmapText::set(&mut text, format_accuracy(&stats, &locale));
+    }
+}
+
+fn format_accuracy(stats: &SessionStats, locale: &Locale) -> String {
+    locale.format(
+        "training.accuracy",
+        &[
+            ("hits", &stats.ducks_hit),
+            ("shots", &stats.shots_fired),
+            ("accuracy", &format!("{:.0}", stats.accuracy() * 100.0)),
+        ],
+    )
+}
@@ -0,0 +1,115 @@
+//! A few prompts over the first wave of the player's first game, one after
+//! the other until the round ends or Space skips them. Whether they've been
+//! seen is kept in the profile, the accessibility page can bring them back.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    demo::Demo, events::RoundEnded, locale::Locale, weapon::EquippedWeapon, GameState, PauseState,
+};
+
+const PROMPT_SECONDS: f32 = 4.0;
+
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tutorial {
+    pub seen: bool,
+}
+
+/// The prompt currently up, `step` is which one
+#[derive(Component)]
+pub struct TutorialPrompt {
+    step: usize,
+    timer: Timer,
+}
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::InGame),
+            start_tutorial.run_if(
+                (|tutorial: Res<Tutorial>| !tutorial.seen).and(not(resource_exists::<Demo>)),
+            ),
+        )
+        .add_systems(
+            Update,
+            (next_tutorial_prompt, finish_tutorial)
+                .chain()
+                .run_if(in_state(PauseState::Running)),
+        );
+    }
+}
+
+fn prompt(step: usize, equipped: &EquippedWeapon, locale: &Locale) -> String {
+    match step % 3 {
+        0 => locale.get("tutorial.shoot").to_string(),
+        1 => locale.format("tutorial.shells", &[("shells", &equipped.weapon.max_ammo)]),
+        _ => locale.get("tutorial.fly_away").to_string(),
+    }
+}
+
+fn start_tutorial(mut commands: Commands, locale: Res<Locale>, equipped: Res<EquippedWeapon>) {
+    commands
+        .spawn((
+            Text::new(prompt(0, &equipped, &locale)),
+            TextFont {
+                font_size: 10.0,
+                ..default()
+            },
+            TextLayout::new_with_justify(JustifyText::Center),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(56.0),
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            TutorialPrompt {
+                step: 0,
+                timer: Timer::from_seconds(PROMPT_SECONDS, TimerMode::Repeating),
+            },
+            StateScoped(GameState::InGame),
+        ))
+        .with_child((
+            TextSpan::new(format!("\n{}", locale.get("tutorial.skip"))),
+            TextFont {
+                font_size: 8.0,
+                ..default()
+            },
+        ));
+}
+
+// Goes round the prompts again if the wave is still going
+fn next_tutorial_prompt(
+    time: Res<Time>,
+    locale: Res<Locale>,
+    equipped: Res<EquippedWeapon>,
+    mut prompt_query: Query<(&mut TutorialPrompt, &mut Text)>,
+) {
+    for (mut tutorial_prompt, mut text) in &mut prompt_query {
+        if !tutorial_prompt.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        tutorial_prompt.step += 1;
+        text.0 = prompt(tutorial_prompt.step, &equipped, &locale);
+    }
+}
+
+fn finish_tutorial(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut tutorial: ResMut<Tutorial>,
+    prompt_query: Query<Entity, With<TutorialPrompt>>,
+    mut round_ended_reader: EventReader<RoundEnded>,
+) {
+    let round_over = round_ended_reader.read().count() > 0;
+    if prompt_query.is_empty() || !(round_over || keyboard_input.just_pressed(KeyCode::Space)) {
+        return;
+    }
+    for entity in &prompt_query {
+        commands.entity(entity).despawn();
+    }
+    tutorial.seen = true;
+}
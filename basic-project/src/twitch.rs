@@ -0,0 +1,270 @@
+//! Twitch chat integration. Viewers type `!duck` or `!golden` to send in a
+//! duck with their name over it, and chat hears about it when one goes
+//! down or gets away.
+//!
+//! Joins the chat of `TWITCH_CHANNEL`. With `TWITCH_NICK` and
+//! `TWITCH_OAUTH` set it logs in as that account so it can post, otherwise
+//! it joins anonymously and only reads. The connection is made and lives on
+//! its own threads, so a slow server doesn't hold up the game starting,
+//! messages cross over to the game through channels.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Write},
+    net::TcpStream,
+    thread,
+};
+
+use bevy::prelude::*;
+use crossbeam_channel::{Receiver, Sender};
+use rand::Rng;
+
+use crate::{
+    bitmap_text::BitmapText,
+    duck_state::Heading,
+    events::{DuckEscaped, DuckHit},
+    rng::GameRng,
+    spawn::{Behaviour, SpawnDuckCommands, SpawnDuckEvent},
+    species::Species,
+    Difficulty, Duck, ExtraDuck, GRASS_Y,
+};
+
+const TWITCH_IRC: &str = "irc.chat.twitch.tv:6667";
+// Seconds between two viewer ducks, so chat can't flood the screen
+const VIEWER_DUCK_INTERVAL: f32 = 2.0;
+const MAX_QUEUED: usize = 10;
+const GOLDEN_POINTS: u32 = 3000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewerDuckKind {
+    Regular,
+    /// Faster and worth a lot more
+    Golden,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewerRequest {
+    pub viewer: String,
+    pub kind: ViewerDuckKind,
+}
+
+/// Both ends of the chat connection that the game holds on to
+#[derive(Resource)]
+pub struct TwitchChat {
+    requests: Receiver<ViewerRequest>,
+    /// Missing when logged in anonymously
+    announcements: Option<Sender<String>>,
+}
+
+/// Viewer ducks waiting for their turn to fly
+#[derive(Resource)]
+pub struct ViewerQueue {
+    queue: VecDeque<ViewerRequest>,
+    timer: Timer,
+}
+
+impl Default for ViewerQueue {
+    fn default() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            timer: Timer::from_seconds(VIEWER_DUCK_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct ViewerDuck {
+    pub viewer: String,
+    pub kind: ViewerDuckKind,
+}
+
+pub struct TwitchPlugin;
+
+impl Plugin for TwitchPlugin {
+    fn build(&self, app: &mut App) {
+        let Ok(channel) = std::env::var("TWITCH_CHANNEL") else {
+            return;
+        };
+        let login = std::env::var("TWITCH_NICK")
+            .ok()
+            .zip(std::env::var("TWITCH_OAUTH").ok());
+        app.insert_resource(connect(channel.to_lowercase(), login))
+            .init_resource::<ViewerQueue>()
+            .add_systems(
+                Update,
+                (
+                    receive_chat,
+                    spawn_viewer_ducks,
+                    // The hits and escapes come out of this frame's simulation
+                    announce_viewer_ducks.in_set(crate::FrameSet::Presentation),
+                )
+                    .run_if(
+                        in_state(crate::PauseState::Running)
+                            .and(in_state(crate::RoundPhase::Playing))
+                            .and(resource_equals(crate::SimulationDriver::Local)),
+                    ),
+            );
+    }
+}
+
+fn connect(channel: String, login: Option<(String, String)>) -> TwitchChat {
+    let (request_sender, requests) = crossbeam_channel::unbounded();
+    let (announcement_sender, announcements) = crossbeam_channel::unbounded();
+    let can_post = login.is_some();
+    thread::spawn(move || {
+        if let Err(error) = read_chat(&channel, login, request_sender, announcements) {
+            println!("Could not join Twitch chat: {error}");
+        }
+    });
+    TwitchChat {
+        requests,
+        announcements: can_post.then_some(announcement_sender),
+    }
+}
+
+// Runs until the connection drops, posting is left to a thread of its own
+fn read_chat(
+    channel: &str,
+    login: Option<(String, String)>,
+    request_sender: Sender<ViewerRequest>,
+    announcements: Receiver<String>,
+) -> io::Result<()> {
+    let mut stream = TcpStream::connect(TWITCH_IRC)?;
+    let can_post = login.is_some();
+    let (nick, pass) = login.unwrap_or_else(|| ("justinfan12345".to_string(), String::new()));
+    if can_post {
+        let pass = pass.trim_start_matches("oauth:");
+        write!(stream, "PASS oauth:{pass}\r\n")?;
+    }
+    write!(
+        stream,
+        "NICK {}\r\nJOIN #{channel}\r\n",
+        nick.to_lowercase()
+    )?;
+
+    if can_post {
+        let mut post_stream = stream.try_clone()?;
+        let channel = channel.to_string();
+        thread::spawn(move || {
+            for message in announcements {
+                if write!(post_stream, "PRIVMSG #{channel} :{message}\r\n").is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    let mut pong_stream = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if let Some(server) = line.strip_prefix("PING ") {
+            if write!(pong_stream, "PONG {server}\r\n").is_err() {
+                break;
+            }
+            continue;
+        }
+        if let Some(request) = parse_request(&line) {
+            if request_sender.send(request).is_err() {
+                break;
+            }
+        }
+    }
+    println!("Twitch chat disconnected");
+    Ok(())
+}
+
+// Chat lines look like `:name!name@name.tmi.twitch.tv PRIVMSG #channel :text`
+fn parse_request(line: &str) -> Option<ViewerRequest> {
+    let (prefix, rest) = line.strip_prefix(':')?.split_once(' ')?;
+    let (_, text) = rest.strip_prefix("PRIVMSG ")?.split_once(" :")?;
+    let viewer = prefix.split('!').next()?.to_string();
+    let kind = match text.split_whitespace().next()? {
+        "!duck" => ViewerDuckKind::Regular,
+        "!golden" => ViewerDuckKind::Golden,
+        _ => return None,
+    };
+    Some(ViewerRequest { viewer, kind })
+}
+
+fn receive_chat(chat: Res<TwitchChat>, mut viewer_queue: ResMut<ViewerQueue>) {
+    for request in chat.requests.try_iter() {
+        if viewer_queue.queue.len() < MAX_QUEUED {
+            viewer_queue.queue.push_back(request);
+        }
+    }
+}
+
+fn spawn_viewer_ducks(
+    mut commands: Commands,
+    time: Res<Time>,
+    difficulty: Res<Difficulty>,
+    mut rng: ResMut<GameRng>,
+    mut viewer_queue: ResMut<ViewerQueue>,
+) {
+    viewer_queue.timer.tick(time.delta());
+    if !viewer_queue.timer.just_finished() {
+        return;
+    }
+    let Some(request) = viewer_queue.queue.pop_front() else {
+        return;
+    };
+    let roll: f32 = rng.gen_range(-1.0..1.0);
+    let position = Vec2::new(roll * 120.0, GRASS_Y);
+    let (species, speed, points) = match request.kind {
+        ViewerDuckKind::Regular => (Species::Regular, 40.0, Duck::default().points),
+        ViewerDuckKind::Golden => (Species::Golden, 80.0, GOLDEN_POINTS),
+    };
+    commands
+        .spawn_duck(SpawnDuckEvent {
+            species,
+            position,
+            behaviour: Behaviour::TakeOff(Heading::towards_center(position.x)),
+        })
+        .insert((
+            Duck {
+                speed: speed * difficulty.speed,
+                points,
+            },
+            ExtraDuck,
+            ViewerDuck {
+                viewer: request.viewer.clone(),
+                kind: request.kind,
+            },
+        ))
+        .with_child((
+            BitmapText::new(request.viewer),
+            Transform::from_xyz(0.0, 20.0, 1.0),
+        ));
+}
+
+fn announce_viewer_ducks(
+    chat: Res<TwitchChat>,
+    viewer_duck_query: Query<&ViewerDuck>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+    mut duck_escaped_reader: EventReader<DuckEscaped>,
+) {
+    let Some(announcements) = &chat.announcements else {
+        duck_hit_reader.clear();
+        duck_escaped_reader.clear();
+        return;
+    };
+    for hit in duck_hit_reader.read() {
+        if let Ok(viewer_duck) = viewer_duck_query.get(hit.entity) {
+            let kind = match viewer_duck.kind {
+                ViewerDuckKind::Regular => "duck",
+                ViewerDuckKind::Golden => "golden duck",
+            };
+            let _ = announcements.send(format!(
+                "{}'s {kind} got shot down for {} points!",
+                viewer_duck.viewer, hit.points
+            ));
+        }
+    }
+    for escaped in duck_escaped_reader.read() {
+        if let Ok(viewer_duck) = viewer_duck_query.get(escaped.entity) {
+            let _ = announcements.send(format!("{}'s duck got away!", viewer_duck.viewer));
+        }
+    }
+}
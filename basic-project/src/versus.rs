@@ -0,0 +1,223 @@
+//! Two players shooting at the same ducks at once, player one on the mouse
+//! and player two on a gamepad, or the arrow keys without one. Whoever takes
+//! a duck down first gets it.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    aim::{AimMotion, GyroMotion},
+    bitmap_text::BitmapText,
+    events::{DuckHit, PlayerId, ResetGame, RoundEnded},
+    hud::Hud,
+    locale::Locale,
+    mid_frame,
+    settings::Settings,
+    sinden::PlayArea,
+    view::View,
+    weapon::{EquippedWeapon, WeaponModifiers},
+    GameAssets, GameInputEvent, GameState,
+};
+
+// Rounds played before the game ends
+pub const VERSUS_ROUNDS: u32 = 5;
+// Pixels per second at full stick
+const GAMEPAD_RETICLE_SPEED: f32 = 160.0;
+pub const PLAYER_COLORS: [Color; 2] = [Color::srgb(1.0, 0.3, 0.3), Color::srgb(0.3, 0.6, 1.0)];
+
+/// Crosshair of one player. Player two's also carries their own
+/// `EquippedWeapon`, player one uses the resource like in the other modes.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Reticle {
+    pub player: PlayerId,
+}
+
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct VersusScores {
+    pub scores: [u32; 2],
+    pub ducks_hit: [u32; 2],
+}
+
+#[derive(Component)]
+pub struct VersusScoreText;
+
+pub fn setup_versus(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.insert_resource(VersusScores::default());
+    for (i, color) in PLAYER_COLORS.into_iter().enumerate() {
+        let mut reticle = commands.spawn((
+            Sprite {
+                color,
+                ..game_assets.crosshair_sprite()
+            },
+            Transform::from_xyz(i as f32 * 32.0 - 16.0, 0.0, 10.0),
+            Reticle {
+                player: PlayerId(i),
+            },
+            StateScoped(GameState::InGame),
+        ));
+        if i > 0 {
+            reticle.insert((EquippedWeapon::default(), AimMotion::default()));
+        }
+    }
+    commands.spawn((
+        BitmapText::new(""),
+        Transform::from_xyz(0.0, 108.0, 3.0),
+        VersusScoreText,
+        Hud,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+pub fn reset_versus(_trigger: Trigger<ResetGame>, mut commands: Commands) {
+    commands.remove_resource::<VersusScores>();
+}
+
+pub fn move_mouse_reticle(
+    play_area: Res<PlayArea>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut reticle_query: Query<(&Reticle, &mut Transform)>,
+) {
+    let Ok(win) = window_query.single() else {
+        return;
+    };
+    let Some(position) = win.cursor_position() else {
+        return;
+    };
+    let position = play_area.to_world(position);
+    for (reticle, mut transform) in &mut reticle_query {
+        if reticle.player == PlayerId(0) {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+}
+
+/// The gamepad player two aims with, the same one every frame while it stays
+/// connected. Rumble and the gyro go by it too.
+pub fn versus_gamepad<'a>(
+    gamepad_query: &'a Query<(Entity, &Gamepad)>,
+) -> Option<(Entity, &'a Gamepad)> {
+    gamepad_query.iter().min_by_key(|(entity, _)| *entity)
+}
+
+// Left stick moves the reticle, A or the right trigger shoots, X reloads.
+// The arrow keys move it too, with Enter to shoot and Backspace to reload.
+// The gyro, when there is one, nudges it on top of the stick.
+#[allow(clippy::too_many_arguments)]
+pub fn handle_gamepad_reticle(
+    time: Res<Time>,
+    real: Res<Time<Real>>,
+    settings: Res<Settings>,
+    gyro: Res<GyroMotion>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<(Entity, &Gamepad)>,
+    modifiers: Res<WeaponModifiers>,
+    view: Res<View>,
+    mut reticle_query: Query<(&Reticle, &EquippedWeapon, &mut AimMotion, &mut Transform)>,
+    mut game_input_event_writer: EventWriter<GameInputEvent>,
+) {
+    let gamepad = versus_gamepad(&gamepad_query).map(|(_, gamepad)| gamepad);
+    let mut input = gamepad.map_or(Vec2::ZERO, Gamepad::left_stick);
+    for (key, direction) in [
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::ArrowUp, Vec2::Y),
+    ] {
+        if keyboard_input.pressed(key) {
+            input += direction;
+        }
+    }
+    let input = input.clamp_length_max(1.0);
+
+    let buttons = [GamepadButton::South, GamepadButton::RightTrigger2];
+    for (reticle, equipped, mut motion, mut transform) in &mut reticle_query {
+        if reticle.player != PlayerId(1) {
+            continue;
+        }
+        let start = if gyro.recenter {
+            Vec2::new(view.x, 0.0)
+        } else {
+            transform.translation.xy()
+        };
+        let position = start
+            + gyro.delta
+            + motion.step(
+                &settings.aim,
+                input,
+                GAMEPAD_RETICLE_SPEED,
+                time.delta_secs(),
+            );
+        let center = Vec2::new(view.x, 0.0);
+        let position = position.clamp(
+            center + Vec2::new(-128.0, -120.0),
+            center + Vec2::new(128.0, 120.0),
+        );
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+
+        let trigger = if equipped.automatic(&modifiers) {
+            gamepad.is_some_and(|gamepad| gamepad.any_pressed(buttons))
+                || keyboard_input.pressed(KeyCode::Enter)
+        } else {
+            gamepad.is_some_and(|gamepad| gamepad.any_just_pressed(buttons))
+                || keyboard_input.just_pressed(KeyCode::Enter)
+        };
+        if trigger {
+            game_input_event_writer.write(GameInputEvent::Shoot(
+                reticle.player,
+                position,
+                mid_frame(&real),
+            ));
+        }
+        if gamepad.is_some_and(|gamepad| gamepad.just_pressed(GamepadButton::West))
+            || keyboard_input.just_pressed(KeyCode::Backspace)
+        {
+            game_input_event_writer.write(GameInputEvent::Reload(reticle.player));
+        }
+    }
+}
+
+pub fn award_versus_points(
+    mut versus_scores: ResMut<VersusScores>,
+    mut duck_hit_reader: EventReader<DuckHit>,
+) {
+    for hit in duck_hit_reader.read() {
+        let PlayerId(player) = hit.player;
+        versus_scores.scores[player] += hit.points;
+        versus_scores.ducks_hit[player] += 1;
+    }
+}
+
+pub fn update_versus_score_text(
+    locale: Res<Locale>,
+    versus_scores: Res<VersusScores>,
+    mut text_query: Query<&mut BitmapText, With<VersusScoreText>>,
+) {
+    if !versus_scores.is_changed() {
+        return;
+    }
+    for mut text in &mut text_query {
+        BitmapText::set(
+            &mut text,
+            locale.format(
+                "versus.scores",
+                &[
+                    ("p1", &format!("{:>6}", versus_scores.scores[0])),
+                    ("p2", &format!("{:>6}", versus_scores.scores[1])),
+                ],
+            ),
+        );
+    }
+}
+
+pub fn end_versus(
+    mut round_ended_reader: EventReader<RoundEnded>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if round_ended_reader
+        .read()
+        .any(|ended| ended.round >= VERSUS_ROUNDS)
+    {
+        next_state.set(GameState::GameOver);
+    }
+}
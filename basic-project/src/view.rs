@@ -0,0 +1,145 @@
+//! Where the camera is looking. On the usual field that's always the middle,
+//! on a level with a panorama, like the marsh, it pans slowly back and forth
+//! across it. Ducks come in, turn around and get carried back by the dog
+//! relative to the view, so the edges the game used to keep at ±120 are
+//! [`View::min_x`] and [`View::max_x`] now.
+//!
+//! The pan is worked out from the game clock the same as the wind, so it's
+//! the same on every replay and every peer. Anything that stays put on the
+//! screen while it pans, the HUD and the weather, has [`ScreenFixed`] and
+//! rides along on the camera.
+
+use bevy::prelude::*;
+
+use crate::{
+    events::ResetGame, level::Level, mode::GameMode, photo::PhotoMode, sinden::PlayArea,
+    time_scale::GameTimeScale, GameAssets, Simulation,
+};
+
+const SCREEN_WIDTH: f32 = 256.0;
+
+/// Middle of what's on screen, in world x
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct View {
+    pub x: f32,
+}
+
+impl View {
+    /// How far either side of the middle ducks turn around, a little short of
+    /// the screen's edge
+    pub const HALF_WIDTH: f32 = 120.0;
+
+    pub fn min_x(&self) -> f32 {
+        self.x - Self::HALF_WIDTH
+    }
+
+    pub fn max_x(&self) -> f32 {
+        self.x + Self::HALF_WIDTH
+    }
+
+    /// World x for `x` from the middle of the screen
+    pub fn to_world(&self, x: f32) -> f32 {
+        self.x + x
+    }
+
+    /// Where it is `seconds` into a game across a panorama `width` wide.
+    /// Starts in the middle heading right and turns round at either end.
+    pub fn panning(seconds: f32, speed: f32, width: f32) -> Self {
+        let reach = (width - SCREEN_WIDTH).max(0.0) / 2.0;
+        if reach == 0.0 {
+            return Self::default();
+        }
+        // Goes out, all the way back and out again, four reaches a lap
+        let lap = (seconds * speed / reach).rem_euclid(4.0);
+        let along = if lap < 1.0 {
+            lap
+        } else if lap < 3.0 {
+            2.0 - lap
+        } else {
+            lap - 4.0
+        };
+        Self {
+            x: (along * reach).round(),
+        }
+    }
+}
+
+/// Stays where it is on the screen when the view pans, its transform is
+/// from the middle of the screen. Only needed on entities without a parent.
+#[derive(Component, Default)]
+pub struct ScreenFixed;
+
+pub struct ViewPlugin;
+
+impl Plugin for ViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<View>()
+            .add_observer(reset_view)
+            .add_observer(fix_to_screen)
+            .add_systems(Simulation, pan_view.before(crate::move_ducks))
+            // Photo mode moves the camera itself
+            .add_systems(PostUpdate, follow_view.run_if(not(in_state(PhotoMode::On))));
+    }
+}
+
+fn reset_view(_trigger: Trigger<ResetGame>, mut view: ResMut<View>) {
+    *view = View::default();
+}
+
+fn pan_view(
+    time_scale: Res<GameTimeScale>,
+    mode: Res<GameMode>,
+    game_assets: Res<GameAssets>,
+    levels: Res<Assets<Level>>,
+    images: Res<Assets<Image>>,
+    mut view: ResMut<View>,
+) {
+    let panned = levels
+        .get(game_assets.level(*mode))
+        .and_then(|level| level.panorama.as_ref())
+        .and_then(|panorama| {
+            let width = images.get(&panorama.handle)?.width() as f32;
+            Some(View::panning(
+                time_scale.elapsed_secs(),
+                panorama.speed,
+                width,
+            ))
+        })
+        .unwrap_or_default();
+    if *view != panned {
+        *view = panned;
+    }
+}
+
+// On the camera it moves with it, where it was in the world is where it is
+// from the middle of the screen
+fn fix_to_screen(
+    trigger: Trigger<OnAdd, ScreenFixed>,
+    mut commands: Commands,
+    parent_query: Query<(), With<ChildOf>>,
+    camera_query: Query<Entity, (With<Camera2d>, Without<crate::screenshot::NativeCamera>)>,
+) {
+    if parent_query.contains(trigger.target()) {
+        return;
+    }
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    commands.entity(camera).add_child(trigger.target());
+}
+
+fn follow_view(
+    view: Res<View>,
+    mut play_area: ResMut<PlayArea>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    if play_area.view_x != view.x {
+        play_area.view_x = view.x;
+    }
+    if !view.is_changed() {
+        return;
+    }
+    for mut transform in &mut camera_query {
+        transform.translation.x = view.x;
+    }
+}
@@ -0,0 +1,460 @@
+//! Weapons turn one trigger pull into one or more pellets, each of which is
+//! hit tested on its own by `handle_shoot_duck`. Their stats come from
+//! `assets/data/default.weapons.ron`, which is also built into the game for
+//! the weapon in hand before the asset has loaded and for a pack's table
+//! that leaves one out.
+
+use std::{sync::LazyLock, time::Duration};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    input::mouse::MouseWheel,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    mode::GameMode, time_scale::GameTimeScale, versus::Reticle, GameAssets, GameInputEvent,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WeaponKind {
+    /// The Zapper, one precise shot
+    #[default]
+    Rifle,
+    /// Spread of pellets that get weaker towards the edge
+    Shotgun,
+    /// Hold to fire, limited by a cooldown
+    Auto,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    pub pellets: u32,
+    /// Radius in pixels the outer pellets land at
+    pub spread: f32,
+    /// How much power a pellet at the full spread radius loses, 0 to 1
+    pub falloff: f32,
+    pub max_ammo: u32,
+    /// Seconds between shots
+    pub cooldown: f32,
+    /// Seconds it takes to put fresh shells in
+    pub reload_time: f32,
+    /// Keeps firing while the button is held
+    pub automatic: bool,
+    /// A pellet hits every duck under it instead of only the one on top
+    #[serde(default)]
+    pub pierce: bool,
+    /// Frame in the weapon icon spritesheet
+    pub icon: usize,
+}
+
+impl Weapon {
+    /// The stats `default.weapons.ron` ships with
+    pub fn new(kind: WeaponKind) -> Self {
+        DEFAULT_WEAPONS
+            .find(kind)
+            .expect("default.weapons.ron is missing a weapon")
+    }
+
+    /// Offset from the aim point and power of every pellet of one shot. The
+    /// first pellet always goes dead centre, the rest are spread evenly on
+    /// a ring so the pattern is the same every time.
+    pub fn pellets(&self) -> impl Iterator<Item = Pellet> + '_ {
+        let ring = self.pellets.saturating_sub(1).max(1) as f32;
+        (0..self.pellets).map(move |i| {
+            if i == 0 {
+                return Pellet {
+                    offset: Vec2::ZERO,
+                    power: 1.0,
+                };
+            }
+            let angle = (i - 1) as f32 / ring * std::f32::consts::TAU;
+            Pellet {
+                offset: Vec2::from_angle(angle) * self.spread,
+                power: 1.0 - self.falloff,
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pellet {
+    pub offset: Vec2,
+    /// A duck dies once the pellets hitting it add up to 1
+    pub power: f32,
+}
+
+#[derive(Resource, Component, Clone, Serialize, Deserialize)]
+pub struct EquippedWeapon {
+    pub weapon: Weapon,
+    pub ammo: u32,
+    cooldown: Timer,
+    // Running while fresh shells go in, it can't fire until they're in
+    reload: Option<Timer>,
+    // The rest of the loadout keeps its shells and reload while it's put
+    // away, a weapon that isn't here comes out full
+    #[serde(default)]
+    holstered: Vec<Holstered>,
+}
+
+/// What a weapon was left with when it was switched away from
+#[derive(Clone, Serialize, Deserialize)]
+struct Holstered {
+    kind: WeaponKind,
+    ammo: u32,
+    reload: Option<Timer>,
+}
+
+impl EquippedWeapon {
+    pub fn new(kind: WeaponKind) -> Self {
+        Self::from_weapon(Weapon::new(kind))
+    }
+
+    pub fn from_weapon(weapon: Weapon) -> Self {
+        let mut cooldown = Timer::from_seconds(weapon.cooldown, TimerMode::Once);
+        // Ready to fire straight away
+        let duration = cooldown.duration();
+        cooldown.tick(duration);
+        Self {
+            ammo: weapon.max_ammo,
+            weapon,
+            cooldown,
+            reload: None,
+            holstered: Vec::new(),
+        }
+    }
+
+    /// Puts this weapon away and takes out `weapon` with the shells it was
+    /// left with. A reload carries on from where it was.
+    pub fn switch_to(&mut self, weapon: Weapon) {
+        let kind = self.weapon.kind;
+        self.holstered.retain(|holstered| holstered.kind != kind);
+        self.holstered.push(Holstered {
+            kind,
+            ammo: self.ammo,
+            reload: self.reload.take(),
+        });
+        let taken_out = self
+            .holstered
+            .iter()
+            .position(|holstered| holstered.kind == weapon.kind)
+            .map(|index| self.holstered.swap_remove(index));
+        self.ammo = taken_out
+            .as_ref()
+            .map_or(weapon.max_ammo, |holstered| holstered.ammo);
+        self.reload = taken_out.and_then(|holstered| holstered.reload);
+        // Swapping takes as long as a shot, no free fire rate from switching
+        self.cooldown = Timer::from_seconds(weapon.cooldown, TimerMode::Once);
+        self.weapon = weapon;
+    }
+
+    /// Uses up a shell if the weapon is ready, returns whether it fired
+    pub fn try_fire(&mut self, modifiers: &WeaponModifiers) -> bool {
+        if !self.cooldown.finished() || self.reloading() {
+            return false;
+        }
+        if !modifiers.rapid_fire {
+            if self.ammo == 0 {
+                return false;
+            }
+            self.ammo -= 1;
+        }
+        self.cooldown.reset();
+        true
+    }
+
+    pub fn automatic(&self, modifiers: &WeaponModifiers) -> bool {
+        self.weapon.automatic || modifiers.rapid_fire
+    }
+
+    /// Fills it up, the weapons that are put away as well
+    pub fn refill(&mut self) {
+        self.ammo = self.weapon.max_ammo;
+        self.reload = None;
+        self.holstered.clear();
+    }
+
+    /// Starts putting fresh shells in, takes the weapon's `reload_time`.
+    /// Does nothing if it's full or already reloading.
+    pub fn start_reload(&mut self) {
+        if self.ammo < self.weapon.max_ammo && !self.reloading() {
+            self.reload = Some(Timer::from_seconds(
+                self.weapon.reload_time,
+                TimerMode::Once,
+            ));
+        }
+    }
+
+    pub fn reloading(&self) -> bool {
+        self.reload.is_some()
+    }
+
+    /// How far along the reload is, 0 to 1, none if it isn't reloading
+    pub fn reload_progress(&self) -> Option<f32> {
+        self.reload.as_ref().map(Timer::fraction)
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        self.cooldown.tick(delta);
+        if let Some(reload) = &mut self.reload {
+            // Only this one, the ones put away keep what they were left with
+            if reload.tick(delta).finished() {
+                self.ammo = self.weapon.max_ammo;
+                self.reload = None;
+            }
+        }
+    }
+}
+
+impl Default for EquippedWeapon {
+    fn default() -> Self {
+        Self::new(WeaponKind::default())
+    }
+}
+
+/// Temporary changes to whatever weapon is equipped, power-ups flip these
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct WeaponModifiers {
+    /// Hold to fire at triple speed without using up shells
+    pub rapid_fire: bool,
+    /// Adds a full power ring of pellets around every shot
+    pub wide_shot: bool,
+}
+
+impl WeaponModifiers {
+    pub fn pellets<'a>(&self, weapon: &'a Weapon) -> impl Iterator<Item = Pellet> + 'a {
+        let extra = if self.wide_shot { 6 } else { 0 };
+        weapon.pellets().chain((0..extra).map(move |i| Pellet {
+            offset: Vec2::from_angle(i as f32 / extra as f32 * std::f32::consts::TAU) * 16.0,
+            power: 1.0,
+        }))
+    }
+}
+
+pub fn tick_weapon_cooldown(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    modifiers: Res<WeaponModifiers>,
+    mut equipped: ResMut<EquippedWeapon>,
+    mut player_weapon_query: Query<&mut EquippedWeapon>,
+) {
+    let delta = time_scale.delta(&time);
+    let delta = if modifiers.rapid_fire {
+        delta * 3
+    } else {
+        delta
+    };
+    equipped.tick(delta);
+    for mut equipped in &mut player_weapon_query {
+        equipped.tick(delta);
+    }
+}
+
+// Shells only come back when the player reloads, players with a weapon of
+// their own reload that one
+pub fn handle_reload(
+    mut equipped: ResMut<EquippedWeapon>,
+    mut player_weapon_query: Query<(&Reticle, &mut EquippedWeapon)>,
+    mut game_input_event_reader: EventReader<GameInputEvent>,
+) {
+    for event in game_input_event_reader.read() {
+        let GameInputEvent::Reload(player) = *event else {
+            continue;
+        };
+        match player_weapon_query
+            .iter_mut()
+            .find(|(reticle, _)| reticle.player == player)
+        {
+            Some((_, mut weapon)) => weapon.start_reload(),
+            None => equipped.start_reload(),
+        }
+    }
+}
+
+static DEFAULT_WEAPONS: LazyLock<WeaponTable> = LazyLock::new(|| {
+    ron::from_str(include_str!("../assets/data/default.weapons.ron"))
+        .expect("default.weapons.ron doesn't parse")
+});
+
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct WeaponTable {
+    pub weapons: Vec<Weapon>,
+}
+
+impl WeaponTable {
+    fn find(&self, kind: WeaponKind) -> Option<Weapon> {
+        self.weapons
+            .iter()
+            .find(|weapon| weapon.kind == kind)
+            .cloned()
+    }
+
+    /// The loaded stats for `kind`, or the built in ones if the table
+    /// doesn't have it
+    pub fn weapon(&self, kind: WeaponKind) -> Weapon {
+        self.find(kind).unwrap_or_else(|| Weapon::new(kind))
+    }
+}
+
+#[derive(Default)]
+pub struct WeaponTableLoader;
+
+#[derive(Debug, Error)]
+pub enum WeaponTableLoaderError {
+    #[error("could not read weapon table: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse weapon table: {0}")]
+    Ron(#[from] ron::de::SpannedError),
+}
+
+impl AssetLoader for WeaponTableLoader {
+    type Asset = WeaponTable;
+    type Settings = ();
+    type Error = WeaponTableLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["weapons.ron"]
+    }
+}
+
+/// Weapons the player can cycle through, each mode gets its own from
+/// `WeaponLoadout::for_mode`
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct WeaponLoadout(pub Vec<WeaponKind>);
+
+impl WeaponLoadout {
+    /// The NES rules only had the Zapper, and so do the modes played for a
+    /// score to compare against someone else's
+    pub fn for_mode(mode: GameMode) -> Self {
+        match mode {
+            GameMode::Classic | GameMode::Daily | GameMode::Hotseat | GameMode::Marsh => {
+                Self(vec![WeaponKind::Rifle])
+            }
+            GameMode::Endless
+            | GameMode::TimeAttack
+            | GameMode::Versus
+            | GameMode::BeTheDuck
+            | GameMode::Training => Self::default(),
+        }
+    }
+}
+
+impl Default for WeaponLoadout {
+    fn default() -> Self {
+        Self(vec![
+            WeaponKind::Rifle,
+            WeaponKind::Shotgun,
+            WeaponKind::Auto,
+        ])
+    }
+}
+
+/// Hands out the mode's loadout as a game starts, taking out its first
+/// weapon if the one in hand isn't in it
+pub fn equip_mode_loadout(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    game_assets: Option<Res<GameAssets>>,
+    weapon_tables: Res<Assets<WeaponTable>>,
+    mut equipped: ResMut<EquippedWeapon>,
+) {
+    let loadout = WeaponLoadout::for_mode(*mode);
+    if let Some(&kind) = loadout
+        .0
+        .first()
+        .filter(|_| !loadout.0.contains(&equipped.weapon.kind))
+    {
+        let weapon = game_assets
+            .and_then(|game_assets| weapon_tables.get(&game_assets.weapon_table))
+            .map_or_else(|| Weapon::new(kind), |table| table.weapon(kind));
+        *equipped = EquippedWeapon::from_weapon(weapon);
+    }
+    commands.insert_resource(loadout);
+}
+
+// Scroll wheel cycles, number keys pick directly
+pub fn switch_weapon(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut mouse_wheel_reader: EventReader<MouseWheel>,
+    loadout: Res<WeaponLoadout>,
+    game_assets: Res<GameAssets>,
+    weapon_tables: Res<Assets<WeaponTable>>,
+    mut equipped: ResMut<EquippedWeapon>,
+) {
+    let scroll: f32 = mouse_wheel_reader.read().map(|event| event.y).sum();
+    if loadout.0.len() < 2 {
+        return;
+    }
+    let current = loadout
+        .0
+        .iter()
+        .position(|kind| *kind == equipped.weapon.kind)
+        .unwrap_or(0);
+    let digits = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    let next = if let Some(index) = digits
+        .iter()
+        .take(loadout.0.len())
+        .position(|key| keyboard_input.just_pressed(*key))
+    {
+        index
+    } else if scroll > 0.0 {
+        (current + 1) % loadout.0.len()
+    } else if scroll < 0.0 {
+        (current + loadout.0.len() - 1) % loadout.0.len()
+    } else {
+        return;
+    };
+    if next == current {
+        return;
+    }
+    let kind = loadout.0[next];
+    let weapon = weapon_tables
+        .get(&game_assets.weapon_table)
+        .map_or_else(|| Weapon::new(kind), |table| table.weapon(kind));
+    equipped.switch_to(weapon);
+}
+
+// Pick up edits to the weapon table, also the first time it finishes loading
+pub fn apply_weapon_table(
+    mut weapon_table_events: EventReader<AssetEvent<WeaponTable>>,
+    game_assets: Res<GameAssets>,
+    weapon_tables: Res<Assets<WeaponTable>>,
+    mut equipped: ResMut<EquippedWeapon>,
+) {
+    for event in weapon_table_events.read() {
+        if !event.is_loaded_with_dependencies(&game_assets.weapon_table)
+            && !event.is_modified(&game_assets.weapon_table)
+        {
+            continue;
+        }
+        if let Some(table) = weapon_tables.get(&game_assets.weapon_table) {
+            let weapon = table.weapon(equipped.weapon.kind);
+            equipped.ammo = equipped.ammo.min(weapon.max_ammo);
+            equipped.weapon = weapon;
+        }
+    }
+}
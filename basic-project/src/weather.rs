@@ -0,0 +1,239 @@
+//! Rain and fog in the later rounds, laid over the field. How much of each
+//! and from when is up to the level file's `weather` stages, see
+//! `level::WeatherStage`, and both can be turned off on the accessibility
+//! page. The rain has a sound to go with it, quiet while the game is paused
+//! or in the background.
+//!
+//! Both stay put on the screen when the view pans. Only what the player
+//! sees changes. The fog sits in front of the top of the field, where ducks
+//! go before they get away, but a shot through it lands the same as
+//! anywhere else.
+
+use bevy::{audio::Volume, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    level::Level, mode::GameMode, settings::Settings, time_scale::GameTimeScale, view::ScreenFixed,
+    wind::Wind, Difficulty, GameAssets, GameState, PauseState, Round,
+};
+
+// Weather changes over this many seconds instead of all at once
+const FADE_TIME: f32 = 3.0;
+const MAX_DROPS: usize = 120;
+const DROP_SPEED: f32 = 240.0;
+const DROP_SIZE: Vec2 = Vec2::new(1.0, 4.0);
+const DROP_COLOR: Color = Color::srgba(0.75, 0.85, 1.0, 0.6);
+const MAX_RAIN_VOLUME: f32 = 0.6;
+const FOG_BANDS: usize = 12;
+// Down from the top of the field, the fog thins out to nothing by here
+const FOG_DEPTH: f32 = 96.0;
+const FOG_COLOR: Color = Color::srgb(0.85, 0.88, 0.9);
+// How much of the sky is hidden at the very top in the thickest fog
+const MAX_FOG_ALPHA: f32 = 0.85;
+const FIELD_SIZE: Vec2 = Vec2::new(256.0, 240.0);
+// Over the ducks, the grass and the feathers, under the HUD
+const FOG_Z: f32 = 1.8;
+const RAIN_Z: f32 = 1.9;
+
+/// How hard it's raining and how thick the fog is right now, from 0 to 1
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Weather {
+    pub rain: f32,
+    pub fog: f32,
+}
+
+#[derive(Component)]
+struct RainDrop(usize);
+
+#[derive(Component)]
+struct FogBand(usize);
+
+#[derive(Component)]
+struct RainSound;
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weather>()
+            .add_systems(OnEnter(GameState::InGame), (clear_weather, spawn_fog))
+            .add_systems(
+                Update,
+                (
+                    change_weather,
+                    (thicken_fog, fill_rain, play_rain_sound),
+                    hush_rain_sound,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                fall_rain
+                    .after(fill_rain)
+                    .run_if(in_state(GameState::InGame).and(in_state(PauseState::Running))),
+            );
+    }
+}
+
+// A new game starts out clear and works its way up
+fn clear_weather(mut weather: ResMut<Weather>) {
+    *weather = Weather::default();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn change_weather(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    round: Res<Round>,
+    difficulty: Res<Difficulty>,
+    mode: Res<GameMode>,
+    game_assets: Res<GameAssets>,
+    levels: Res<Assets<Level>>,
+    mut weather: ResMut<Weather>,
+) {
+    let stage = levels
+        .get(game_assets.level(*mode))
+        .and_then(|level| level.weather(round.number, difficulty.speed))
+        .filter(|_| settings.accessibility.weather);
+    let target = stage.map_or(Weather::default(), |stage| Weather {
+        rain: stage.rain.clamp(0.0, 1.0),
+        fog: stage.fog.clamp(0.0, 1.0),
+    });
+    if *weather == target {
+        return;
+    }
+    let step = time.delta_secs() / FADE_TIME;
+    let toward = |from: f32, to: f32| from + (to - from).clamp(-step, step);
+    *weather = Weather {
+        rain: toward(weather.rain, target.rain),
+        fog: toward(weather.fog, target.fog),
+    };
+}
+
+// Stacked strips thinning out downwards, each a little more see-through
+// than the one above it
+fn spawn_fog(mut commands: Commands) {
+    let height = FOG_DEPTH / FOG_BANDS as f32;
+    for i in 0..FOG_BANDS {
+        let y = FIELD_SIZE.y / 2.0 - height * (i as f32 + 0.5);
+        commands.spawn((
+            Sprite::from_color(FOG_COLOR.with_alpha(0.0), Vec2::new(FIELD_SIZE.x, height)),
+            Transform::from_xyz(0.0, y, FOG_Z),
+            FogBand(i),
+            ScreenFixed,
+            StateScoped(GameState::InGame),
+        ));
+    }
+}
+
+fn thicken_fog(weather: Res<Weather>, mut band_query: Query<(&FogBand, &mut Sprite)>) {
+    if !weather.is_changed() {
+        return;
+    }
+    for (band, mut sprite) in &mut band_query {
+        let thinning = 1.0 - band.0 as f32 / FOG_BANDS as f32;
+        sprite
+            .color
+            .set_alpha(weather.fog * thinning * MAX_FOG_ALPHA);
+    }
+}
+
+// Scattered over the field by their number rather than rolled, nothing
+// here changes how the game plays so it doesn't need the rng
+fn drop_start(i: usize) -> Vec2 {
+    let scatter = |step: f32| (i as f32 * step).fract() - 0.5;
+    Vec2::new(scatter(0.618_034), scatter(0.414_214)) * FIELD_SIZE
+}
+
+fn fill_rain(
+    mut commands: Commands,
+    weather: Res<Weather>,
+    drop_query: Query<(Entity, &RainDrop)>,
+) {
+    let wanted = (weather.rain * MAX_DROPS as f32).round() as usize;
+    let mut have = 0;
+    for (entity, drop) in &drop_query {
+        if drop.0 >= wanted {
+            commands.entity(entity).despawn();
+        } else {
+            have += 1;
+        }
+    }
+    for i in have..wanted {
+        commands.spawn((
+            Sprite::from_color(DROP_COLOR, DROP_SIZE),
+            Transform::from_translation(drop_start(i).extend(RAIN_Z)),
+            RainDrop(i),
+            ScreenFixed,
+            StateScoped(GameState::InGame),
+        ));
+    }
+}
+
+// Slanted by the wind, and back in at the top once they reach the bottom
+fn fall_rain(
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    wind: Res<Wind>,
+    mut drop_query: Query<&mut Transform, With<RainDrop>>,
+) {
+    let velocity = Vec2::new(wind.0, -DROP_SPEED);
+    let delta = time_scale.delta_secs(&time);
+    let half = FIELD_SIZE / 2.0;
+    for mut transform in &mut drop_query {
+        let mut position = transform.translation.truncate() + velocity * delta;
+        if position.y < -half.y {
+            position.y += FIELD_SIZE.y;
+        }
+        if position.x.abs() > half.x {
+            position.x -= FIELD_SIZE.x * position.x.signum();
+        }
+        transform.translation = position.extend(RAIN_Z);
+        transform.rotation = Quat::from_rotation_z(velocity.x.atan2(-velocity.y));
+    }
+}
+
+fn play_rain_sound(
+    mut commands: Commands,
+    weather: Res<Weather>,
+    game_assets: Res<GameAssets>,
+    mut sound_query: Query<(Entity, Option<&mut AudioSink>), With<RainSound>>,
+) {
+    if !weather.is_changed() {
+        return;
+    }
+    let volume = Volume::Linear(weather.rain * MAX_RAIN_VOLUME);
+    match sound_query.single_mut() {
+        Ok((entity, _)) if weather.rain <= 0.0 => commands.entity(entity).despawn(),
+        // The sink turns up a frame or so after the player is spawned
+        Ok((_, Some(mut sink))) => sink.set_volume(volume),
+        Ok((_, None)) => {}
+        Err(_) if weather.rain > 0.0 => {
+            commands.spawn((
+                AudioPlayer::new(game_assets.rain_sound.clone()),
+                PlaybackSettings::LOOP.with_volume(volume),
+                RainSound,
+                StateScoped(GameState::InGame),
+            ));
+        }
+        Err(_) => {}
+    }
+}
+
+// The loop keeps going on its own, so it's paused along with the game and
+// while the window is in the background
+fn hush_rain_sound(
+    pause_state: Res<State<PauseState>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    sink_query: Query<&AudioSink, With<RainSound>>,
+) {
+    let focused = window_query.single().map_or(true, |window| window.focused);
+    let hushed = !focused || *pause_state.get() == PauseState::Paused;
+    for sink in &sink_query {
+        if hushed && !sink.is_paused() {
+            sink.pause();
+        } else if !hushed && sink.is_paused() {
+            sink.play();
+        }
+    }
+}
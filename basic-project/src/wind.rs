@@ -0,0 +1,137 @@
+//! Wind that picks up as the rounds go on. It pushes the ducks sideways,
+//! carries falling ducks and feathers along, and sways the grass so the
+//! player can see which way it's blowing.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::{
+    events::DuckHit, time_scale::GameTimeScale, GameAssets, GameState, PauseState, Round,
+    Simulation,
+};
+
+// Pixels per second of wind added each round after the first
+const WIND_PER_ROUND: f32 = 4.0;
+const MAX_WIND: f32 = 40.0;
+// How far the grass leans at full strength, in pixels
+const GRASS_SWAY: f32 = 2.0;
+const FEATHERS: usize = 4;
+const FEATHER_LIFETIME: f32 = 1.5;
+// Feathers pick up the wind speed quickly, this is the fraction per second
+const FEATHER_DRAG: f32 = 3.0;
+
+/// Horizontal wind in pixels per second, positive blows to the right
+#[derive(Resource, Default, Clone, Copy, Deref)]
+pub struct Wind(pub f32);
+
+impl Wind {
+    /// Strongest the wind gets in this round
+    pub fn max_for_round(round: u32) -> f32 {
+        (round.saturating_sub(1) as f32 * WIND_PER_ROUND).min(MAX_WIND)
+    }
+}
+
+#[derive(Component)]
+struct Feather {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
+#[derive(Component)]
+struct SwayingGrass;
+
+pub struct WindPlugin;
+
+impl Plugin for WindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Wind>()
+            .add_systems(OnEnter(GameState::InGame), spawn_swaying_grass)
+            .add_systems(Simulation, blow_wind.before(crate::move_ducks))
+            .add_systems(
+                Update,
+                (drop_feathers, blow_feathers, sway_grass).run_if(in_state(PauseState::Running)),
+            );
+    }
+}
+
+// Worked out from the game clock instead of rolled, so the wind is the same
+// on every replay and every peer without touching the rngs
+fn blow_wind(time_scale: Res<GameTimeScale>, round: Res<Round>, mut wind: ResMut<Wind>) {
+    let t = time_scale.elapsed_secs();
+    // Two slow waves drifting in and out of phase read as gusts
+    let gust = (t * 0.21).sin() * 0.7 + (t * 0.57 + round.number as f32).sin() * 0.3;
+    wind.0 = gust * Wind::max_for_round(round.number);
+}
+
+// The grass along the front of the field, on top of the background so only
+// its blades move
+fn spawn_swaying_grass(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.spawn((
+        Sprite {
+            image: game_assets.background_spritesheet.clone(),
+            rect: Some(game_assets.background_rect(Rect::new(0.0, 140.0, 256.0, 180.0))),
+            ..default()
+        },
+        Transform::from_xyz(0.0, -40.0, 1.5),
+        SwayingGrass,
+        StateScoped(GameState::InGame),
+    ));
+}
+
+fn sway_grass(
+    time_scale: Res<GameTimeScale>,
+    wind: Res<Wind>,
+    mut grass_query: Query<&mut Transform, With<SwayingGrass>>,
+) {
+    let lean = (wind.0 / MAX_WIND) * GRASS_SWAY;
+    // Flutters a little around the lean, more the harder it blows
+    let flutter = (time_scale.elapsed_secs() * 5.0).sin() * lean.abs() * 0.25;
+    for mut transform in &mut grass_query {
+        transform.translation.x = (lean + flutter).round();
+    }
+}
+
+fn drop_feathers(mut commands: Commands, mut duck_hit_reader: EventReader<DuckHit>) {
+    for hit in duck_hit_reader.read() {
+        // Fanned out evenly, nothing here changes how the game plays so it
+        // doesn't need the rng
+        for i in 0..FEATHERS {
+            let angle = TAU * (i as f32 + 0.5) / FEATHERS as f32;
+            commands.spawn((
+                Sprite::from_color(Color::WHITE, Vec2::splat(2.0)),
+                Transform::from_translation(hit.position.extend(2.0)),
+                Feather {
+                    velocity: Vec2::from_angle(angle) * 30.0,
+                    lifetime: Timer::from_seconds(FEATHER_LIFETIME, TimerMode::Once),
+                },
+                StateScoped(GameState::InGame),
+            ));
+        }
+    }
+}
+
+fn blow_feathers(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<GameTimeScale>,
+    wind: Res<Wind>,
+    mut feather_query: Query<(Entity, &mut Transform, &mut Sprite, &mut Feather)>,
+) {
+    let delta = time_scale.delta_secs(&time);
+    for (entity, mut transform, mut sprite, mut feather) in &mut feather_query {
+        feather.lifetime.tick(time_scale.delta(&time));
+        if feather.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Settles into a slow drift down with the wind
+        let target = Vec2::new(wind.0, -15.0);
+        let velocity = feather.velocity;
+        feather.velocity += (target - velocity) * (FEATHER_DRAG * delta).min(1.0);
+        transform.translation += (feather.velocity * delta).extend(0.0);
+        sprite
+            .color
+            .set_alpha(feather.lifetime.fraction_remaining());
+    }
+}
@@ -0,0 +1,43 @@
+//! Messages exchanged between the host of an online game and the player
+//! joining it. The host runs the simulation, the joining player only sends
+//! their crosshair and shots and draws whatever snapshot comes back.
+
+use serde::{Deserialize, Serialize};
+
+/// Bump when the messages change so old builds can't connect
+pub const PROTOCOL_ID: u64 = 1;
+pub const DEFAULT_PORT: u16 = 5000;
+
+/// World space position, x right and y up from the middle of the screen
+pub type Position = [f32; 2];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    /// Where the crosshair is, sent every frame
+    Cursor(Position),
+    Shoot(Position),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    Snapshot(Snapshot),
+    GameOver,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub ducks: Vec<DuckState>,
+    /// Crosshair of each player, host first
+    pub cursors: [Position; 2],
+    pub scores: [u32; 2],
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DuckState {
+    /// Stays the same for as long as the duck is around
+    pub id: u64,
+    pub position: Position,
+    pub scale: f32,
+    pub frame: usize,
+    pub flip_x: bool,
+}